@@ -8,6 +8,7 @@
 pub enum BuiltinFn {
     CProverAssert,
     CProverAssume,
+    CProverHavocObject,
     Calloc,
     Ceil,
     Ceilf,
@@ -65,6 +66,7 @@ fn to_string(&self) -> String {
         match self {
             CProverAssert => "__CPROVER_assert",
             CProverAssume => "__CPROVER_assume",
+            CProverHavocObject => "__CPROVER_havoc_object",
             Calloc => "calloc",
             Ceil => "ceil",
             Ceilf => "ceilf",
@@ -126,6 +128,7 @@ pub fn param_types(&self) -> Vec<Type> {
         match self {
             CProverAssert => vec![Type::bool(), Type::c_char().to_pointer()],
             CProverAssume => vec![Type::bool()],
+            CProverHavocObject => vec![Type::void_pointer()],
             Calloc => vec![Type::size_t(), Type::size_t()],
             Ceil => vec![Type::double()],
             Ceilf => vec![Type::float()],
@@ -180,6 +183,7 @@ pub fn return_type(&self) -> Type {
         match self {
             CProverAssert => Type::empty(),
             CProverAssume => Type::empty(),
+            CProverHavocObject => Type::empty(),
             Calloc => Type::void_pointer(),
             Ceil => Type::double(),
             Ceilf => Type::float(),
@@ -237,6 +241,7 @@ pub fn list_all() -> Vec<BuiltinFn> {
         vec![
             CProverAssert,
             CProverAssume,
+            CProverHavocObject,
             Calloc,
             Ceil,
             Ceilf,
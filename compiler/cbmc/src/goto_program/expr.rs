@@ -80,8 +80,18 @@ pub enum ExprValue {
     Dereference(Expr),
     /// `1.0`
     DoubleConstant(f64),
+    /// `exists variable . domain`
+    Exists {
+        variable: Expr, // SymbolExpr
+        domain: Expr,
+    },
     /// `1.0f`
     FloatConstant(f32),
+    /// `forall variable . domain`
+    Forall {
+        variable: Expr, // SymbolExpr
+        domain: Expr,
+    },
     /// `function(arguments)`
     FunctionCall {
         function: Expr,
@@ -486,6 +496,28 @@ pub fn double_constant_from_bitpattern(bp: u64) -> Self {
         Self::double_constant(c)
     }
 
+    /// `exists variable . domain`
+    pub fn exists_expr(variable: Expr, domain: Expr) -> Self {
+        assert!(
+            variable.is_symbol(),
+            "Expected a symbol as the bound variable, got {:?}",
+            variable
+        );
+        assert!(domain.typ().is_bool(), "Expected a boolean domain, got {:?}", domain);
+        expr!(Exists { variable, domain }, Type::bool())
+    }
+
+    /// `forall variable . domain`
+    pub fn forall_expr(variable: Expr, domain: Expr) -> Self {
+        assert!(
+            variable.is_symbol(),
+            "Expected a symbol as the bound variable, got {:?}",
+            variable
+        );
+        assert!(domain.typ().is_bool(), "Expected a boolean domain, got {:?}", domain);
+        expr!(Forall { variable, domain }, Type::bool())
+    }
+
     /// `1.0f`
     pub fn float_constant(c: f32) -> Self {
         expr!(FloatConstant(c), Type::float())
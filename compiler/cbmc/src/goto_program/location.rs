@@ -32,6 +32,23 @@ pub fn is_builtin(&self) -> bool {
         }
     }
 
+    /// The file of a user code location, if any.
+    pub fn filename(&self) -> Option<String> {
+        match self {
+            Location::Loc { file, .. } => Some(file.to_string()),
+            _ => None,
+        }
+    }
+
+    /// The line of a location, if known.
+    pub fn line(&self) -> Option<u64> {
+        match self {
+            Location::Loc { line, .. } => Some(*line),
+            Location::BuiltinFunction { line, .. } => *line,
+            Location::None => None,
+        }
+    }
+
     /// Convert a location to a short string suitable for (e.g.) logging.
     /// Goal is to return just "file:line" as clearly as possible.
     pub fn short_string(&self) -> String {
@@ -5,6 +5,7 @@
 mod builtin;
 mod expr;
 mod location;
+mod property_class;
 mod stmt;
 mod symbol;
 mod symbol_table;
@@ -16,6 +17,7 @@
     ArithmeticOverflowResult, BinaryOperand, Expr, ExprValue, SelfOperand, UnaryOperand,
 };
 pub use location::Location;
+pub use property_class::PropertyClass;
 pub use stmt::{Stmt, StmtBody, SwitchCase};
 pub use symbol::{Symbol, SymbolValues};
 pub use symbol_table::SymbolTable;
@@ -0,0 +1,42 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//! The classes of the properties that assertions check. CBMC reports the class of each property
+//! along with its result and uses it in the property id (`<function>.<class>.<n>`), so that
+//! failures can be grouped and filtered without looking at their descriptions. The driver
+//! (`scripts/rmc.py`) reads the class back from the property id.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertyClass {
+    /// An arithmetic operation overflows or divides by zero.
+    ArithmeticOverflow,
+    /// A user assertion or a panic, e.g. `assert!`, `panic!` or `unwrap()` on `None`.
+    Assertion,
+    /// A thread waits for a lock or a condition variable that is never released or notified.
+    Deadlock,
+    /// An `rmc::expect_fail` check, which is expected to fail.
+    ExpectFail,
+    /// An index is out of the bounds of an array or slice.
+    IndexOutOfBounds,
+    /// RMC generated code that is wrong. These should never fail.
+    SanityCheck,
+    /// The precondition of an intrinsic whose violation is undefined behavior.
+    UndefinedBehavior,
+    /// Code that RMC does not model is reached.
+    UnsupportedConstruct,
+}
+
+impl PropertyClass {
+    /// The name of the class in the property ids reported by CBMC, which `scripts/rmc.py` matches.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PropertyClass::ArithmeticOverflow => "arithmetic_overflow",
+            PropertyClass::Assertion => "assertion",
+            PropertyClass::Deadlock => "deadlock",
+            PropertyClass::ExpectFail => "expect_fail",
+            PropertyClass::IndexOutOfBounds => "index_out_of_bounds",
+            PropertyClass::SanityCheck => "sanity_check",
+            PropertyClass::UndefinedBehavior => "undefined_behavior",
+            PropertyClass::UnsupportedConstruct => "unsupported_construct",
+        }
+    }
+}
@@ -1,7 +1,7 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 use self::StmtBody::*;
-use super::{BuiltinFn, Expr, Location};
+use super::{Expr, Location, PropertyClass};
 use crate::InternedString;
 use std::fmt::Debug;
 use tracing::debug;
@@ -35,6 +35,13 @@ pub struct Stmt {
 /// Each statement is described by reference to the corresponding C code that would generate it.
 #[derive(Debug, Clone)]
 pub enum StmtBody {
+    /// `__CPROVER_assert(cond, msg);`
+    /// CBMC reports the property under `property_class`, e.g. `assertion` or `arithmetic_overflow`.
+    Assert {
+        cond: Expr,
+        property_class: PropertyClass,
+        msg: InternedString,
+    },
     /// `lhs = rhs;`
     Assign {
         lhs: Expr,
@@ -52,6 +59,8 @@ pub enum StmtBody {
     Break,
     /// `continue;`
     Continue,
+    /// `__CPROVER_DEAD(symbol);`: the lifetime of `symbol` ends.
+    Dead(Expr),
     /// `lhs.typ lhs = value;` or `lhs.typ lhs;`
     Decl {
         lhs: Expr, // SymbolExpr
@@ -73,7 +82,13 @@ pub enum StmtBody {
         arguments: Vec<Expr>,
     },
     /// `goto dest;`
-    Goto(InternedString),
+    /// If the goto is the back edge of a loop, it may carry the loop contracts
+    /// (`__CPROVER_loop_invariant(..)` and `__CPROVER_decreases(..)`) of that loop.
+    Goto {
+        dest: InternedString,
+        loop_invariant: Option<Expr>,
+        loop_decreases: Option<Expr>,
+    },
     /// `if (i) { t } else { e }`
     Ifthenelse {
         i: Expr,
@@ -89,6 +104,8 @@ pub enum StmtBody {
     Return(Option<Expr>),
     /// `;`
     Skip,
+    /// `__CPROVER_ASYNC_1: body;`: runs `body` in a new thread, which ends after it.
+    StartThread(Stmt),
     /// `switch (control) { case1.case: cast1.body; case2.case: case2.body; ... }`
     Switch {
         control: Expr,
@@ -162,6 +179,7 @@ pub fn assign(lhs: Expr, rhs: Expr, loc: Location) -> Self {
                 rhs.typ()
             );
             let assert_stmt = Stmt::assert_false(
+                PropertyClass::SanityCheck,
                 &format!(
                     "Reached assignment statement with unequal types {:?} {:?}",
                     lhs.typ(),
@@ -177,15 +195,16 @@ pub fn assign(lhs: Expr, rhs: Expr, loc: Location) -> Self {
     }
 
     /// `__CPROVER_assert(cond, msg);`
-    pub fn assert(cond: Expr, msg: &str, loc: Location) -> Self {
+    /// The property is reported under `property_class`, and its id is
+    /// `<function>.<property_class>.<n>`.
+    pub fn assert(cond: Expr, property_class: PropertyClass, msg: &str, loc: Location) -> Self {
         assert!(cond.typ().is_bool());
-        BuiltinFn::CProverAssert
-            .call(vec![cond, Expr::string_constant(msg)], loc.clone())
-            .as_stmt(loc)
+        let msg = msg.into();
+        stmt!(Assert { cond, property_class, msg }, loc)
     }
 
-    pub fn assert_false(msg: &str, loc: Location) -> Self {
-        Stmt::assert(Expr::bool_false(), msg, loc)
+    pub fn assert_false(property_class: PropertyClass, msg: &str, loc: Location) -> Self {
+        Stmt::assert(Expr::bool_false(), property_class, msg, loc)
     }
 
     /// A __CPROVER_assert to sanity check expected components of code
@@ -198,7 +217,12 @@ pub fn assert_sanity_check(expect_true: Expr, message: &str, url: &str, loc: Loc
         Stmt::block(
             vec![
                 // Assert our expected true expression.
-                Stmt::assert(expect_true.clone(), &assert_msg, loc.clone()),
+                Stmt::assert(
+                    expect_true.clone(),
+                    PropertyClass::SanityCheck,
+                    &assert_msg,
+                    loc.clone(),
+                ),
                 // If expect_true is false, assume false to block any further
                 // exploration of this path.
                 Stmt::assume(expect_true, loc.clone()),
@@ -233,6 +257,12 @@ pub fn continue_stmt(loc: Location) -> Self {
         stmt!(Continue, loc)
     }
 
+    /// `__CPROVER_DEAD(symbol);`
+    pub fn dead(symbol: Expr, loc: Location) -> Self {
+        assert!(symbol.is_symbol());
+        stmt!(Dead(symbol), loc)
+    }
+
     /// `lhs.typ lhs = value;` or `lhs.typ lhs;`
     pub fn decl(lhs: Expr, value: Option<Expr>, loc: Location) -> Self {
         assert!(lhs.is_symbol());
@@ -274,7 +304,21 @@ pub fn function_call(
     pub fn goto<T: Into<InternedString>>(dest: T, loc: Location) -> Self {
         let dest = dest.into();
         assert!(!dest.is_empty());
-        stmt!(Goto(dest), loc)
+        stmt!(Goto { dest, loop_invariant: None, loop_decreases: None }, loc)
+    }
+
+    /// `goto dest;` as the back edge of a loop, annotated with the contracts of that loop.
+    /// `goto-instrument --apply-loop-contracts` uses them instead of unwinding the loop.
+    pub fn with_loop_contracts(self, invariant: Expr, decreases: Option<Expr>) -> Self {
+        assert!(invariant.typ().is_bool());
+        let loc = self.location().clone();
+        match self.body() {
+            Goto { dest, .. } => stmt!(
+                Goto { dest: *dest, loop_invariant: Some(invariant), loop_decreases: decreases },
+                loc
+            ),
+            _ => unreachable!("Loop contracts can only be attached to a goto: {:?}", self),
+        }
     }
 
     /// `if (i) { t } else { e }` or `if (i) { t }`
@@ -293,6 +337,11 @@ pub fn skip(loc: Location) -> Self {
         stmt!(Skip, loc)
     }
 
+    /// `__CPROVER_ASYNC_1: body;`
+    pub fn start_thread(body: Stmt, loc: Location) -> Self {
+        stmt!(StartThread(body), loc)
+    }
+
     /// `switch (control) { case1.case: cast1.body; case2.case: case2.body; ... }`
     pub fn switch(
         control: Expr,
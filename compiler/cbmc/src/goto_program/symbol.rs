@@ -293,6 +293,11 @@ pub fn with_is_type(mut self, v: bool) -> Symbol {
         self
     }
 
+    pub fn with_value(mut self, value: Expr) -> Symbol {
+        self.value = SymbolValues::Expr(value);
+        self
+    }
+
     pub fn with_pretty_name<T: Into<InternedString>>(mut self, pretty_name: T) -> Symbol {
         self.pretty_name = Some(pretty_name.into());
         self
@@ -239,8 +239,20 @@ fn transform_type_union_tag(&mut self, tag: InternedString) -> Type {
     }
 
     /// Normalize goto label name.
-    fn transform_stmt_goto(&mut self, label: InternedString) -> Stmt {
-        Stmt::goto(self.normalize_identifier(label), Location::none())
+    fn transform_stmt_goto(
+        &mut self,
+        label: InternedString,
+        loop_invariant: &Option<Expr>,
+        loop_decreases: &Option<Expr>,
+    ) -> Stmt {
+        let goto = Stmt::goto(self.normalize_identifier(label), Location::none());
+        match loop_invariant {
+            Some(invariant) => goto.with_loop_contracts(
+                self.transform_expr(invariant),
+                loop_decreases.as_ref().map(|decreases| self.transform_expr(decreases)),
+            ),
+            None => goto,
+        }
     }
 
     /// Normalize label name.
@@ -39,7 +39,8 @@ mod tests {
     use super::{
         super::super::{
             super::{MachineModel, RoundingMode},
-            DatatypeComponent, Expr, Location, Stmt, SwitchCase, Symbol, SymbolTable, Type,
+            DatatypeComponent, Expr, Location, PropertyClass, Stmt, SwitchCase, Symbol, SymbolTable,
+            Type,
         },
         IdentityTransformer,
     };
@@ -223,7 +224,17 @@ fn exprs() {
                 Expr::symbol_expression("a".to_string(), Type::c_int()).address_of().dereference(),
             );
             add_sym(Expr::double_constant(1.0));
+            add_sym(Expr::exists_expr(
+                Expr::symbol_expression("i".to_string(), Type::c_int()),
+                Expr::symbol_expression("i".to_string(), Type::c_int())
+                    .lt(Expr::int_constant(5, Type::c_int())),
+            ));
             add_sym(Expr::float_constant(1.0));
+            add_sym(Expr::forall_expr(
+                Expr::symbol_expression("i".to_string(), Type::c_int()),
+                Expr::symbol_expression("i".to_string(), Type::c_int())
+                    .lt(Expr::int_constant(5, Type::c_int())),
+            ));
             add_sym(
                 Expr::array_expr(
                     Type::bool().array_of(2),
@@ -366,13 +377,21 @@ fn stmts() {
                 Expr::bool_true(),
                 Location::none(),
             ));
-            add_sym(Stmt::assert(Expr::bool_true(), "", Location::none()));
+            add_sym(Stmt::assert(
+                Expr::bool_true(),
+                PropertyClass::Assertion,
+                "",
+                Location::none(),
+            ));
             add_sym(Stmt::assume(Expr::bool_false(), Location::none()));
             add_sym(Stmt::atomic_block(
-                vec![Stmt::assert_false("", Location::none())],
+                vec![Stmt::assert_false(PropertyClass::Assertion, "", Location::none())],
+                Location::none(),
+            ));
+            add_sym(Stmt::block(
+                vec![Stmt::assert_false(PropertyClass::Assertion, "", Location::none())],
                 Location::none(),
             ));
-            add_sym(Stmt::block(vec![Stmt::assert_false("", Location::none())], Location::none()));
             add_sym(Stmt::break_stmt(Location::none()));
             add_sym(Stmt::continue_stmt(Location::none()));
             add_sym(Stmt::decl(
@@ -414,6 +433,10 @@ fn stmts() {
             ));
             add_sym(Stmt::ret(Some(Expr::bool_true()), Location::none()));
             add_sym(Stmt::skip(Location::none()));
+            add_sym(Stmt::start_thread(
+                Stmt::assert_false(PropertyClass::Assertion, "", Location::none()),
+                Location::none(),
+            ));
             add_sym(Stmt::switch(
                 Expr::int_constant(3, Type::c_int()),
                 vec![
@@ -442,7 +465,10 @@ fn stmts() {
                 Stmt::skip(Location::none()),
                 Location::none(),
             ));
-            add_sym(Stmt::assert_false("", Location::none()).with_label("tag1".to_string()));
+            add_sym(
+                Stmt::assert_false(PropertyClass::Assertion, "", Location::none())
+                    .with_label("tag1".to_string()),
+            );
         }
 
         assert_transform_eq(original);
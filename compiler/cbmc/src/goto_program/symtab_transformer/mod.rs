@@ -5,7 +5,9 @@
 mod gen_c_transformer;
 mod identity_transformer;
 mod passes;
+mod quantifier_transformer;
 mod transformer;
 
 pub use passes::do_passes;
+pub use quantifier_transformer::QuantifierTransformer;
 use transformer::Transformer;
@@ -0,0 +1,567 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::Transformer;
+use crate::goto_program::{
+    BuiltinFn, Expr, ExprValue, Stmt, StmtBody, SymbolTable, SymbolValues, Type,
+};
+use crate::InternedString;
+use rustc_data_structures::fx::FxHashMap;
+
+/// How deeply calls may be nested inside a quantified predicate.
+const MAX_INLINE_DEPTH: usize = 16;
+/// How many paths may be explored when turning a function body into an expression.
+const MAX_INLINE_PATHS: usize = 1024;
+
+/// Maps the locals and parameters of an inlined function to their current value.
+type Environment = FxHashMap<InternedString, Expr>;
+
+/// Struct for inlining the function calls that appear inside `forall` and `exists` expressions.
+///
+/// CBMC does not allow side effects, and therefore function calls, inside quantifiers.
+/// Quantified predicates are written as Rust closures, so this pass replaces every call in a
+/// quantifier domain by an equivalent side-effect free expression: the body of the callee is
+/// executed symbolically, and the values it returns on each path are combined into an
+/// if-then-else over the branch conditions. Only loop-free callees can be inlined this way.
+///
+/// The assertions met along the way (e.g. bounds checks) become part of the predicate: the call
+/// in the quantifier domain evaluates to false for a value of the bound variable for which one
+/// of them fails, so a `forall` that reaches a failing check does not hold.
+pub struct QuantifierTransformer<'a, 'e> {
+    new_symbol_table: SymbolTable,
+    original_symbol_table: &'a SymbolTable,
+    /// The number of quantifiers we are currently nested in.
+    quantifier_depth: usize,
+    /// The callees we are currently inlining, innermost last.
+    inline_stack: Vec<InternedString>,
+    /// The values to substitute for symbols while inlining a callee.
+    substitution: Environment,
+    /// The number of paths explored while inlining the outermost call.
+    paths: usize,
+    /// The length of `inline_stack` when the innermost quantifier was entered. Calls made at that
+    /// depth are the calls in the quantifier domain itself.
+    domain_inline_depth: usize,
+    /// The condition under which the statement being inlined executes.
+    path: Expr,
+    /// The assertions met while inlining the current call in the quantifier domain, each one
+    /// implied by the condition of its path.
+    checks: Vec<Expr>,
+    /// The first call that could not be inlined, if any.
+    error: &'e mut Option<String>,
+}
+
+impl<'a, 'e> QuantifierTransformer<'a, 'e> {
+    /// Inline the function calls in every quantifier of the given symbol table.
+    /// Returns a description of the first call that could not be inlined, if any.
+    pub fn transform(original_symbol_table: &'a SymbolTable) -> Result<SymbolTable, String> {
+        let mut error = None;
+        let new_symbol_table = SymbolTable::new(original_symbol_table.machine_model().clone());
+        let symbol_table = QuantifierTransformer {
+            new_symbol_table,
+            original_symbol_table,
+            quantifier_depth: 0,
+            inline_stack: vec![],
+            substitution: Environment::default(),
+            paths: 0,
+            domain_inline_depth: 0,
+            path: Expr::bool_true(),
+            checks: vec![],
+            error: &mut error,
+        }
+        .transform_symbol_table(original_symbol_table);
+        match error {
+            Some(error) => Err(error),
+            None => Ok(symbol_table),
+        }
+    }
+
+    /// Transforms the domain of a quantifier, inlining the calls it contains.
+    fn transform_quantifier_domain(&mut self, domain: &Expr) -> Expr {
+        self.quantifier_depth += 1;
+        let outer_depth = std::mem::replace(&mut self.domain_inline_depth, self.inline_stack.len());
+        let outer_path = std::mem::replace(&mut self.path, Expr::bool_true());
+        let outer_checks = std::mem::take(&mut self.checks);
+        let transformed_domain = self.transform_expr(domain);
+        self.domain_inline_depth = outer_depth;
+        self.path = outer_path;
+        self.checks = outer_checks;
+        self.quantifier_depth -= 1;
+        transformed_domain
+    }
+
+    /// Makes the value of a call in the quantifier domain false if one of the assertions that
+    /// the call executes fails.
+    fn add_checks(&mut self, typ: &Type, value: Expr) -> Result<Expr, String> {
+        let checks = std::mem::take(&mut self.checks);
+        if checks.is_empty() {
+            return Ok(value);
+        }
+        if !(typ.is_bool() || typ.is_c_bool()) {
+            return Err(format!("assertion in {:?}, whose value is not a boolean", value));
+        }
+        let all_checks = checks.into_iter().reduce(|all, check| all.and(check)).unwrap();
+        Ok(all_checks.and(value).cast_to(typ.clone()))
+    }
+
+    fn report(&mut self, error: String) {
+        if self.error.is_none() {
+            *self.error = Some(error);
+        }
+    }
+
+    /// Transforms `e` in the context of the callee currently being inlined.
+    fn substitute(&mut self, env: &Environment, e: &Expr) -> Expr {
+        let outer = std::mem::replace(&mut self.substitution, env.clone());
+        let transformed = self.transform_expr(e);
+        self.substitution = outer;
+        transformed
+    }
+
+    /// Returns a side-effect free expression equivalent to `function(arguments)`,
+    /// where the arguments are evaluated under the current substitution.
+    fn inline_call(&mut self, function: &Expr, arguments: &[Expr]) -> Result<Expr, String> {
+        let name = match function.value() {
+            ExprValue::Symbol { identifier } => *identifier,
+            _ => return Err(format!("indirect call to {:?}", function)),
+        };
+        let original_symbol_table = self.original_symbol_table;
+        let symbol = original_symbol_table
+            .lookup(name)
+            .ok_or_else(|| format!("call to unknown function {}", name))?;
+        let body = match &symbol.value {
+            SymbolValues::Stmt(body) => body,
+            _ => return Err(format!("call to {}, which has no body", name)),
+        };
+        if self.inline_stack.contains(&name) {
+            return Err(format!("recursive call to {}", name));
+        }
+        if self.inline_stack.len() >= MAX_INLINE_DEPTH {
+            return Err(format!("calls nested too deeply at {}", name));
+        }
+
+        let parameters = symbol.typ.parameters().unwrap();
+        if parameters.len() != arguments.len() {
+            return Err(format!("call to {} with the wrong number of arguments", name));
+        }
+        let mut env = Environment::default();
+        for (parameter, argument) in parameters.iter().zip(arguments) {
+            let argument = self.transform_expr(argument);
+            env.insert(parameter.identifier().unwrap(), argument);
+        }
+
+        if self.inline_stack.is_empty() {
+            self.paths = 0;
+        }
+        self.inline_stack.push(name);
+        let program = Program::new(body);
+        let result = self.execute(&program, 0, env);
+        self.inline_stack.pop();
+        result?.ok_or_else(|| format!("{} never returns", name))
+    }
+
+    /// Executes `program` from `pc` and returns the value it evaluates to,
+    /// or `None` if every path from `pc` is infeasible.
+    fn execute(
+        &mut self,
+        program: &Program<'_>,
+        mut pc: usize,
+        mut env: Environment,
+    ) -> Result<Option<Expr>, String> {
+        self.paths += 1;
+        if self.paths > MAX_INLINE_PATHS {
+            return Err(format!("too many paths in {}", self.inline_stack.last().unwrap()));
+        }
+        loop {
+            let instruction = match program.instructions.get(pc) {
+                Some(instruction) => instruction,
+                None => return Ok(None),
+            };
+            match instruction {
+                Instruction::Jump(target) => pc = program.forward_target(pc, target)?,
+                Instruction::Branch(cond, target) => {
+                    let cond = self.substitute(&env, cond);
+                    let target = program.forward_target(pc, target)?;
+                    let outer_path = self.path.clone();
+                    self.path = outer_path.clone().and(cond.clone());
+                    let taken = self.execute(program, target, env.clone())?;
+                    self.path = outer_path.clone().and(cond.clone().not());
+                    let not_taken = self.execute(program, pc + 1, env)?;
+                    self.path = outer_path;
+                    return Ok(match (taken, not_taken) {
+                        (Some(t), Some(e)) => Some(cond.ternary(t, e)),
+                        (t, e) => t.or(e),
+                    });
+                }
+                Instruction::Stmt(stmt) => {
+                    match stmt.body() {
+                        StmtBody::Assign { lhs, rhs }
+                        | StmtBody::Decl { lhs, value: Some(rhs) } => {
+                            let value = self.substitute(&env, rhs);
+                            env.insert(symbol_identifier(lhs)?, value);
+                        }
+                        StmtBody::Assume { cond } => {
+                            if self.assume(&env, cond)? {
+                                return Ok(None);
+                            }
+                        }
+                        StmtBody::Assert { cond, .. } => self.assert(&env, cond),
+                        StmtBody::Dead(_)
+                        | StmtBody::Decl { value: None, .. }
+                        | StmtBody::Skip => {}
+                        StmtBody::Expression(e) => {
+                            if let Some(cond) = builtin_call_arg(e, BuiltinFn::CProverAssert) {
+                                self.assert(&env, cond);
+                            } else if let Some(cond) =
+                                builtin_call_arg(e, BuiltinFn::CProverAssume)
+                            {
+                                if self.assume(&env, cond)? {
+                                    return Ok(None);
+                                }
+                            } else if e.is_side_effect() {
+                                return Err(format!("side effect {:?}", e));
+                            }
+                        }
+                        StmtBody::FunctionCall { lhs, function, arguments } => {
+                            let outer = std::mem::replace(&mut self.substitution, env.clone());
+                            let value = self.inline_call(function, arguments);
+                            self.substitution = outer;
+                            let value = value?;
+                            if let Some(lhs) = lhs {
+                                env.insert(symbol_identifier(lhs)?, value);
+                            }
+                        }
+                        StmtBody::Return(Some(e)) => return Ok(Some(self.substitute(&env, e))),
+                        _ => return Err(format!("unsupported statement {:?}", stmt)),
+                    }
+                    pc += 1;
+                }
+            }
+        }
+    }
+
+    /// Records that the current path asserts `cond`.
+    fn assert(&mut self, env: &Environment, cond: &Expr) {
+        let cond = self.substitute(env, cond);
+        self.checks.push(self.path.clone().implies(cond));
+    }
+
+    /// Whether assuming `cond` makes the current path infeasible. We can only drop the path
+    /// when `cond` is false, as after a failed assertion; other assumptions are not supported.
+    fn assume(&mut self, env: &Environment, cond: &Expr) -> Result<bool, String> {
+        match self.substitute(env, cond).value() {
+            ExprValue::BoolConstant(false) => Ok(true),
+            ExprValue::BoolConstant(true) => Ok(false),
+            _ => Err(format!("assumption {:?}", cond)),
+        }
+    }
+}
+
+impl<'a, 'e> Transformer for QuantifierTransformer<'a, 'e> {
+    /// Get reference to symbol table.
+    fn symbol_table(&self) -> &SymbolTable {
+        &self.new_symbol_table
+    }
+
+    /// Get mutable reference to symbol table.
+    fn mut_symbol_table(&mut self) -> &mut SymbolTable {
+        &mut self.new_symbol_table
+    }
+
+    /// Get owned symbol table.
+    fn extract_symbol_table(self) -> SymbolTable {
+        self.new_symbol_table
+    }
+
+    /// Inline calls in the domain of an existential quantifier.
+    fn transform_expr_exists(&mut self, _typ: &Type, variable: &Expr, domain: &Expr) -> Expr {
+        let transformed_variable = self.transform_expr(variable);
+        let transformed_domain = self.transform_quantifier_domain(domain);
+        Expr::exists_expr(transformed_variable, transformed_domain)
+    }
+
+    /// Inline calls in the domain of a universal quantifier.
+    fn transform_expr_forall(&mut self, _typ: &Type, variable: &Expr, domain: &Expr) -> Expr {
+        let transformed_variable = self.transform_expr(variable);
+        let transformed_domain = self.transform_quantifier_domain(domain);
+        Expr::forall_expr(transformed_variable, transformed_domain)
+    }
+
+    /// Replace calls inside quantifiers by the value they return.
+    fn transform_expr_function_call(
+        &mut self,
+        typ: &Type,
+        function: &Expr,
+        arguments: &[Expr],
+    ) -> Expr {
+        if self.quantifier_depth == 0 {
+            let transformed_function = self.transform_expr(function);
+            let transformed_arguments =
+                arguments.iter().map(|argument| self.transform_expr(argument)).collect();
+            return transformed_function.call(transformed_arguments);
+        }
+        let in_domain = self.inline_stack.len() == self.domain_inline_depth;
+        if in_domain {
+            self.path = Expr::bool_true();
+            self.checks.clear();
+        }
+        let value = self.inline_call(function, arguments);
+        let value = if in_domain { value.and_then(|v| self.add_checks(typ, v)) } else { value };
+        match value {
+            Ok(value) => value,
+            Err(error) => {
+                self.report(error);
+                Expr::nondet(self.transform_type(typ))
+            }
+        }
+    }
+
+    /// Substitute the locals of the callee being inlined.
+    fn transform_expr_symbol(&mut self, typ: &Type, identifier: InternedString) -> Expr {
+        match self.substitution.get(&identifier) {
+            Some(value) => value.clone(),
+            None => {
+                let transformed_typ = self.transform_type(typ);
+                Expr::symbol_expression(identifier, transformed_typ)
+            }
+        }
+    }
+}
+
+fn symbol_identifier(e: &Expr) -> Result<InternedString, String> {
+    match e.value() {
+        ExprValue::Symbol { identifier } => Ok(*identifier),
+        _ => Err(format!("assignment to {:?}", e)),
+    }
+}
+
+/// The first argument of `e`, if `e` is a call to `builtin`.
+fn builtin_call_arg(e: &Expr, builtin: BuiltinFn) -> Option<&Expr> {
+    match e.value() {
+        ExprValue::FunctionCall { function, arguments } => match function.value() {
+            ExprValue::Symbol { identifier } if *identifier == builtin.to_string() => {
+                arguments.first()
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The target of a jump in a `Program`.
+enum Target {
+    Label(InternedString),
+    Index(usize),
+}
+
+enum Instruction<'a> {
+    /// A statement without control flow.
+    Stmt(&'a Stmt),
+    Jump(Target),
+    /// Jump to the target if the condition holds.
+    Branch(Expr, Target),
+}
+
+/// A function body flattened into a list of instructions, with the labels resolved.
+struct Program<'a> {
+    instructions: Vec<Instruction<'a>>,
+    labels: FxHashMap<InternedString, usize>,
+}
+
+impl<'a> Program<'a> {
+    fn new(body: &'a Stmt) -> Self {
+        let mut program = Program { instructions: vec![], labels: FxHashMap::default() };
+        program.flatten(body);
+        program
+    }
+
+    fn flatten(&mut self, stmt: &'a Stmt) {
+        match stmt.body() {
+            StmtBody::Block(stmts) => stmts.iter().for_each(|s| self.flatten(s)),
+            StmtBody::Label { label, body } => {
+                self.labels.insert(*label, self.instructions.len());
+                self.flatten(body);
+            }
+            StmtBody::Goto { dest, .. } => {
+                self.instructions.push(Instruction::Jump(Target::Label(*dest)))
+            }
+            StmtBody::Ifthenelse { i, t, e } => {
+                self.flatten_branches(vec![(i.clone(), t)], e.as_ref())
+            }
+            StmtBody::Switch { control, cases, default } => {
+                let branches = cases
+                    .iter()
+                    .map(|case| (control.clone().eq(case.case().clone()), case.body()))
+                    .collect();
+                self.flatten_branches(branches, default.as_ref())
+            }
+            _ => self.instructions.push(Instruction::Stmt(stmt)),
+        }
+    }
+
+    /// Flattens `if (c1) b1 else if (c2) b2 ... else default` into `if (c1) goto l1;
+    /// if (c2) goto l2; ... default; goto end; l1: b1; goto end; l2: b2; goto end; ... end:`
+    fn flatten_branches(&mut self, branches: Vec<(Expr, &'a Stmt)>, default: Option<&'a Stmt>) {
+        let first_branch = self.instructions.len();
+        for (cond, _) in &branches {
+            self.instructions.push(Instruction::Branch(cond.clone(), Target::Index(0)));
+        }
+        let mut jumps_to_end = vec![];
+        if let Some(default) = default {
+            self.flatten(default);
+        }
+        jumps_to_end.push(self.instructions.len());
+        self.instructions.push(Instruction::Jump(Target::Index(0)));
+        for (k, (cond, body)) in branches.into_iter().enumerate() {
+            let start = Target::Index(self.instructions.len());
+            self.instructions[first_branch + k] = Instruction::Branch(cond, start);
+            self.flatten(body);
+            jumps_to_end.push(self.instructions.len());
+            self.instructions.push(Instruction::Jump(Target::Index(0)));
+        }
+        let end = self.instructions.len();
+        for jump in jumps_to_end {
+            self.instructions[jump] = Instruction::Jump(Target::Index(end));
+        }
+    }
+
+    /// Resolves the target of the jump at `pc`. Loops are not supported, so it must jump forward.
+    fn forward_target(&self, pc: usize, target: &Target) -> Result<usize, String> {
+        let index = match target {
+            Target::Index(index) => *index,
+            Target::Label(label) => {
+                *self.labels.get(label).ok_or_else(|| format!("jump to unknown label {}", label))?
+            }
+        };
+        if index <= pc { Err("a loop".to_string()) } else { Ok(index) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::{
+        super::{MachineModel, RoundingMode},
+        Expr, Location, PropertyClass, Stmt, Symbol, SymbolTable, Type,
+    };
+    use super::QuantifierTransformer;
+
+    fn default_machine_model() -> MachineModel {
+        MachineModel::new(
+            1,
+            "x86_64",
+            8,
+            false,
+            8,
+            64,
+            32,
+            32,
+            false,
+            128,
+            64,
+            64,
+            4,
+            true,
+            64,
+            RoundingMode::ToNearest,
+            16,
+            32,
+            false,
+            32,
+            32,
+        )
+    }
+
+    fn param() -> Expr {
+        Expr::symbol_expression("is_small::x", Type::c_int())
+    }
+
+    fn is_small(body: Vec<Stmt>) -> Symbol {
+        let parameter = Type::c_int().as_parameter(Some("is_small::x".into()), Some("x".into()));
+        Symbol::function(
+            "is_small",
+            Type::code(vec![parameter], Type::c_bool()),
+            Some(Stmt::block(body, Location::none())),
+            None::<&str>,
+            Location::none(),
+        )
+    }
+
+    /// `if (x < 5) goto l1; return false; l1: return true;`
+    fn is_small_body() -> Vec<Stmt> {
+        vec![
+            param().lt(Expr::int_constant(5, Type::c_int())).if_then_else(
+                Stmt::goto("l1", Location::none()),
+                None,
+                Location::none(),
+            ),
+            Expr::c_false().ret(Location::none()),
+            Expr::c_true().ret(Location::none()).with_label("l1"),
+        ]
+    }
+
+    /// `forall i . i >= 0 ==> domain(i)`
+    fn quantifier(domain: impl Fn(Expr) -> Expr) -> Symbol {
+        let i = Expr::symbol_expression("i", Type::c_int());
+        let range = i.clone().ge(Expr::int_constant(0, Type::c_int()));
+        let value = Expr::forall_expr(i.clone(), range.implies(domain(i)));
+        Symbol::constant("q", "q", "q", value, Location::none())
+    }
+
+    fn call_is_small(i: Expr) -> Expr {
+        let typ = Type::code(
+            vec![Type::c_int().as_parameter(Some("is_small::x".into()), Some("x".into()))],
+            Type::c_bool(),
+        );
+        Expr::symbol_expression("is_small", typ).call(vec![i])
+    }
+
+    #[test]
+    fn inline_branches() {
+        let mut original = SymbolTable::new(default_machine_model());
+        original.insert(is_small(is_small_body()));
+        original.insert(quantifier(call_is_small));
+
+        let mut expected = SymbolTable::new(default_machine_model());
+        expected.insert(is_small(is_small_body()));
+        expected.insert(quantifier(|i| {
+            i.lt(Expr::int_constant(5, Type::c_int())).ternary(Expr::c_true(), Expr::c_false())
+        }));
+
+        let transformed = QuantifierTransformer::transform(&original).unwrap();
+        assert_eq!(expected.to_irep(), transformed.to_irep());
+    }
+
+    #[test]
+    fn inline_assertions() {
+        let mut body = is_small_body();
+        let check = param().lt(Expr::int_constant(10, Type::c_int()));
+        body.insert(0, Stmt::assert(check, PropertyClass::Assertion, "x < 10", Location::none()));
+        let mut original = SymbolTable::new(default_machine_model());
+        original.insert(is_small(body.clone()));
+        original.insert(quantifier(call_is_small));
+
+        let mut expected = SymbolTable::new(default_machine_model());
+        expected.insert(is_small(body));
+        expected.insert(quantifier(|i| {
+            let check =
+                Expr::bool_true().implies(i.clone().lt(Expr::int_constant(10, Type::c_int())));
+            let value =
+                i.lt(Expr::int_constant(5, Type::c_int())).ternary(Expr::c_true(), Expr::c_false());
+            check.and(value).cast_to(Type::c_bool())
+        }));
+
+        let transformed = QuantifierTransformer::transform(&original).unwrap();
+        assert_eq!(expected.to_irep(), transformed.to_irep());
+    }
+
+    #[test]
+    fn reject_loops() {
+        let mut original = SymbolTable::new(default_machine_model());
+        let mut body = is_small_body();
+        body.insert(0, Stmt::skip(Location::none()).with_label("l0"));
+        body[2] = Stmt::goto("l0", Location::none());
+        original.insert(is_small(body));
+        original.insert(quantifier(call_is_small));
+
+        assert!(QuantifierTransformer::transform(&original).is_err());
+    }
+}
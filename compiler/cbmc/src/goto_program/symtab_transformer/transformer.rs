@@ -2,8 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use crate::goto_program::{
-    BinaryOperand, CIntType, DatatypeComponent, Expr, ExprValue, Location, Parameter, SelfOperand,
-    Stmt, StmtBody, SwitchCase, Symbol, SymbolTable, SymbolValues, Type, UnaryOperand,
+    BinaryOperand, CIntType, DatatypeComponent, Expr, ExprValue, Location, Parameter,
+    PropertyClass, SelfOperand, Stmt, StmtBody, SwitchCase, Symbol, SymbolTable, SymbolValues, Type,
+    UnaryOperand,
 };
 use crate::InternedString;
 use num::bigint::BigInt;
@@ -250,7 +251,13 @@ fn transform_expr(&mut self, e: &Expr) -> Expr {
             ExprValue::CBoolConstant(value) => self.transform_expr_c_bool_constant(typ, value),
             ExprValue::Dereference(child) => self.transform_expr_dereference(typ, child),
             ExprValue::DoubleConstant(value) => self.transform_expr_double_constant(typ, value),
+            ExprValue::Exists { variable, domain } => {
+                self.transform_expr_exists(typ, variable, domain)
+            }
             ExprValue::FloatConstant(value) => self.transform_expr_float_constant(typ, value),
+            ExprValue::Forall { variable, domain } => {
+                self.transform_expr_forall(typ, variable, domain)
+            }
             ExprValue::FunctionCall { function, arguments } => {
                 self.transform_expr_function_call(typ, function, arguments)
             }
@@ -353,11 +360,25 @@ fn transform_expr_double_constant(&mut self, _typ: &Type, value: &f64) -> Expr {
         Expr::double_constant(*value)
     }
 
+    /// Transforms an existential quantifier expr (`exists variable . domain`)
+    fn transform_expr_exists(&mut self, _typ: &Type, variable: &Expr, domain: &Expr) -> Expr {
+        let transformed_variable = self.transform_expr(variable);
+        let transformed_domain = self.transform_expr(domain);
+        Expr::exists_expr(transformed_variable, transformed_domain)
+    }
+
     /// Transforms a float constant expr (`1.0f`)
     fn transform_expr_float_constant(&mut self, _typ: &Type, value: &f32) -> Expr {
         Expr::float_constant(*value)
     }
 
+    /// Transforms a universal quantifier expr (`forall variable . domain`)
+    fn transform_expr_forall(&mut self, _typ: &Type, variable: &Expr, domain: &Expr) -> Expr {
+        let transformed_variable = self.transform_expr(variable);
+        let transformed_domain = self.transform_expr(domain);
+        Expr::forall_expr(transformed_variable, transformed_domain)
+    }
+
     /// Transforms a function call expr (`function(arguments)`)
     fn transform_expr_function_call(
         &mut self,
@@ -494,12 +515,16 @@ fn transform_expr_un_op(&mut self, _typ: &Type, op: &UnaryOperand, e: &Expr) ->
     /// the corresponding stmt transformer method.
     fn transform_stmt(&mut self, stmt: &Stmt) -> Stmt {
         match stmt.body() {
+            StmtBody::Assert { cond, property_class, msg } => {
+                self.transform_stmt_assert(cond, *property_class, *msg)
+            }
             StmtBody::Assign { lhs, rhs } => self.transform_stmt_assign(lhs, rhs),
             StmtBody::Assume { cond } => self.transform_stmt_assume(cond),
             StmtBody::AtomicBlock(block) => self.transform_stmt_atomic_block(block),
             StmtBody::Block(block) => self.transform_stmt_block(block),
             StmtBody::Break => self.transform_stmt_break(),
             StmtBody::Continue => self.transform_stmt_continue(),
+            StmtBody::Dead(symbol) => self.transform_stmt_dead(symbol),
             StmtBody::Decl { lhs, value } => self.transform_stmt_decl(lhs, value),
             StmtBody::Expression(expr) => self.transform_stmt_expression(expr),
             StmtBody::For { init, cond, update, body } => {
@@ -508,11 +533,14 @@ fn transform_stmt(&mut self, stmt: &Stmt) -> Stmt {
             StmtBody::FunctionCall { lhs, function, arguments } => {
                 self.transform_stmt_function_call(lhs, function, arguments)
             }
-            StmtBody::Goto(label) => self.transform_stmt_goto(*label),
+            StmtBody::Goto { dest, loop_invariant, loop_decreases } => {
+                self.transform_stmt_goto(*dest, loop_invariant, loop_decreases)
+            }
             StmtBody::Ifthenelse { i, t, e } => self.transform_stmt_ifthenelse(i, t, e),
             StmtBody::Label { label, body } => self.transform_stmt_label(*label, body),
             StmtBody::Return(value) => self.transform_stmt_return(value),
             StmtBody::Skip => self.transform_stmt_skip(),
+            StmtBody::StartThread(body) => self.transform_stmt_start_thread(body),
             StmtBody::Switch { control, cases, default } => {
                 self.transform_stmt_switch(control, cases, default)
             }
@@ -521,6 +549,17 @@ fn transform_stmt(&mut self, stmt: &Stmt) -> Stmt {
         .with_location(stmt.location().clone())
     }
 
+    /// Transforms a CPROVER assert stmt (`__CPROVER_assert(cond, msg);`)
+    fn transform_stmt_assert(
+        &mut self,
+        cond: &Expr,
+        property_class: PropertyClass,
+        msg: InternedString,
+    ) -> Stmt {
+        let transformed_cond = self.transform_expr(cond);
+        Stmt::assert(transformed_cond, property_class, &msg.to_string(), Location::none())
+    }
+
     /// Transforms an assign stmt (`lhs = rhs;`)
     fn transform_stmt_assign(&mut self, lhs: &Expr, rhs: &Expr) -> Stmt {
         let transformed_lhs = self.transform_expr(lhs);
@@ -556,6 +595,12 @@ fn transform_stmt_continue(&mut self) -> Stmt {
         Stmt::continue_stmt(Location::none())
     }
 
+    /// Transform a dead stmt (`__CPROVER_DEAD(symbol);`)
+    fn transform_stmt_dead(&mut self, symbol: &Expr) -> Stmt {
+        let transformed_symbol = self.transform_expr(symbol);
+        Stmt::dead(transformed_symbol, Location::none())
+    }
+
     /// Transform a decl stmt (`lhs.typ lhs = value;` or `lhs.typ lhs;`)
     fn transform_stmt_decl(&mut self, lhs: &Expr, value: &Option<Expr>) -> Stmt {
         let transformed_lhs = self.transform_expr(lhs);
@@ -605,8 +650,20 @@ fn transform_stmt_function_call(
     }
 
     /// Transforms a goto stmt (`goto dest;`)
-    fn transform_stmt_goto(&mut self, label: InternedString) -> Stmt {
-        Stmt::goto(label, Location::none())
+    fn transform_stmt_goto(
+        &mut self,
+        label: InternedString,
+        loop_invariant: &Option<Expr>,
+        loop_decreases: &Option<Expr>,
+    ) -> Stmt {
+        let goto = Stmt::goto(label, Location::none());
+        match loop_invariant {
+            Some(invariant) => goto.with_loop_contracts(
+                self.transform_expr(invariant),
+                loop_decreases.as_ref().map(|decreases| self.transform_expr(decreases)),
+            ),
+            None => goto,
+        }
     }
 
     /// Transforms an if-then-else stmt (`if (i) { t } else { e }`)
@@ -635,6 +692,12 @@ fn transform_stmt_skip(&mut self) -> Stmt {
         Stmt::skip(Location::none())
     }
 
+    /// Transforms a start thread stmt (`__CPROVER_ASYNC_1: body;`)
+    fn transform_stmt_start_thread(&mut self, body: &Stmt) -> Stmt {
+        let transformed_body = self.transform_stmt(body);
+        Stmt::start_thread(transformed_body, Location::none())
+    }
+
     /// Transforms a switch stmt (`switch (control) { case1.case: cast1.body; case2.case: case2.body; ... }`)
     fn transform_stmt_switch(
         &mut self,
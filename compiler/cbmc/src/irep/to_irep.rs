@@ -192,6 +192,18 @@ fn to_irep(&self, mm: &MachineModel) -> Irep {
                     named_sub: vector_map![(IrepId::Value, Irep::just_hex_id(c))],
                 }
             }
+            ExprValue::Exists { variable, domain } => Irep {
+                id: IrepId::Exists,
+                sub: vec![
+                    Irep {
+                        id: IrepId::Tuple,
+                        sub: vec![variable.to_irep(mm)],
+                        named_sub: vector_map![],
+                    },
+                    domain.to_irep(mm),
+                ],
+                named_sub: vector_map![],
+            },
             ExprValue::FloatConstant(i) => {
                 let c: u32 = unsafe { std::mem::transmute(*i) };
                 Irep {
@@ -200,6 +212,18 @@ fn to_irep(&self, mm: &MachineModel) -> Irep {
                     named_sub: vector_map![(IrepId::Value, Irep::just_hex_id(c))],
                 }
             }
+            ExprValue::Forall { variable, domain } => Irep {
+                id: IrepId::Forall,
+                sub: vec![
+                    Irep {
+                        id: IrepId::Tuple,
+                        sub: vec![variable.to_irep(mm)],
+                        named_sub: vector_map![],
+                    },
+                    domain.to_irep(mm),
+                ],
+                named_sub: vector_map![],
+            },
             ExprValue::FunctionCall { function, arguments } => side_effect_irep(
                 IrepId::FunctionCall,
                 vec![function.to_irep(mm), arguments_irep(arguments, mm)],
@@ -336,13 +360,32 @@ fn to_irep(&self, mm: &MachineModel) -> Irep {
 
 impl ToIrep for Stmt {
     fn to_irep(&self, mm: &MachineModel) -> Irep {
-        self.body().to_irep(mm).with_location(self.location(), mm)
+        match self.body() {
+            // CBMC takes the class and the description of a property from its source location,
+            // so an assertion needs one even when its location is unknown.
+            StmtBody::Assert { property_class, msg, .. } => {
+                let loc = match self.location().to_irep(mm) {
+                    loc if loc.is_nil() => Irep::just_named_sub(vector_map![]),
+                    loc => loc,
+                };
+                self.body().to_irep(mm).with_named_sub(
+                    IrepId::CSourceLocation,
+                    loc.with_named_sub(IrepId::Comment, Irep::just_string_id(msg.to_string()))
+                        .with_named_sub(
+                            IrepId::PropertyClass,
+                            Irep::just_string_id(property_class.as_str()),
+                        ),
+                )
+            }
+            _ => self.body().to_irep(mm).with_location(self.location(), mm),
+        }
     }
 }
 
 impl ToIrep for StmtBody {
     fn to_irep(&self, mm: &MachineModel) -> Irep {
         match self {
+            StmtBody::Assert { cond, .. } => code_irep(IrepId::Assert, vec![cond.to_irep(mm)]),
             StmtBody::Assign { lhs, rhs } => {
                 code_irep(IrepId::Assign, vec![lhs.to_irep(mm), rhs.to_irep(mm)])
             }
@@ -359,6 +402,7 @@ fn to_irep(&self, mm: &MachineModel) -> Irep {
             }
             StmtBody::Break => code_irep(IrepId::Break, vec![]),
             StmtBody::Continue => code_irep(IrepId::Continue, vec![]),
+            StmtBody::Dead(symbol) => code_irep(IrepId::Dead, vec![symbol.to_irep(mm)]),
             StmtBody::Decl { lhs, value } => {
                 if value.is_some() {
                     code_irep(
@@ -382,8 +426,18 @@ fn to_irep(&self, mm: &MachineModel) -> Irep {
                     arguments_irep(arguments, mm),
                 ],
             ),
-            StmtBody::Goto(dest) => code_irep(IrepId::Goto, vec![])
-                .with_named_sub(IrepId::Destination, Irep::just_string_id(dest.to_string())),
+            StmtBody::Goto { dest, loop_invariant, loop_decreases } => {
+                let goto = code_irep(IrepId::Goto, vec![])
+                    .with_named_sub(IrepId::Destination, Irep::just_string_id(dest.to_string()));
+                let goto = match loop_invariant {
+                    Some(inv) => goto.with_named_sub(IrepId::CSpecLoopInvariant, inv.to_irep(mm)),
+                    None => goto,
+                };
+                match loop_decreases {
+                    Some(dec) => goto.with_named_sub(IrepId::CSpecDecreases, dec.to_irep(mm)),
+                    None => goto,
+                }
+            }
             StmtBody::Ifthenelse { i, t, e } => code_irep(
                 IrepId::Ifthenelse,
                 vec![
@@ -398,6 +452,7 @@ fn to_irep(&self, mm: &MachineModel) -> Irep {
                 code_irep(IrepId::Return, vec![e.as_ref().map_or(Irep::nil(), |x| x.to_irep(mm))])
             }
             StmtBody::Skip => code_irep(IrepId::Skip, vec![]),
+            StmtBody::StartThread(body) => code_irep(IrepId::StartThread, vec![body.to_irep(mm)]),
             StmtBody::Switch { control, cases, default } => {
                 let mut switch_arms: Vec<Irep> = cases.iter().map(|x| x.to_irep(mm)).collect();
                 if default.is_some() {
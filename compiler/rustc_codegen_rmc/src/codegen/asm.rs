@@ -0,0 +1,210 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module handles inline assembly, which RMC cannot translate.
+//!
+//! An `asm!` block in a function for which `#[rmc::asm_model(f)]` gives a model is replaced by a
+//! call to the model, which takes the values of the `in` and `inout` operands of the block, in
+//! order, and returns the values of its `out` and `inout` operands: the value itself if there is
+//! only one, and a tuple otherwise. Since the model is given for the function, the function must
+//! have a single `asm!` block.
+//!
+//! Otherwise, with `-Z asm-overapproximation`, the block sets its outputs to nondeterministic
+//! values and, unless it is `nomem` or `readonly`, the objects its pointer inputs point to as well.
+//! Without this option, reaching the block fails an unsupported construct check.
+
+use crate::GotocCtx;
+use cbmc::goto_program::{BuiltinFn, Expr, Location, Stmt, Type};
+use rustc_ast::InlineAsmOptions;
+use rustc_middle::mir::{
+    BasicBlock, InlineAsmOperand, LlvmInlineAsm, Operand, Place, TerminatorKind,
+};
+use rustc_middle::ty::{self, Instance, Ty};
+use rustc_span::Span;
+
+const ASM_ISSUE_URL: &str = "https://github.com/model-checking/rmc/issues/2";
+
+impl<'tcx> GotocCtx<'tcx> {
+    pub fn asm_overapproximation_enabled(&self) -> bool {
+        self.tcx.sess.opts.debugging_opts.asm_overapproximation
+    }
+
+    /// The model given with `#[rmc::asm_model]` for the inline assembly of the current function.
+    /// A model cannot tell the blocks of a function apart, so this reports an error at `span`, the
+    /// block we codegen, if the function has several of them.
+    fn asm_model_of_current_fn(&self, span: Span) -> Option<Instance<'tcx>> {
+        let model = self.asm_models.get(&self.current_fn().instance().def_id()).copied()?;
+        let num_blocks = self
+            .current_fn()
+            .mir()
+            .basic_blocks()
+            .iter()
+            .filter(|bbd| matches!(bbd.terminator().kind, TerminatorKind::InlineAsm { .. }))
+            .count();
+        if num_blocks > 1 {
+            self.tcx.sess.span_err(
+                span,
+                &format!(
+                    "`#[rmc::asm_model]` can only model a function with a single `asm!` block, \
+                     but `{}` has {}",
+                    self.current_fn().readable_name(),
+                    num_blocks
+                ),
+            );
+        }
+        Some(model)
+    }
+
+    /// Codegens an `asm!` block, which continues at `destination` unless it does not return.
+    pub fn codegen_inline_asm(
+        &mut self,
+        operands: &[InlineAsmOperand<'tcx>],
+        options: InlineAsmOptions,
+        destination: Option<BasicBlock>,
+        span: Span,
+    ) -> Stmt {
+        let loc = self.codegen_span(&span);
+        let inputs: Vec<&Operand<'tcx>> = operands
+            .iter()
+            .filter_map(|op| match op {
+                InlineAsmOperand::In { value, .. }
+                | InlineAsmOperand::InOut { in_value: value, .. } => Some(value),
+                _ => None,
+            })
+            .collect();
+        let outputs: Vec<&Place<'tcx>> = operands
+            .iter()
+            .filter_map(|op| match op {
+                InlineAsmOperand::Out { place, .. }
+                | InlineAsmOperand::InOut { out_place: place, .. } => place.as_ref(),
+                _ => None,
+            })
+            .collect();
+        let body = if let Some(model) = self.asm_model_of_current_fn(span) {
+            self.codegen_asm_model_call(model, &inputs, &outputs, span, loc.clone())
+        } else if self.asm_overapproximation_enabled() {
+            let writes_memory =
+                !options.intersects(InlineAsmOptions::NOMEM | InlineAsmOptions::READONLY);
+            let mut stmts = if writes_memory {
+                self.codegen_havoc_pointees(&inputs, loc.clone())
+            } else {
+                vec![]
+            };
+            stmts.extend(outputs.iter().map(|place| self.codegen_havoc_place(place)));
+            Stmt::block(stmts, loc.clone())
+        } else {
+            return self
+                .codegen_unimplemented("InlineAsm", Type::empty(), loc.clone(), ASM_ISSUE_URL)
+                .as_stmt(loc);
+        };
+        let next = match destination {
+            Some(target) => Stmt::goto(self.current_fn().find_label(&target), loc.clone()),
+            // The block is `noreturn`, so no execution continues after it.
+            None => Stmt::assume(Expr::bool_false(), loc.clone()),
+        };
+        Stmt::block(vec![body, next], loc)
+    }
+
+    /// Codegens an `llvm_asm!` block. These blocks have no `nomem` option, so we always
+    /// over-approximate the memory behind their pointer inputs, and behind their indirect outputs.
+    pub fn codegen_llvm_inline_asm(&mut self, asm: &LlvmInlineAsm<'tcx>, loc: Location) -> Stmt {
+        if !self.asm_overapproximation_enabled() {
+            return self
+                .codegen_unimplemented("InlineAsm", Type::empty(), loc.clone(), ASM_ISSUE_URL)
+                .as_stmt(loc);
+        }
+        let inputs: Vec<&Operand<'tcx>> = asm.inputs.iter().map(|(_, op)| op).collect();
+        let mut stmts = self.codegen_havoc_pointees(&inputs, loc.clone());
+        for (place, output) in asm.outputs.iter().zip(asm.asm.outputs.iter()) {
+            if output.is_indirect {
+                let ptr = self.codegen_place(place).goto_expr;
+                stmts.push(self.codegen_havoc_object(ptr, loc.clone()));
+            } else {
+                stmts.push(self.codegen_havoc_place(place));
+            }
+        }
+        Stmt::block(stmts, loc)
+    }
+
+    /// Replaces an `asm!` block by a call to its `model`.
+    fn codegen_asm_model_call(
+        &mut self,
+        model: Instance<'tcx>,
+        inputs: &[&Operand<'tcx>],
+        outputs: &[&Place<'tcx>],
+        span: Span,
+        loc: Location,
+    ) -> Stmt {
+        let input_tys: Vec<Ty<'tcx>> = inputs.iter().map(|op| self.operand_ty(op)).collect();
+        let output_tys: Vec<Ty<'tcx>> = outputs.iter().map(|place| self.place_ty(place)).collect();
+        let ret_ty = match output_tys.as_slice() {
+            [ty] => *ty,
+            tys => self.tcx.mk_tup(tys.iter()),
+        };
+        let sig = self.fn_sig_of_instance(model).unwrap();
+        let sig = self.tcx.normalize_erasing_late_bound_regions(ty::ParamEnv::reveal_all(), sig);
+        if sig.inputs() != input_tys.as_slice() || sig.output() != ret_ty {
+            let input_names: Vec<String> = input_tys.iter().map(|ty| ty.to_string()).collect();
+            self.tcx.sess.span_err(
+                span,
+                &format!(
+                    "the model `{}` of this inline assembly must have the signature `fn({}) -> {}`",
+                    self.readable_instance_name(model),
+                    input_names.join(", "),
+                    ret_ty
+                ),
+            );
+            return Stmt::skip(loc);
+        }
+
+        let func = self.codegen_func_expr(model, None);
+        let mut args = vec![];
+        for (op, ty) in inputs.iter().zip(input_tys) {
+            if self.ignore_var_ty(ty) {
+                continue;
+            }
+            let arg = self.codegen_operand(op);
+            args.push(if ty.is_bool() { arg.cast_to(Type::c_bool()) } else { arg });
+        }
+        let call = func.call(args).with_location(loc.clone());
+        match outputs {
+            [] => call.as_stmt(loc),
+            [place] => self.codegen_expr_to_place(place, call),
+            _ => {
+                let result = self.gen_temp_variable(call.typ().clone(), loc.clone()).to_expr();
+                let mut stmts = vec![Stmt::decl(result.clone(), Some(call), loc.clone())];
+                for (i, place) in outputs.iter().enumerate() {
+                    let value =
+                        result.clone().member(&GotocCtx::tuple_fld_name(i), &self.symbol_table);
+                    stmts.push(self.codegen_expr_to_place(place, value));
+                }
+                Stmt::block(stmts, loc)
+            }
+        }
+    }
+
+    /// Sets the objects that the pointers and references among `inputs` point to to
+    /// nondeterministic values.
+    fn codegen_havoc_pointees(&mut self, inputs: &[&Operand<'tcx>], loc: Location) -> Vec<Stmt> {
+        let mut stmts = vec![];
+        for op in inputs {
+            if matches!(self.operand_ty(op).kind(), ty::RawPtr(..) | ty::Ref(..)) {
+                let ptr = self.codegen_operand(op);
+                stmts.push(self.codegen_havoc_object(ptr, loc.clone()));
+            }
+        }
+        stmts
+    }
+
+    /// Sets the whole object that `ptr` points to to a nondeterministic value.
+    pub fn codegen_havoc_object(&mut self, ptr: Expr, loc: Location) -> Stmt {
+        BuiltinFn::CProverHavocObject
+            .call(vec![ptr.cast_to(Type::void_pointer())], loc.clone())
+            .as_stmt(loc)
+    }
+
+    fn codegen_havoc_place(&mut self, place: &Place<'tcx>) -> Stmt {
+        let typ = self.codegen_ty(self.place_ty(place));
+        self.codegen_expr_to_place(place, typ.nondet())
+    }
+}
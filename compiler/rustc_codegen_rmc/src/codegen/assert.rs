@@ -0,0 +1,45 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This file contains the code that generates the assertions checked by RMC.
+//! Every assertion is tagged with the class of property it checks (see
+//! `cbmc::goto_program::PropertyClass`).
+
+use crate::GotocCtx;
+use cbmc::goto_program::{Expr, Location, PropertyClass, Stmt};
+use rustc_middle::mir::AssertKind;
+
+/// The class of the check made by a MIR `Assert` terminator.
+pub fn property_class_of_assert_kind<O>(kind: &AssertKind<O>) -> PropertyClass {
+    match kind {
+        AssertKind::BoundsCheck { .. } => PropertyClass::IndexOutOfBounds,
+        AssertKind::Overflow(..)
+        | AssertKind::OverflowNeg(..)
+        | AssertKind::DivisionByZero(..)
+        | AssertKind::RemainderByZero(..) => PropertyClass::ArithmeticOverflow,
+        AssertKind::ResumedAfterReturn(..) | AssertKind::ResumedAfterPanic(..) => {
+            PropertyClass::Assertion
+        }
+    }
+}
+
+impl<'tcx> GotocCtx<'tcx> {
+    pub fn codegen_assert(
+        &self,
+        cond: Expr,
+        property_class: PropertyClass,
+        msg: &str,
+        loc: Location,
+    ) -> Stmt {
+        Stmt::assert(cond, property_class, msg, loc)
+    }
+
+    pub fn codegen_assert_false(
+        &self,
+        property_class: PropertyClass,
+        msg: &str,
+        loc: Location,
+    ) -> Stmt {
+        Stmt::assert_false(property_class, msg, loc)
+    }
+}
@@ -3,9 +3,10 @@
 //! this module defines functions which impose data invariant on generated data types.
 
 use crate::GotocCtx;
-use cbmc::goto_program::{Expr, Location, Stmt, Symbol, Type};
+use cbmc::goto_program::{Expr, Location, PropertyClass, Stmt, Symbol, Type};
 use cbmc::NO_PRETTY_NAME;
 use rustc_middle::mir::interpret::{ConstValue, Scalar};
+use rustc_middle::mir::{Operand, Rvalue, Statement, StatementKind};
 use rustc_middle::ty;
 use rustc_middle::ty::layout::LayoutOf;
 use rustc_middle::ty::ScalarInt;
@@ -35,9 +36,9 @@ fn fold_invariants_or(iv: Vec<Expr>) -> Expr {
 }
 
 impl<'tcx> GotocCtx<'tcx> {
-    fn invariant_name(&mut self, t: Ty<'tcx>) -> String {
+    fn invariant_name(&mut self, t: Ty<'tcx>, validity: bool) -> String {
         let ty_name = self.ty_mangled_name(t);
-        let fname = format!("{}:invariant", ty_name);
+        let fname = format!("{}:{}", ty_name, if validity { "validity" } else { "invariant" });
         fname
     }
 
@@ -81,7 +82,14 @@ fn bound_true_false(&mut self, fname: String, t: Ty<'tcx>) -> Option<Expr> {
     /// for a type t, this function generates
     ///     bool t:invariant(t *x);
     pub fn codegen_assumption(&mut self, t: Ty<'tcx>) -> Option<Expr> {
-        let fname = self.invariant_name(t);
+        self.codegen_invariant(t, false)
+    }
+
+    /// The data invariant of `t` (see `codegen_assumption`), or, if `validity`, the part of it
+    /// that any value of type `t` must satisfy (see `codegen_validity_check`): a raw pointer may
+    /// be dangling, so neither raw pointers nor the values they point to are constrained.
+    fn codegen_invariant(&mut self, t: Ty<'tcx>, validity: bool) -> Option<Expr> {
+        let fname = self.invariant_name(t, validity);
         match t.kind() {
             ty::Bool => self.bound_true_false(fname, t),
             ty::Int(_) | ty::Uint(_) | ty::Float(_) => None,
@@ -98,7 +106,7 @@ pub fn codegen_assumption(&mut self, t: Ty<'tcx>) -> Option<Expr> {
                 else if def.is_struct() {
                     let variant = &def.variants.raw[0];
                     self.ensure(&fname, |ctx, _| {
-                        ctx.codegen_assumption_struct(&fname, t, variant, subst)
+                        ctx.codegen_assumption_struct(&fname, t, variant, subst, validity)
                     });
                     self.find_function(&fname)
                 } else {
@@ -107,7 +115,7 @@ pub fn codegen_assumption(&mut self, t: Ty<'tcx>) -> Option<Expr> {
                         None
                     } else {
                         self.ensure(&fname, |ctx, _| {
-                            ctx.codegen_assumption_enum(&fname, t, def, subst)
+                            ctx.codegen_assumption_enum(&fname, t, def, subst, validity)
                         });
                         self.find_function(&fname)
                     }
@@ -115,29 +123,35 @@ pub fn codegen_assumption(&mut self, t: Ty<'tcx>) -> Option<Expr> {
             }
             ty::Foreign(_) => unreachable!("cannot generate assumptions for foreign types"),
             ty::Array(et, c) => {
-                self.ensure(&fname, |ctx, _| ctx.codegen_assumption_array(&fname, t, et, c));
+                self.ensure(&fname, |ctx, _| {
+                    ctx.codegen_assumption_array(&fname, t, et, c, validity)
+                });
                 self.find_function(&fname)
             }
             ty::Str | ty::Slice(_) => unreachable!("should be handled by Ref or RawPtr"),
-            ty::RawPtr(rt) => self.codegen_assumption_ref_ptr(&fname, t, rt.ty, false),
-            ty::Ref(_, rt, _) => self.codegen_assumption_ref_ptr(&fname, t, rt, true),
+            ty::RawPtr(_) if validity => None,
+            ty::RawPtr(rt) => self.codegen_assumption_ref_ptr(&fname, t, rt.ty, false, validity),
+            ty::Ref(_, rt, _) => self.codegen_assumption_ref_ptr(&fname, t, rt, true, validity),
             ty::FnDef(_, _) | ty::FnPtr(_) => None,
             ty::Dynamic(_, _) => unreachable!(),
             ty::Closure(_, _) => None,
-            ty::Generator(_, _, _) => unimplemented!(),
+            // like closures, generators are only created by the code that defines them
+            ty::Generator(_, _, _) => None,
             ty::GeneratorWitness(_) => unimplemented!(),
             ty::Never => None,
             ty::Tuple(ts) => {
                 if ts.is_empty() {
                     None
                 } else {
-                    self.ensure(&fname, |ctx, _| ctx.codegen_assumption_tuple(&fname, t, ts));
+                    self.ensure(&fname, |ctx, _| {
+                        ctx.codegen_assumption_tuple(&fname, t, ts, validity)
+                    });
                     self.find_function(&fname)
                 }
             }
             ty::Projection(_) | ty::Opaque(_, _) => {
                 let normalized = self.tcx.normalize_erasing_regions(ty::ParamEnv::reveal_all(), t);
-                self.codegen_assumption(normalized)
+                self.codegen_invariant(normalized, validity)
             }
             ty::Bound(_, _) | ty::Param(_) => unreachable!("monomorphization bug"),
             ty::Placeholder(_) | ty::Infer(_) | ty::Error(_) => {
@@ -146,20 +160,71 @@ pub fn codegen_assumption(&mut self, t: Ty<'tcx>) -> Option<Expr> {
         }
     }
 
+    /// With `-Z validity-checks`, asserts that `value`, of type `t`, is valid for `t`, i.e.
+    /// satisfies the data invariant of `t` (see `codegen_assumption`) except for raw pointers.
+    /// This is used where a value is produced from raw bytes, which is undefined behavior unless
+    /// the bytes are valid for the type.
+    pub fn codegen_validity_check(
+        &mut self,
+        value: Expr,
+        t: Ty<'tcx>,
+        source: &str,
+        loc: Location,
+    ) -> Option<Stmt> {
+        if !self.tcx.sess.opts.debugging_opts.validity_checks {
+            return None;
+        }
+        let f = self.codegen_invariant(t, true)?;
+        Some(self.codegen_assert(
+            f.call(vec![value.address_of()]),
+            PropertyClass::UndefinedBehavior,
+            &format!("{}: value is valid for type {}", source, t),
+            loc,
+        ))
+    }
+
+    /// Reading a union field reinterprets the bytes of the union, so with `-Z validity-checks`
+    /// the value read must be valid for the type of the field.
+    /// `MaybeUninit::assume_init` reads a union field, so it is checked as well.
+    pub fn codegen_union_read_validity_check(
+        &mut self,
+        stmt: &Statement<'tcx>,
+        loc: Location,
+    ) -> Option<Stmt> {
+        let (lhs, rhs) = match &stmt.kind {
+            StatementKind::Assign(box (
+                lhs,
+                Rvalue::Use(Operand::Copy(rhs) | Operand::Move(rhs)),
+            )) => (lhs, rhs),
+            _ => return None,
+        };
+        if !self.place_reads_union_field(rhs) {
+            return None;
+        }
+        let lhs_ty = self.place_ty(lhs);
+        if self.layout_of(lhs_ty).is_zst() {
+            return None;
+        }
+        let value = self.codegen_place(lhs).goto_expr;
+        self.codegen_validity_check(value, lhs_ty, "union field read", loc)
+    }
+
     /// * fname - function name
     /// * t - type of a reference
     /// * rt - type of the referenced term
     /// * is_ref - whether we are handling references or pointers
+    /// * validity - whether we are generating a validity check (see `codegen_invariant`)
     fn codegen_assumption_ref_ptr(
         &mut self,
         fname: &str,
         t: Ty<'tcx>,
         rt: Ty<'tcx>,
         is_ref: bool,
+        validity: bool,
     ) -> Option<Expr> {
         match rt.kind() {
             ty::Slice(e) => {
-                let ef = self.codegen_assumption(e);
+                let ef = self.codegen_invariant(e, validity);
                 if ef.is_none() && !is_ref {
                     None
                 } else {
@@ -179,13 +244,16 @@ fn codegen_assumption_ref_ptr(
                     self.find_function(fname)
                 }
             }
+            // We do not know the type of a trait object, nor the layout of a foreign type, so we
+            // cannot check that these values are valid.
+            ty::Dynamic(_, _) | ty::Foreign(_) if validity => None,
             ty::Dynamic(_, _) => unimplemented!(),
             ty::Projection(_) | ty::Opaque(_, _) => {
                 let normalized = self.tcx.normalize_erasing_regions(ty::ParamEnv::reveal_all(), t);
-                self.codegen_assumption_ref_ptr(fname, t, normalized, is_ref)
+                self.codegen_assumption_ref_ptr(fname, t, normalized, is_ref, validity)
             }
             _ => {
-                let ef = self.codegen_assumption(rt);
+                let ef = self.codegen_invariant(rt, validity);
                 if ef.is_none() && !is_ref {
                     None
                 } else {
@@ -277,11 +345,12 @@ fn codegen_assumption_struct_invariant(
         ptr: Expr,
         variant: &ty::VariantDef,
         subst: ty::subst::SubstsRef<'tcx>,
+        validity: bool,
     ) -> Vec<Expr> {
         let mut invariants = vec![];
         for fd in &variant.fields {
             let t = fd.ty(self.tcx, subst);
-            if let Some(f) = self.codegen_assumption(t) {
+            if let Some(f) = self.codegen_invariant(t, validity) {
                 let fp = ptr
                     .clone()
                     .dereference()
@@ -301,9 +370,11 @@ fn codegen_assumption_struct(
         t: Ty<'tcx>,
         variant: &'tcx ty::VariantDef,
         subst: ty::subst::SubstsRef<'tcx>,
+        validity: bool,
     ) -> Symbol {
         self.codegen_assumption_genfunc(fname, t, |tcx, ptr, body| {
-            let invariants = tcx.codegen_assumption_struct_invariant(ptr, variant, subst);
+            let invariants =
+                tcx.codegen_assumption_struct_invariant(ptr, variant, subst, validity);
             body.push(fold_invariants(invariants).ret(Location::none()));
         })
     }
@@ -314,20 +385,24 @@ fn codegen_assumption_enum(
         t: Ty<'tcx>,
         def: &'tcx ty::AdtDef,
         subst: ty::subst::SubstsRef<'tcx>,
+        validity: bool,
     ) -> Symbol {
         match def.variants.len() {
             0 => unreachable!(),
-            1 => self.codegen_assumption_enum_single_variant(fname, t, &def.variants.raw[0], subst),
+            1 => {
+                let variant = &def.variants.raw[0];
+                self.codegen_assumption_enum_single_variant(fname, t, variant, subst, validity)
+            }
             _ => {
                 let layout = self.layout_of(t);
                 match &layout.variants {
                     Variants::Single { .. } => unreachable!(),
                     Variants::Multiple { tag_encoding, .. } => match tag_encoding {
                         TagEncoding::Direct => {
-                            self.codegen_assumption_enum_direct(fname, t, def, subst)
+                            self.codegen_assumption_enum_direct(fname, t, def, subst, validity)
                         }
                         TagEncoding::Niche { .. } => {
-                            self.codegen_assumption_enum_niche(fname, t, def, subst)
+                            self.codegen_assumption_enum_niche(fname, t, def, subst, validity)
                         }
                     },
                 }
@@ -341,9 +416,11 @@ fn codegen_assumption_enum_single_variant(
         t: Ty<'tcx>,
         variant: &ty::VariantDef,
         subst: ty::subst::SubstsRef<'tcx>,
+        validity: bool,
     ) -> Symbol {
         self.codegen_assumption_genfunc(fname, t, |tcx, ptr, body| {
-            let invariants = tcx.codegen_assumption_struct_invariant(ptr, variant, subst);
+            let invariants =
+                tcx.codegen_assumption_struct_invariant(ptr, variant, subst, validity);
             body.push(fold_invariants(invariants).ret(Location::none()));
         })
     }
@@ -354,6 +431,7 @@ fn codegen_assumption_enum_niche(
         t: Ty<'tcx>,
         def: &'tcx ty::AdtDef,
         subst: ty::subst::SubstsRef<'tcx>,
+        validity: bool,
     ) -> Symbol {
         let layout = self.layout_of(t);
         let (tag, dataful_variant, niche_variants, niche_start) = match &layout.variants {
@@ -388,7 +466,8 @@ fn codegen_assumption_enum_niche(
                 }
             }
 
-            let data_invar = tcx.codegen_assumption_struct_invariant(ptr, variant, subst);
+            let data_invar =
+                tcx.codegen_assumption_struct_invariant(ptr, variant, subst, validity);
             invariants.push(fold_invariants(data_invar));
             body.push(fold_invariants_or(invariants).ret(Location::none()));
         })
@@ -401,6 +480,7 @@ fn codegen_assumption_enum_direct(
         t: Ty<'tcx>,
         def: &'tcx ty::AdtDef,
         subst: ty::subst::SubstsRef<'tcx>,
+        validity: bool,
     ) -> Symbol {
         self.codegen_assumption_genfunc(fname, t, |tcx, ptr, body| {
             // here we have enum
@@ -477,7 +557,7 @@ fn codegen_assumption_enum_direct(
                 let mut case_invariants = vec![];
                 for f in &variant.fields {
                     let ft = f.ty(tcx.tcx, subst);
-                    if let Some(fi) = tcx.codegen_assumption(ft) {
+                    if let Some(fi) = tcx.codegen_invariant(ft, validity) {
                         let fname = f.ident.name.to_string();
                         case_invariants.push(fi.call(vec![
                             var_struct.clone().member(&fname, &tcx.symbol_table).address_of(),
@@ -512,9 +592,10 @@ fn codegen_assumption_array(
         t: Ty<'tcx>,
         et: Ty<'tcx>,
         c: &'tcx ty::Const<'tcx>,
+        validity: bool,
     ) -> Symbol {
         self.codegen_assumption_genfunc(fname, t, |tcx, ptr, body| {
-            if let Some(f) = tcx.codegen_assumption(et) {
+            if let Some(f) = tcx.codegen_invariant(et, validity) {
                 let idx = tcx.gen_function_local_variable(2, &fname, Type::size_t());
                 body.push(Stmt::decl(idx.to_expr(), Some(Type::size_t().zero()), Location::none()));
                 let idxe = idx.to_expr();
@@ -551,12 +632,13 @@ fn codegen_assumption_tuple(
         fname: &str,
         t: Ty<'tcx>,
         ts: ty::subst::SubstsRef<'tcx>,
+        validity: bool,
     ) -> Symbol {
         self.codegen_assumption_genfunc(fname, t, |tcx, ptr, body| {
             let mut invariants = vec![];
             for (i, t) in ts.iter().enumerate() {
                 let t = t.expect_ty();
-                if let Some(f) = tcx.codegen_assumption(t) {
+                if let Some(f) = tcx.codegen_invariant(t, validity) {
                     let field = ptr
                         .clone()
                         .dereference()
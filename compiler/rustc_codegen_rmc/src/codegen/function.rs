@@ -3,10 +3,16 @@
 
 //! This file contains functions related to codegenning MIR functions into gotoc
 
+use crate::context::{ContractMetadata, HarnessMetadata, LoopBoundMetadata, StubMetadata};
+use crate::utils::{resolve_fn_path, resolve_sibling_fn};
 use crate::GotocCtx;
-use cbmc::goto_program::{Expr, Stmt, Symbol};
+use cbmc::goto_program::{Expr, PropertyClass, Stmt, Symbol};
+use rustc_ast::ast;
+use rustc_hir::def_id::DefId;
 use rustc_middle::mir::{HasLocalDecls, Local};
-use rustc_middle::ty::{self, Instance, TyS};
+use rustc_middle::ty::{self, Instance, InstanceDef, TyCtxt, TyS};
+use rustc_span::Span;
+use std::convert::TryFrom;
 use tracing::{debug, warn};
 
 /// Utility to skip functions that can't currently be successfully codgenned.
@@ -15,16 +21,10 @@ fn should_skip_current_fn(&self) -> bool {
         match self.current_fn().readable_name() {
             // https://github.com/model-checking/rmc/issues/202
             "fmt::ArgumentV1::<'a>::as_usize" => true,
-            // https://github.com/model-checking/rmc/issues/204
-            name if name.ends_with("__getit") => true,
             // https://github.com/model-checking/rmc/issues/281
             name if name.starts_with("bridge::client") => true,
             // https://github.com/model-checking/rmc/issues/282
             "bridge::closure::Closure::<'a, A, R>::call" => true,
-            // Generators
-            name if name.starts_with("<std::future::from_generator::GenFuture<T>") => true,
-            name if name.contains("reusable_box::ReusableBoxFuture") => true,
-            "tokio::sync::Semaphore::acquire_owned::{closure#0}" => true,
             _ => false,
         }
     }
@@ -69,7 +69,9 @@ pub fn codegen_function(&mut self, instance: Instance<'tcx>) {
         } else if self.should_skip_current_fn() {
             debug!("Skipping function {}", self.current_fn().readable_name());
             let loc = self.codegen_span(&self.current_fn().mir().span);
-            let body = Stmt::assert_false(
+            self.record_unsupported_construct("skipped function", None, &loc, None);
+            let body = self.codegen_assert_false(
+                PropertyClass::UnsupportedConstruct,
                 &format!(
                     "The function {} is not currently supported by RMC",
                     self.current_fn().readable_name()
@@ -91,6 +93,9 @@ pub fn codegen_function(&mut self, instance: Instance<'tcx>) {
             self.current_fn_mut().set_labels(labels);
             self.codegen_function_prelude();
             self.codegen_declare_variables();
+            for claim in self.codegen_init_claim_args() {
+                self.current_fn_mut().push_onto_block(claim);
+            }
 
             mir.basic_blocks().iter_enumerated().for_each(|(bb, bbd)| self.codegen_block(bb, bbd));
 
@@ -98,6 +103,8 @@ pub fn codegen_function(&mut self, instance: Instance<'tcx>) {
             let stmts = self.current_fn_mut().extract_block();
             let body = Stmt::block(stmts, loc);
             self.symbol_table.update_fn_declaration_with_definition(&name, body);
+
+            self.handle_rmctool_attributes();
         }
         self.reset_current_fn();
     }
@@ -197,6 +204,358 @@ pub fn declare_function(&mut self, instance: Instance<'tcx>) {
                 ctx.codegen_span(&mir.span),
             )
         });
+        // Models and stubs must be known before we codegen the code they replace.
+        self.handle_rmctool_asm_model();
+        self.handle_rmctool_stubs();
         self.reset_current_fn();
     }
 }
+
+/// If the attribute is named `rmctool::name`, this extracts `name`
+fn rmctool_attr_name(attr: &ast::Attribute) -> Option<String> {
+    match &attr.kind {
+        ast::AttrKind::Normal(ast::AttrItem { path: ast::Path { segments, .. }, .. }, _)
+            if segments.len() == 2 && &*segments[0].ident.as_str() == "rmctool" =>
+        {
+            Some(segments[1].ident.as_str().to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Extracts the names in `#[rmctool::name(ident_1, ..., ident_n)]`, if all its arguments are names
+fn rmctool_attr_idents(attr: &ast::Attribute) -> Option<Vec<String>> {
+    attr.meta_item_list()?.iter().map(|arg| arg.ident().map(|ident| ident.to_string())).collect()
+}
+
+/// Extracts the paths in `#[rmctool::name(path_1, ..., path_n)]`, if all its arguments are paths
+fn rmctool_attr_paths(attr: &ast::Attribute) -> Option<Vec<Vec<String>>> {
+    attr.meta_item_list()?
+        .iter()
+        .map(|arg| match arg.meta_item() {
+            Some(meta) if meta.is_word() => {
+                Some(meta.path.segments.iter().map(|segment| segment.ident.to_string()).collect())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether `def_id` is a function that we codegen although nothing calls it: the proof harnesses,
+/// the functions that `rmc_macros` generates for a function with a contract (see
+/// `handle_rmctool_contract`), and the models of inline assembly (see `handle_rmctool_asm_model`).
+pub fn is_rmctool_root(tcx: TyCtxt<'_>, def_id: DefId) -> bool {
+    tcx.get_attrs(def_id).iter().any(|attr| {
+        matches!(
+            rmctool_attr_name(attr).as_deref(),
+            Some("proof" | "contract_companion" | "asm_model")
+        )
+    })
+}
+
+/// Handling of the attributes generated by the `rmc` library macros
+impl<'tcx> GotocCtx<'tcx> {
+    /// This updates the goto context with any information that should be accumulated from a
+    /// function's attributes.
+    ///
+    /// Currently, this is proof harness annotations,
+    /// i.e. `#[rmc::proof]` (which `rmc_macros` translates to `#[rmctool::proof]` for us to handle here)
+    /// along with the `#[rmc::unwind(N)]` and `#[rmc::proof_for_contract(f)]` attached to them,
+    /// the functions with a contract, and the loop bounds of other functions.
+    fn handle_rmctool_attributes(&mut self) {
+        let instance = self.current_fn().instance();
+        // Shims share the `DefId` of the function they wrap; only record the function itself.
+        if !matches!(instance.def, InstanceDef::Item(..)) {
+            return;
+        }
+        let mut proof_attr = None;
+        let mut unwind_attr = None;
+        let mut for_contract_attr = None;
+        for attr in self.tcx.get_attrs(instance.def_id()) {
+            match rmctool_attr_name(attr).as_deref() {
+                Some("proof") => proof_attr = Some(attr),
+                Some("unwind") => unwind_attr = Some(attr),
+                Some("proof_for_contract") => for_contract_attr = Some(attr),
+                Some("contract") => self.handle_rmctool_contract(attr),
+                // Recorded when the function is declared, see `declare_function`
+                Some("asm_model") | Some("stub") => {}
+                // See `is_rmctool_root`
+                Some("contract_companion") => {}
+                Some(name) => self
+                    .tcx
+                    .sess
+                    .span_err(attr.span, &format!("unsupported RMC attribute `rmctool::{}`", name)),
+                None => {}
+            }
+        }
+
+        match proof_attr {
+            Some(proof) => self.handle_rmctool_proof(proof, unwind_attr, for_contract_attr),
+            None => {
+                if let Some(unwind) = unwind_attr {
+                    self.handle_rmctool_loop_bound(unwind);
+                }
+            }
+        }
+    }
+
+    /// Update `self` (the goto context) to add the current function as a listed proof harness
+    fn handle_rmctool_proof(
+        &mut self,
+        attr: &ast::Attribute,
+        unwind_attr: Option<&ast::Attribute>,
+        for_contract_attr: Option<&ast::Attribute>,
+    ) {
+        if self.current_fn().mir().arg_count != 0 {
+            self.tcx.sess.span_err(attr.span, "a proof harness cannot take any arguments");
+            return;
+        }
+        let loc = self.codegen_span(&self.current_fn().mir().span);
+        let harness = HarnessMetadata {
+            pretty_name: self.current_fn().readable_name().to_string(),
+            mangled_name: self.current_fn().name(),
+            // Harnesses generated by macros may not map to a source location.
+            original_file: loc.filename(),
+            original_line: loc.line(),
+            unwind_value: unwind_attr.and_then(|attr| self.handle_rmctool_unwind(attr)),
+            for_contract: for_contract_attr.and_then(|attr| self.handle_rmctool_for_contract(attr)),
+            // Filled in once the whole crate is codegenned, see `record_stub_target`
+            stubs: vec![],
+        };
+        self.proof_harnesses.push(harness);
+    }
+
+    /// Update `self` (the goto context) to bound the loops of the current function, which is not a
+    /// proof harness, with `#[rmctool::unwind(N)]`
+    fn handle_rmctool_loop_bound(&mut self, attr: &ast::Attribute) {
+        if let Some(unwind_value) = self.handle_rmctool_unwind(attr) {
+            let bound = LoopBoundMetadata {
+                pretty_name: self.current_fn().readable_name().to_string(),
+                mangled_name: self.current_fn().name(),
+                unwind_value,
+            };
+            self.loop_bounds.push(bound);
+        }
+    }
+
+    /// Extracts the symbol of the function `f` whose contract the current proof harness checks,
+    /// from `#[rmctool::proof_for_contract(f)]`
+    fn handle_rmctool_for_contract(&self, attr: &ast::Attribute) -> Option<String> {
+        let def_id = self.current_fn().instance().def_id();
+        let resolved = match rmctool_attr_paths(attr).as_deref() {
+            Some([path]) if def_id.is_local() => {
+                let module = self.tcx.parent_module_from_def_id(def_id.expect_local());
+                resolve_fn_path(self.tcx, module, path)
+            }
+            _ => Err("expected the path of a function".to_string()),
+        };
+        match resolved {
+            Ok(def_id) if !self.tcx.generics_of(def_id).requires_monomorphization(self.tcx) => {
+                Some(self.symbol_name(Instance::mono(self.tcx, def_id)))
+            }
+            Ok(_) => {
+                self.tcx
+                    .sess
+                    .span_err(attr.span, "the contract of a generic function cannot be checked");
+                None
+            }
+            Err(msg) => {
+                self.tcx.sess.span_err(
+                    attr.span,
+                    &format!("invalid `#[rmc::proof_for_contract]` attribute: {}", msg),
+                );
+                None
+            }
+        }
+    }
+
+    /// Update `self` (the goto context) to add the current function as a function with a contract.
+    /// `#[rmctool::contract(check, replace)]` names the functions generated by `rmc_macros` next to
+    /// the current one that respectively check the contract, and replace calls by the contract.
+    fn handle_rmctool_contract(&mut self, attr: &ast::Attribute) {
+        let def_id = self.current_fn().instance().def_id();
+        let companions = match rmctool_attr_idents(attr).as_deref() {
+            Some([check, replace]) => resolve_sibling_fn(self.tcx, def_id, check)
+                .zip(resolve_sibling_fn(self.tcx, def_id, replace)),
+            _ => None,
+        };
+        let (check, replace) = match companions {
+            Some(companions) => companions,
+            None => {
+                self.tcx.sess.span_err(attr.span, "malformed `rmctool::contract` attribute");
+                return;
+            }
+        };
+        if self.tcx.generics_of(def_id).requires_monomorphization(self.tcx) {
+            self.tcx.sess.span_err(attr.span, "generic functions cannot have a contract");
+            return;
+        }
+        let contract = ContractMetadata {
+            pretty_name: self.current_fn().readable_name().to_string(),
+            mangled_name: self.current_fn().name(),
+            check_name: self.symbol_name(Instance::mono(self.tcx, check)),
+            replace_name: self.symbol_name(Instance::mono(self.tcx, replace)),
+        };
+        self.contracts.push(contract);
+    }
+
+    /// Update `self` (the goto context) to add the current function as the model of the inline
+    /// assembly in the function at the path given by `#[rmctool::asm_model(f)]`.
+    fn handle_rmctool_asm_model(&mut self) {
+        let instance = self.current_fn().instance();
+        let def_id = match instance.def {
+            InstanceDef::Item(def) => def.did,
+            _ => return,
+        };
+        for attr in self.tcx.get_attrs(def_id) {
+            if rmctool_attr_name(attr).as_deref() != Some("asm_model") {
+                continue;
+            }
+            let resolved = match (rmctool_attr_paths(attr).as_deref(), def_id.as_local()) {
+                (Some([path]), Some(local)) => {
+                    let module = self.tcx.parent_module_from_def_id(local);
+                    resolve_fn_path(self.tcx, module, path)
+                }
+                _ => Err("expected the path of a function".to_string()),
+            };
+            match resolved {
+                Ok(target) => match self.asm_models.insert(target, instance) {
+                    Some(other) if other != instance => self.tcx.sess.span_err(
+                        attr.span,
+                        &format!(
+                            "the inline assembly of `{}` has several models",
+                            self.tcx.def_path_str(target)
+                        ),
+                    ),
+                    _ => {}
+                },
+                Err(msg) => self.tcx.sess.span_err(
+                    attr.span,
+                    &format!("invalid `#[rmc::asm_model]` attribute: {}", msg),
+                ),
+            }
+        }
+    }
+
+    /// Update `self` (the goto context) with the functions that the current function, a proof
+    /// harness, replaces with `#[rmctool::stub(target, replacement)]`.
+    fn handle_rmctool_stubs(&mut self) {
+        let instance = self.current_fn().instance();
+        let def_id = match instance.def {
+            InstanceDef::Item(def) => def.did,
+            _ => return,
+        };
+        let attrs = self.tcx.get_attrs(def_id);
+        let stub_attrs =
+            attrs.iter().filter(|attr| rmctool_attr_name(attr).as_deref() == Some("stub"));
+        let is_harness =
+            attrs.iter().any(|attr| rmctool_attr_name(attr).as_deref() == Some("proof"));
+        for attr in stub_attrs {
+            let module = match def_id.as_local() {
+                Some(local) if is_harness => self.tcx.parent_module_from_def_id(local),
+                _ => {
+                    self.tcx.sess.span_err(
+                        attr.span,
+                        "the `#[rmc::stub]` attribute can only be used on proof harnesses",
+                    );
+                    continue;
+                }
+            };
+            let resolved = match rmctool_attr_paths(attr).as_deref() {
+                Some([target, replacement]) => {
+                    resolve_fn_path(self.tcx, module, target).and_then(|target| {
+                        resolve_fn_path(self.tcx, module, replacement)
+                            .map(|replacement| (target, replacement))
+                    })
+                }
+                _ => Err("expected the paths of a function and of its replacement".to_string()),
+            };
+            match resolved {
+                Ok((target, replacement)) => self.add_stub(target, replacement, attr.span),
+                Err(msg) => self
+                    .tcx
+                    .sess
+                    .span_err(attr.span, &format!("invalid `#[rmc::stub]` attribute: {}", msg)),
+            }
+        }
+    }
+
+    /// Makes the current proof harness replace the calls to `target` by calls to `replacement`.
+    fn add_stub(&mut self, target: DefId, replacement: DefId, span: Span) {
+        if self.tcx.generics_of(replacement).requires_monomorphization(self.tcx) {
+            self.tcx.sess.span_err(span, "the replacement of a stubbed function cannot be generic");
+            return;
+        }
+        let replacement = Instance::mono(self.tcx, replacement);
+        let harness = self.current_fn().name();
+        self.stub_targets.entry(target).or_default().push((harness, replacement));
+        if !self.tcx.generics_of(target).requires_monomorphization(self.tcx) {
+            // The symbol of a function that is not generic is the same in every crate, so we can
+            // replace its calls from other crates, which we do not see.
+            let target = Instance::mono(self.tcx, target);
+            if self.fn_sig_erased(target) != self.fn_sig_erased(replacement) {
+                self.tcx.sess.span_err(
+                    span,
+                    &format!(
+                        "`{}` does not have the same signature as `{}`",
+                        self.readable_instance_name(replacement),
+                        self.readable_instance_name(target)
+                    ),
+                );
+                return;
+            }
+            self.record_stub_target(target);
+        }
+    }
+
+    /// Makes the proof harnesses that stub the function of `instance` replace the calls to
+    /// `instance` by calls to their replacement, if it has the same signature. Calls to a generic
+    /// function are replaced for the instances of the function that we codegen.
+    pub fn record_stub_target(&mut self, instance: Instance<'tcx>) {
+        if !matches!(instance.def, InstanceDef::Item(..)) {
+            return;
+        }
+        let replacements = match self.stub_targets.get(&instance.def_id()) {
+            Some(replacements) => replacements.clone(),
+            None => return,
+        };
+        let original_name = self.symbol_name(instance);
+        for (harness, replacement) in replacements {
+            if self.fn_sig_erased(instance) != self.fn_sig_erased(replacement) {
+                continue;
+            }
+            let stub = StubMetadata {
+                original_name: original_name.clone(),
+                replacement_name: self.symbol_name(replacement),
+            };
+            let stubs = self.stubs.entry(harness).or_default();
+            if !stubs.contains(&stub) {
+                stubs.push(stub);
+            }
+        }
+    }
+
+    /// The signature of `instance`, without its late-bound regions.
+    fn fn_sig_erased(&self, instance: Instance<'tcx>) -> ty::FnSig<'tcx> {
+        let sig = self.fn_sig_of_instance(instance).unwrap();
+        self.tcx.normalize_erasing_late_bound_regions(ty::ParamEnv::reveal_all(), sig)
+    }
+
+    /// Extracts the bound `N` from `#[rmctool::unwind(N)]`
+    fn handle_rmctool_unwind(&self, attr: &ast::Attribute) -> Option<u32> {
+        let value = match attr.meta_item_list().as_deref() {
+            Some([arg]) => match arg.literal().map(|lit| &lit.kind) {
+                Some(ast::LitKind::Int(value, _)) => u32::try_from(*value).ok(),
+                _ => None,
+            },
+            _ => None,
+        };
+        if value.is_none() {
+            self.tcx.sess.span_err(
+                attr.span,
+                "the `#[rmc::unwind]` attribute expects a single unsigned integer argument",
+            );
+        }
+        value
+    }
+}
@@ -1,10 +1,11 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 //! this module handles intrinsics
-use tracing::{debug, warn};
+use tracing::debug;
 
+use super::typ::pointee_type;
 use crate::GotocCtx;
-use cbmc::goto_program::{BuiltinFn, Expr, Location, Stmt, Type};
+use cbmc::goto_program::{BuiltinFn, Expr, Location, PropertyClass, Stmt, Type};
 use rustc_middle::mir::Place;
 use rustc_middle::ty::layout::LayoutOf;
 use rustc_middle::ty::Instance;
@@ -54,6 +55,7 @@ pub fn codegen_intrinsic(
         /// https://doc.rust-lang.org/core/intrinsics/fn.copy.html
         /// https://doc.rust-lang.org/core/intrinsics/fn.copy_nonoverlapping.html
         /// An intrinsic that translates directly into either memmove (for copy) or memcpy (copy_nonoverlapping)
+        /// Both pointers must be aligned, and the source and destination of memcpy must not overlap.
         macro_rules! codegen_intrinsic_copy {
             ($f:ident) => {{
                 let src = fargs.remove(0).cast_to(Type::void_pointer());
@@ -71,7 +73,33 @@ macro_rules! codegen_intrinsic_copy {
                     }
                 };
                 let n = sz.mul(count);
-                let call_memcopy = BuiltinFn::$f.call(vec![dst.clone(), src, n.clone()], loc);
+                let pointee = self.monomorphize(instance.substs.type_at(0));
+                let mut checks = vec![];
+                for ptr in [&src, &dst] {
+                    checks.extend(self.codegen_alignment_check(
+                        ptr.clone(),
+                        pointee,
+                        &format!("{}: pointer is aligned", intrinsic),
+                        loc.clone(),
+                    ));
+                }
+                if let BuiltinFn::Memcpy = BuiltinFn::$f {
+                    checks.push(self.codegen_nonoverlapping_check(
+                        intrinsic,
+                        src.clone(),
+                        dst.clone(),
+                        n.clone(),
+                        loc.clone(),
+                    ));
+                }
+                checks.push(self.codegen_copy_init(
+                    src.clone(),
+                    dst.clone(),
+                    n.clone(),
+                    loc.clone(),
+                ));
+                let call_memcopy =
+                    BuiltinFn::$f.call(vec![dst.clone(), src, n.clone()], loc.clone());
 
                 // The C implementation of memcpy does not allow an invalid pointer for
                 // the src/dst, but the LLVM implementation specifies that a copy with
@@ -80,7 +108,8 @@ macro_rules! codegen_intrinsic_copy {
                 // string unless we codegen this zero check.
                 // https://llvm.org/docs/LangRef.html#llvm-memcpy-intrinsic
                 let copy_if_nontrivial = n.is_zero().ternary(dst, call_memcopy);
-                self.codegen_expr_to_place(p, copy_if_nontrivial)
+                checks.push(self.codegen_expr_to_place(p, copy_if_nontrivial));
+                Stmt::block(checks, loc)
             }};
         }
 
@@ -130,6 +159,27 @@ macro_rules! codegen_op_with_overflow {
             }};
         }
 
+        // Intrinsics of the form unchecked_*, for which an overflow is undefined behavior
+        macro_rules! codegen_unchecked_op {
+            ($f:ident) => {{
+                let a = fargs.remove(0);
+                let b = fargs.remove(0);
+                let res = a.$f(b);
+                Stmt::block(
+                    vec![
+                        self.codegen_assert(
+                            res.overflowed.not(),
+                            PropertyClass::UndefinedBehavior,
+                            &format!("{} does not overflow", intrinsic),
+                            loc.clone(),
+                        ),
+                        self.codegen_expr_to_place(p, res.result),
+                    ],
+                    loc,
+                )
+            }};
+        }
+
         // Intrinsics which encode a simple binary operation
         macro_rules! codegen_intrinsic_boolean_binop {
             ($f:ident) => {{ self.binop(p, fargs, |a, b| a.$f(b).cast_to(Type::c_bool())) }};
@@ -191,8 +241,9 @@ macro_rules! codegen_unimplemented_intrinsic {
         // Most atomic intrinsics do:
         //   1. Perform an operation on a primary argument (e.g., addition)
         //   2. Return the previous value of the primary argument
-        // The primary argument is always passed by reference. In a sequential
-        // context, atomic orderings can be ignored.
+        // The primary argument is always passed by reference. The operation is an
+        // atomic block, so that other threads are not interleaved with it. Atomic
+        // orderings are ignored: CBMC only explores sequentially consistent executions.
         //
         // Atomic binops are transformed as follows:
         // -------------------------
@@ -205,7 +256,6 @@ macro_rules! codegen_unimplemented_intrinsic {
         // -------------------------
         macro_rules! codegen_atomic_binop {
             ($op: ident) => {{
-                warn!("RMC does not support concurrency for now. {} treated as a sequential operation.", intrinsic);
                 let loc = self.codegen_span_option(span);
                 let var1_ref = fargs.remove(0);
                 let var1 = var1_ref.dereference();
@@ -225,7 +275,7 @@ macro_rules! codegen_atomic_binop {
         }
 
         match intrinsic {
-            "abort" => Stmt::assert_false("abort intrinsic", loc),
+            "abort" => self.codegen_assert_false(PropertyClass::Assertion, "abort intrinsic", loc),
             "add_with_overflow" => codegen_op_with_overflow!(add_overflow),
             "arith_offset" => codegen_intrinsic_binop!(plus),
             "assert_inhabited" => {
@@ -233,7 +283,11 @@ macro_rules! codegen_atomic_binop {
                 let layout = self.layout_of(ty);
                 if layout.abi.is_uninhabited() {
                     let loc = self.codegen_span_option(span);
-                    Stmt::assert_false(&format!("type is uninhabited: {:?}", ty), loc)
+                    self.codegen_assert_false(
+                        PropertyClass::Assertion,
+                        &format!("type is uninhabited: {:?}", ty),
+                        loc,
+                    )
                 } else {
                     Stmt::skip(loc)
                 }
@@ -253,9 +307,12 @@ macro_rules! codegen_atomic_binop {
             // https://doc.rust-lang.org/core/intrinsics/fn.assume.html
             // Informs the optimizer that a condition is always true.
             // If the condition is false, the behavior is undefined.
-            "assume" => {
-                Stmt::assert(fargs.remove(0).cast_to(Type::bool()), "assumption failed", loc)
-            }
+            "assume" => self.codegen_assert(
+                fargs.remove(0).cast_to(Type::bool()),
+                PropertyClass::UndefinedBehavior,
+                "assumption failed",
+                loc,
+            ),
             "atomic_and" => codegen_atomic_binop!(bitand),
             "atomic_and_acq" => codegen_atomic_binop!(bitand),
             "atomic_and_acqrel" => codegen_atomic_binop!(bitand),
@@ -264,33 +321,37 @@ macro_rules! codegen_atomic_binop {
             name if name.starts_with("atomic_cxchg") => {
                 self.codegen_atomic_cxchg(intrinsic, fargs, p, loc)
             }
-            "atomic_fence" => self.codegen_atomic_noop(intrinsic, loc),
-            "atomic_fence_acq" => self.codegen_atomic_noop(intrinsic, loc),
-            "atomic_fence_acqrel" => self.codegen_atomic_noop(intrinsic, loc),
-            "atomic_fence_rel" => self.codegen_atomic_noop(intrinsic, loc),
-            "atomic_load" => self.codegen_atomic_load(intrinsic, fargs, p, loc),
-            "atomic_load_acq" => self.codegen_atomic_load(intrinsic, fargs, p, loc),
-            "atomic_load_relaxed" => self.codegen_atomic_load(intrinsic, fargs, p, loc),
-            "atomic_load_unordered" => self.codegen_atomic_load(intrinsic, fargs, p, loc),
+            "atomic_fence" => self.codegen_atomic_noop(loc),
+            "atomic_fence_acq" => self.codegen_atomic_noop(loc),
+            "atomic_fence_acqrel" => self.codegen_atomic_noop(loc),
+            "atomic_fence_rel" => self.codegen_atomic_noop(loc),
+            "atomic_load" => self.codegen_atomic_load(fargs, p, loc),
+            "atomic_load_acq" => self.codegen_atomic_load(fargs, p, loc),
+            "atomic_load_relaxed" => self.codegen_atomic_load(fargs, p, loc),
+            "atomic_load_unordered" => self.codegen_atomic_load(fargs, p, loc),
             "atomic_or" => codegen_atomic_binop!(bitor),
             "atomic_or_acq" => codegen_atomic_binop!(bitor),
             "atomic_or_acqrel" => codegen_atomic_binop!(bitor),
             "atomic_or_rel" => codegen_atomic_binop!(bitor),
             "atomic_or_relaxed" => codegen_atomic_binop!(bitor),
-            "atomic_store" => self.codegen_atomic_store(intrinsic, fargs, p, loc),
-            "atomic_store_rel" => self.codegen_atomic_store(intrinsic, fargs, p, loc),
-            "atomic_store_relaxed" => self.codegen_atomic_store(intrinsic, fargs, p, loc),
-            "atomic_store_unordered" => self.codegen_atomic_store(intrinsic, fargs, p, loc),
+            "atomic_singlethreadfence" => self.codegen_atomic_noop(loc),
+            "atomic_singlethreadfence_acq" => self.codegen_atomic_noop(loc),
+            "atomic_singlethreadfence_acqrel" => self.codegen_atomic_noop(loc),
+            "atomic_singlethreadfence_rel" => self.codegen_atomic_noop(loc),
+            "atomic_store" => self.codegen_atomic_store(fargs, p, loc),
+            "atomic_store_rel" => self.codegen_atomic_store(fargs, p, loc),
+            "atomic_store_relaxed" => self.codegen_atomic_store(fargs, p, loc),
+            "atomic_store_unordered" => self.codegen_atomic_store(fargs, p, loc),
             "atomic_xadd" => codegen_atomic_binop!(plus),
             "atomic_xadd_acq" => codegen_atomic_binop!(plus),
             "atomic_xadd_acqrel" => codegen_atomic_binop!(plus),
             "atomic_xadd_rel" => codegen_atomic_binop!(plus),
             "atomic_xadd_relaxed" => codegen_atomic_binop!(plus),
-            "atomic_xchg" => self.codegen_atomic_store(intrinsic, fargs, p, loc),
-            "atomic_xchg_acq" => self.codegen_atomic_store(intrinsic, fargs, p, loc),
-            "atomic_xchg_acqrel" => self.codegen_atomic_store(intrinsic, fargs, p, loc),
-            "atomic_xchg_rel" => self.codegen_atomic_store(intrinsic, fargs, p, loc),
-            "atomic_xchg_relaxed" => self.codegen_atomic_store(intrinsic, fargs, p, loc),
+            "atomic_xchg" => self.codegen_atomic_store(fargs, p, loc),
+            "atomic_xchg_acq" => self.codegen_atomic_store(fargs, p, loc),
+            "atomic_xchg_acqrel" => self.codegen_atomic_store(fargs, p, loc),
+            "atomic_xchg_rel" => self.codegen_atomic_store(fargs, p, loc),
+            "atomic_xchg_relaxed" => self.codegen_atomic_store(fargs, p, loc),
             "atomic_xor" => codegen_atomic_binop!(bitxor),
             "atomic_xor_acq" => codegen_atomic_binop!(bitxor),
             "atomic_xor_acqrel" => codegen_atomic_binop!(bitxor),
@@ -334,6 +395,9 @@ macro_rules! codegen_atomic_binop {
             "expf64" => codegen_simple_intrinsic!(Exp),
             "fabsf32" => codegen_simple_intrinsic!(Fabsf),
             "fabsf64" => codegen_simple_intrinsic!(Fabs),
+            "float_to_int_unchecked" => {
+                self.codegen_float_to_int_unchecked(instance, fargs, p, loc)
+            }
             "floorf32" => codegen_simple_intrinsic!(Floorf),
             "floorf64" => codegen_simple_intrinsic!(Floor),
             "fmaf32" => codegen_simple_intrinsic!(Fmaf),
@@ -356,7 +420,7 @@ macro_rules! codegen_atomic_binop {
             "nearbyintf32" => codegen_simple_intrinsic!(Nearbyintf),
             "nearbyintf64" => codegen_simple_intrinsic!(Nearbyint),
             "needs_drop" => codegen_intrinsic_const!(),
-            "offset" => codegen_intrinsic_binop!(plus),
+            "offset" => self.codegen_offset(instance, fargs, p, loc),
             "powf32" => codegen_simple_intrinsic!(Powf),
             "powf64" => codegen_simple_intrinsic!(Pow),
             "powif32" => codegen_simple_intrinsic!(Powif),
@@ -411,35 +475,28 @@ macro_rules! codegen_atomic_binop {
             "sqrtf32" => codegen_simple_intrinsic!(Sqrtf),
             "sqrtf64" => codegen_simple_intrinsic!(Sqrt),
             "sub_with_overflow" => codegen_op_with_overflow!(sub_overflow),
-            "transmute" => self.codegen_intrinsic_transmute(fargs, ret_ty, p),
+            "transmute" => self.codegen_intrinsic_transmute(fargs, ret_ty, p, loc),
             "truncf32" => codegen_simple_intrinsic!(Truncf),
             "truncf64" => codegen_simple_intrinsic!(Trunc),
-            "try" => {
-                codegen_unimplemented_intrinsic!("https://github.com/model-checking/rmc/issues/267")
-            }
+            "try" => self.codegen_try(fargs, p, loc),
             "type_id" => codegen_intrinsic_const!(),
             "type_name" => codegen_intrinsic_const!(),
-            "unaligned_volatile_load" => {
-                self.codegen_expr_to_place(p, fargs.remove(0).dereference())
-            }
-            "unchecked_add" => codegen_intrinsic_binop!(plus),
-            "unchecked_div" => codegen_intrinsic_binop!(div),
-            "unchecked_mul" => codegen_intrinsic_binop!(mul),
-            "unchecked_rem" => codegen_intrinsic_binop!(rem),
-            "unchecked_shl" => codegen_intrinsic_binop!(shl),
-            "unchecked_shr" => {
-                if fargs[0].typ().is_signed(self.symbol_table.machine_model()) {
-                    codegen_intrinsic_binop!(ashr)
-                } else {
-                    codegen_intrinsic_binop!(lshr)
-                }
-            }
-            "unchecked_sub" => codegen_intrinsic_binop!(sub),
+            "unaligned_volatile_load" => self.codegen_volatile_load(instance, fargs, p, loc),
+            "unchecked_add" => codegen_unchecked_op!(add_overflow),
+            "unchecked_div" => self.codegen_unchecked_div(intrinsic, fargs, p, loc),
+            "unchecked_mul" => codegen_unchecked_op!(mul_overflow),
+            "unchecked_rem" => self.codegen_unchecked_div(intrinsic, fargs, p, loc),
+            "unchecked_shl" => self.codegen_unchecked_shift(intrinsic, instance, fargs, p, loc),
+            "unchecked_shr" => self.codegen_unchecked_shift(intrinsic, instance, fargs, p, loc),
+            "unchecked_sub" => codegen_unchecked_op!(sub_overflow),
             "unlikely" => self.codegen_expr_to_place(p, fargs.remove(0)),
-            "unreachable" => Stmt::assert_false("unreachable", loc),
+            "unreachable" => {
+                self.codegen_assert_false(PropertyClass::UndefinedBehavior, "unreachable", loc)
+            }
             "volatile_copy_memory" => codegen_intrinsic_copy!(Memmove),
             "volatile_copy_nonoverlapping_memory" => codegen_intrinsic_copy!(Memcpy),
-            "volatile_load" => self.codegen_expr_to_place(p, fargs.remove(0).dereference()),
+            "volatile_load" => self.codegen_volatile_load(instance, fargs, p, loc),
+            // CBMC arithmetic wraps around, since RMC does not ask it to check for overflows.
             "wrapping_add" => codegen_intrinsic_binop!(plus),
             "wrapping_mul" => codegen_intrinsic_binop!(mul),
             "wrapping_sub" => codegen_intrinsic_binop!(sub),
@@ -450,8 +507,10 @@ macro_rules! codegen_atomic_binop {
                 let ty = self.monomorphize(instance.substs.type_at(0));
                 let layout = self.layout_of(ty);
                 let sz = Expr::int_constant(layout.size.bytes(), Type::size_t());
-                let e = BuiltinFn::Memset.call(vec![dst, val, count.mul(sz)], loc);
-                self.codegen_expr_to_place(p, e)
+                let n = count.mul(sz);
+                let mark_init = self.codegen_mark_init(dst.clone(), n.clone(), true, loc.clone());
+                let e = BuiltinFn::Memset.call(vec![dst, val, n], loc.clone());
+                Stmt::block(vec![mark_init, self.codegen_expr_to_place(p, e)], loc)
             }
 
             // Unimplemented
@@ -461,15 +520,70 @@ macro_rules! codegen_atomic_binop {
         }
     }
 
+    /// A volatile load reads a `T`, so its bytes must be initialized.
+    fn codegen_volatile_load(
+        &mut self,
+        instance: Instance<'tcx>,
+        mut fargs: Vec<Expr>,
+        p: &Place<'tcx>,
+        loc: Location,
+    ) -> Stmt {
+        let src = fargs.remove(0);
+        let ty = self.monomorphize(instance.substs.type_at(0));
+        let mut stmts: Vec<Stmt> =
+            self.codegen_check_init(src.clone(), ty, loc.clone()).into_iter().collect();
+        stmts.push(self.codegen_expr_to_place(p, src.dereference()));
+        Stmt::block(stmts, loc)
+    }
+
     fn codegen_exact_div(&mut self, mut fargs: Vec<Expr>, p: &Place<'tcx>, loc: Location) -> Stmt {
         // Check for undefined behavior conditions defined in
         // https://doc.rust-lang.org/std/intrinsics/fn.exact_div.html
-        let mm = self.symbol_table.machine_model();
         let a = fargs.remove(0);
         let b = fargs.remove(0);
+        let division_is_exact = a.clone().rem(b.clone()).eq(a.typ().zero());
+        let mut stmts = vec![self.codegen_assert(
+            division_is_exact,
+            PropertyClass::UndefinedBehavior,
+            "exact_div arguments divide exactly",
+            loc.clone(),
+        )];
+        stmts.extend(self.codegen_division_checks("exact_div", &a, &b, loc.clone()));
+        stmts.push(self.codegen_expr_to_place(p, a.div(b)));
+        Stmt::block(stmts, loc)
+    }
+
+    /// `unchecked_div` and `unchecked_rem` are undefined behavior in the same cases in which
+    /// the division operators panic.
+    /// https://doc.rust-lang.org/std/intrinsics/fn.unchecked_div.html
+    /// https://doc.rust-lang.org/std/intrinsics/fn.unchecked_rem.html
+    fn codegen_unchecked_div(
+        &mut self,
+        intrinsic: &str,
+        mut fargs: Vec<Expr>,
+        p: &Place<'tcx>,
+        loc: Location,
+    ) -> Stmt {
+        let a = fargs.remove(0);
+        let b = fargs.remove(0);
+        let mut stmts = self.codegen_division_checks(intrinsic, &a, &b, loc.clone());
+        let e = if intrinsic == "unchecked_div" { a.div(b) } else { a.rem(b) };
+        stmts.push(self.codegen_expr_to_place(p, e));
+        Stmt::block(stmts, loc)
+    }
+
+    /// Checks that the divisor `b` is not zero, and that `a / b` does not overflow, i.e. that
+    /// it is not `MIN / -1` for signed integers.
+    fn codegen_division_checks(
+        &self,
+        intrinsic: &str,
+        a: &Expr,
+        b: &Expr,
+        loc: Location,
+    ) -> Vec<Stmt> {
+        let mm = self.symbol_table.machine_model();
         let atyp = a.typ();
         let btyp = b.typ();
-        let division_is_exact = a.clone().rem(b.clone()).eq(atyp.zero());
         let divisor_is_nonzero = b.clone().neq(btyp.zero());
         let dividend_is_int_min = if atyp.is_signed(&mm) {
             a.clone().eq(atyp.min_int_expr(mm))
@@ -479,16 +593,82 @@ fn codegen_exact_div(&mut self, mut fargs: Vec<Expr>, p: &Place<'tcx>, loc: Loca
         let divisor_is_minus_one =
             if btyp.is_signed(mm) { b.clone().eq(btyp.one().neg()) } else { Expr::bool_false() };
         let division_does_not_overflow = dividend_is_int_min.and(divisor_is_minus_one).not();
+        vec![
+            self.codegen_assert(
+                divisor_is_nonzero,
+                PropertyClass::UndefinedBehavior,
+                &format!("{} divisor is nonzero", intrinsic),
+                loc.clone(),
+            ),
+            self.codegen_assert(
+                division_does_not_overflow,
+                PropertyClass::UndefinedBehavior,
+                &format!("{} division does not overflow", intrinsic),
+                loc,
+            ),
+        ]
+    }
+
+    /// `unchecked_shl` and `unchecked_shr` are undefined behavior if the shift distance is
+    /// negative or not less than the width of the shifted value.
+    /// https://doc.rust-lang.org/std/intrinsics/fn.unchecked_shl.html
+    fn codegen_unchecked_shift(
+        &mut self,
+        intrinsic: &str,
+        instance: Instance<'tcx>,
+        mut fargs: Vec<Expr>,
+        p: &Place<'tcx>,
+        loc: Location,
+    ) -> Stmt {
+        let ty = self.monomorphize(instance.substs.type_at(0));
+        let a = fargs.remove(0);
+        let b = fargs.remove(0);
+        let shift_overflows = self.codegen_shift_overflows(ty, b.clone());
+        let e = if intrinsic == "unchecked_shl" {
+            a.shl(b)
+        } else if ty.is_signed() {
+            a.ashr(b)
+        } else {
+            a.lshr(b)
+        };
+        Stmt::block(
+            vec![
+                self.codegen_assert(
+                    shift_overflows.not(),
+                    PropertyClass::UndefinedBehavior,
+                    &format!("{} shift distance is in range", intrinsic),
+                    loc.clone(),
+                ),
+                self.codegen_expr_to_place(p, e),
+            ],
+            loc,
+        )
+    }
+
+    /// `float_to_int_unchecked` is undefined behavior if the value is NaN, infinite, or does not
+    /// fit in the integer type after truncation.
+    /// https://doc.rust-lang.org/std/intrinsics/fn.float_to_int_unchecked.html
+    fn codegen_float_to_int_unchecked(
+        &mut self,
+        instance: Instance<'tcx>,
+        mut fargs: Vec<Expr>,
+        p: &Place<'tcx>,
+        loc: Location,
+    ) -> Stmt {
+        let float_ty = self.monomorphize(instance.substs.type_at(0));
+        let int_ty = self.monomorphize(instance.substs.type_at(1));
+        let value = fargs.remove(0);
+        let in_range = self.codegen_float_to_int_in_range(value.clone(), float_ty, int_ty);
+        let int_typ = self.codegen_ty(int_ty);
         Stmt::block(
             vec![
-                Stmt::assert(division_is_exact, "exact_div arguments divide exactly", loc.clone()),
-                Stmt::assert(divisor_is_nonzero, "exact_div divisor is nonzero", loc.clone()),
-                Stmt::assert(
-                    division_does_not_overflow,
-                    "exact_div division does not overflow",
+                self.codegen_assert(
+                    in_range,
+                    PropertyClass::UndefinedBehavior,
+                    "float_to_int_unchecked value is in the range of the target type",
                     loc.clone(),
                 ),
-                self.codegen_expr_to_place(p, a.div(b)),
+                self.codegen_expr_to_place(p, value.cast_to(int_typ)),
             ],
             loc,
         )
@@ -503,15 +683,10 @@ fn codegen_exact_div(&mut self, mut fargs: Vec<Expr>, p: &Place<'tcx>, loc: Loca
     /// -------------------------
     fn codegen_atomic_load(
         &mut self,
-        intrinsic: &str,
         mut fargs: Vec<Expr>,
         p: &Place<'tcx>,
         loc: Location,
     ) -> Stmt {
-        warn!(
-            "RMC does not support concurrency for now. {} treated as a sequential operation.",
-            intrinsic
-        );
         let var1_ref = fargs.remove(0);
         let var1 = var1_ref.dereference().with_location(loc.clone());
         let res_stmt = self.codegen_expr_to_place(p, var1);
@@ -522,15 +697,16 @@ fn codegen_atomic_load(
     /// its primary argument and returns a tuple that contains:
     ///  * the previous value
     ///  * a boolean value indicating whether the operation was successful or not
-    /// In a sequential context, the update is always sucessful so we assume the
-    /// second value to be true.
+    /// The operation succeeds if the previous value is the expected one. With
+    /// `-Z concurrency`, weak operations may also fail spuriously.
     /// -------------------------
     /// var = atomic_cxchg(var1, var2, var3)
     /// -------------------------
     /// unsigned char tmp;
     /// tmp = *var1;
-    /// if (*var1 == var2) *var1 = var3;
-    /// var = (tmp, true);
+    /// bool success = *var1 == var2; // && nondet() if weak
+    /// if (success) *var1 = var3;
+    /// var = (tmp, success);
     /// -------------------------
     fn codegen_atomic_cxchg(
         &mut self,
@@ -539,26 +715,30 @@ fn codegen_atomic_cxchg(
         p: &Place<'tcx>,
         loc: Location,
     ) -> Stmt {
-        warn!(
-            "RMC does not support concurrency for now. {} treated as a sequential operation.",
-            intrinsic
-        );
         let var1_ref = fargs.remove(0);
         let var1 = var1_ref.dereference().with_location(loc.clone());
         let tmp = self.gen_temp_variable(var1.typ().clone(), loc.clone()).to_expr();
         let decl_stmt = Stmt::decl(tmp.clone(), Some(var1.to_owned()), loc.clone());
         let var2 = fargs.remove(0).with_location(loc.clone());
         let var3 = fargs.remove(0).with_location(loc.clone());
-        let eq_expr = (var1.clone()).eq(var2.clone());
+        let mut eq_expr = (var1.clone()).eq(var2.clone());
+        if intrinsic.starts_with("atomic_cxchgweak") && self.concurrency_enabled() {
+            eq_expr = eq_expr.and(Type::bool().nondet());
+        }
+        let success = self.gen_temp_variable(Type::bool(), loc.clone()).to_expr();
+        let success_stmt = Stmt::decl(success.clone(), Some(eq_expr), loc.clone());
         let assign_stmt = (var1.clone()).assign(var3, loc.clone());
-        let cond_update_stmt = Stmt::if_then_else(eq_expr, assign_stmt, None, loc.clone());
+        let cond_update_stmt = Stmt::if_then_else(success.clone(), assign_stmt, None, loc.clone());
         let place_type = self.place_ty(p);
         let res_type = self.codegen_ty(place_type);
-        let tuple_expr =
-            Expr::struct_expr_from_values(res_type, vec![tmp, Expr::c_true()], &self.symbol_table)
-                .with_location(loc.clone());
+        let tuple_expr = Expr::struct_expr_from_values(
+            res_type,
+            vec![tmp, success.cast_to(Type::c_bool())],
+            &self.symbol_table,
+        )
+        .with_location(loc.clone());
         let res_stmt = self.codegen_expr_to_place(p, tuple_expr);
-        Stmt::atomic_block(vec![decl_stmt, cond_update_stmt, res_stmt], loc)
+        Stmt::atomic_block(vec![decl_stmt, success_stmt, cond_update_stmt, res_stmt], loc)
     }
 
     /// An atomic store updates the value referenced in
@@ -573,15 +753,10 @@ fn codegen_atomic_cxchg(
     /// -------------------------
     fn codegen_atomic_store(
         &mut self,
-        intrinsic: &str,
         mut fargs: Vec<Expr>,
         p: &Place<'tcx>,
         loc: Location,
     ) -> Stmt {
-        warn!(
-            "RMC does not support concurrency for now. {} treated as a sequential operation.",
-            intrinsic
-        );
         let var1_ref = fargs.remove(0);
         let var1 = var1_ref.dereference().with_location(loc.clone());
         let tmp = self.gen_temp_variable(var1.typ().clone(), loc.clone()).to_expr();
@@ -593,15 +768,76 @@ fn codegen_atomic_store(
     }
 
     /// Atomic no-ops (e.g., atomic_fence) are transformed into SKIP statements
-    fn codegen_atomic_noop(&mut self, intrinsic: &str, loc: Location) -> Stmt {
-        warn!(
-            "RMC does not support concurrency for now. {} treated as a sequential operation.",
-            intrinsic
-        );
+    fn codegen_atomic_noop(&mut self, loc: Location) -> Stmt {
         let skip_stmt = Stmt::skip(loc.clone());
         Stmt::atomic_block(vec![skip_stmt], loc)
     }
 
+    /// `offset` is undefined behavior if the resulting pointer is not in bounds of the object the
+    /// pointer points to, or one byte past its end. Offsets by zero bytes are always allowed.
+    /// https://doc.rust-lang.org/std/intrinsics/fn.offset.html
+    fn codegen_offset(
+        &mut self,
+        instance: Instance<'tcx>,
+        mut fargs: Vec<Expr>,
+        p: &Place<'tcx>,
+        loc: Location,
+    ) -> Stmt {
+        let ptr_ty = self.monomorphize(instance.substs.type_at(0));
+        let pointee_size = self.layout_of(pointee_type(ptr_ty).unwrap()).size.bytes();
+        let ptr = fargs.remove(0);
+        let count = fargs.remove(0);
+        let res = ptr.clone().plus(count.clone());
+        if pointee_size == 0 {
+            return self.codegen_expr_to_place(p, res);
+        }
+        let offset = res.clone().pointer_offset();
+        let in_bounds = res
+            .clone()
+            .same_object(ptr)
+            .and(offset.clone().ge(Type::ssize_t().zero()))
+            .and(offset.cast_to(Type::size_t()).le(res.clone().object_size()));
+        Stmt::block(
+            vec![
+                self.codegen_assert(
+                    count.is_zero().or(in_bounds),
+                    PropertyClass::UndefinedBehavior,
+                    "offset: result is in bounds of the allocated object",
+                    loc.clone(),
+                ),
+                self.codegen_expr_to_place(p, res),
+            ],
+            loc,
+        )
+    }
+
+    /// The source and destination of `copy_nonoverlapping`, both `n` bytes long, must not
+    /// overlap. Only regions of the same object can overlap.
+    /// https://doc.rust-lang.org/std/intrinsics/fn.copy_nonoverlapping.html
+    pub fn codegen_nonoverlapping_check(
+        &self,
+        name: &str,
+        src: Expr,
+        dst: Expr,
+        n: Expr,
+        loc: Location,
+    ) -> Stmt {
+        let n = n.cast_to(Type::ssize_t());
+        let src_offset = src.clone().pointer_offset();
+        let dst_offset = dst.clone().pointer_offset();
+        let nonoverlapping = src
+            .same_object(dst)
+            .not()
+            .or(src_offset.clone().plus(n.clone()).le(dst_offset.clone()))
+            .or(dst_offset.plus(n.clone()).le(src_offset));
+        self.codegen_assert(
+            n.is_zero().or(nonoverlapping),
+            PropertyClass::UndefinedBehavior,
+            &format!("{}: source and destination do not overlap", name),
+            loc,
+        )
+    }
+
     /// ptr_offset_from returns the offset between two pointers
     /// https://doc.rust-lang.org/std/intrinsics/fn.ptr_offset_from.html
     fn codegen_ptr_offset_from(
@@ -616,8 +852,9 @@ fn codegen_ptr_offset_from(
 
         Stmt::block(
             vec![
-                Stmt::assert(
+                self.codegen_assert(
                     pointers_to_same_object,
+                    PropertyClass::UndefinedBehavior,
                     "ptr_offset_from: pointers point to same object",
                     loc.clone(),
                 ),
@@ -652,11 +889,17 @@ fn codegen_intrinsic_transmute(
         mut fargs: Vec<Expr>,
         ret_ty: &'tcx TyS<'tcx>,
         p: &Place<'tcx>,
+        loc: Location,
     ) -> Stmt {
         assert!(fargs.len() == 1, "transmute had unexpected arguments {:?}", fargs);
         let arg = fargs.remove(0);
         let expr = arg.transmute_to(self.codegen_ty(ret_ty), &self.symbol_table);
-        self.codegen_expr_to_place(p, expr)
+        let transmute = self.codegen_expr_to_place(p, expr);
+        let value = self.codegen_place(p).goto_expr;
+        match self.codegen_validity_check(value, ret_ty, "transmute", loc.clone()) {
+            Some(check) => Stmt::block(vec![transmute, check], loc),
+            None => transmute,
+        }
     }
 
     // `raw_eq` determines whether the raw bytes of two values are equal.
@@ -0,0 +1,419 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module instruments programs to detect reads of uninitialized memory, with
+//! `-Z uninit-checks`.
+//!
+//! Shadow memory that records the initialization of every byte would be as large as the memory
+//! itself. Instead, we track a single byte, chosen nondeterministically when the program starts:
+//! since CBMC explores every choice, a read of an uninitialized byte fails in the execution that
+//! tracks that byte. The tracked byte is an object and an offset in globals, and whether it is
+//! initialized is a third global.
+//!
+//!  * Memory is initialized, unless it comes from `__rust_alloc` or `MaybeUninit::uninit`.
+//!  * Writes initialize the bytes they write.
+//!  * Typed reads of memory behind a pointer or in a union, and `ptr::read`, assert that the bytes
+//!    that hold data for the type read are initialized. Padding and the contents of unions, such
+//!    as `MaybeUninit`, do not need to be.
+//!  * Copies, including passing arguments and returning values, may move the tracked byte from
+//!    the source to the destination. The execution that does not move it keeps tracking the
+//!    source, and considers the destination initialized: the destination byte is checked by the
+//!    execution that tracked the corresponding source byte.
+//!
+//! Arguments and return values are moved through "channels", object ids that do not belong to
+//! any object, which the callee and the caller claim.
+
+use crate::GotocCtx;
+use cbmc::goto_program::{Expr, Location, PropertyClass, Stmt, Type};
+use rustc_middle::mir::visit::{NonMutatingUseContext, PlaceContext, Visitor};
+use rustc_middle::mir::{self, Operand, Place, Rvalue, Statement, StatementKind};
+use rustc_middle::ty::layout::LayoutOf;
+use rustc_middle::ty::Ty;
+use rustc_target::abi::{FieldsShape, TyAndLayout, Variants};
+
+const TRACKED_OBJECT: &str = "__rmc_init_tracked_object";
+const TRACKED_OFFSET: &str = "__rmc_init_tracked_offset";
+const TRACKED_INIT: &str = "__rmc_init_tracked_is_init";
+
+/// The channels are the largest object ids: the tracked byte is lost when it is in `NOWHERE`,
+/// `RETURN` carries return values and `ARG + i` carries the argument `i`.
+const NOWHERE: u64 = 0;
+const RETURN: u64 = 1;
+const ARG: u64 = 2;
+const MAX_ARGS: u64 = 256;
+
+impl<'tcx> GotocCtx<'tcx> {
+    fn uninit_checks_enabled(&self) -> bool {
+        self.tcx.sess.opts.debugging_opts.uninit_checks
+    }
+
+    fn init_channel(&self, channel: u64) -> Expr {
+        let mm = self.symbol_table.machine_model();
+        Type::size_t().max_int_expr(mm).sub(Expr::int_constant(channel, Type::size_t()))
+    }
+
+    fn init_tracked_object(&mut self) -> Expr {
+        let first_channel = self.init_channel(ARG + MAX_ARGS);
+        self.ensure_global_var(TRACKED_OBJECT, false, Type::size_t(), Location::none(), |_, var| {
+            Some(Stmt::block(
+                vec![
+                    var.clone().assign(Type::size_t().nondet(), Location::none()),
+                    Stmt::assume(var.lt(first_channel), Location::none()),
+                ],
+                Location::none(),
+            ))
+        })
+    }
+
+    fn init_tracked_offset(&mut self) -> Expr {
+        self.ensure_global_var(
+            TRACKED_OFFSET,
+            false,
+            Type::ssize_t(),
+            Location::none(),
+            |_, var| Some(var.assign(Type::ssize_t().nondet(), Location::none())),
+        )
+    }
+
+    fn init_tracked_is_init(&mut self) -> Expr {
+        self.ensure_global_var(TRACKED_INIT, false, Type::bool(), Location::none(), |_, var| {
+            Some(var.assign(Expr::bool_true(), Location::none()))
+        })
+    }
+
+    /// Whether the tracked byte is one of the `size` bytes at `ptr`.
+    fn init_tracks(&mut self, ptr: Expr, size: Expr) -> Expr {
+        let object = self.init_tracked_object();
+        let offset = self.init_tracked_offset();
+        let start = ptr.clone().pointer_offset();
+        let end = start.clone().plus(size.cast_to(Type::ssize_t()));
+        ptr.pointer_object().eq(object).and(offset.clone().ge(start)).and(offset.lt(end))
+    }
+
+    /// Moves the tracked byte from the `size` bytes at `src` to the same position in `dst`.
+    fn init_move_tracked(&mut self, src: Expr, dst: Expr) -> Stmt {
+        let object = self.init_tracked_object();
+        let offset = self.init_tracked_offset();
+        let new_offset =
+            offset.clone().sub(src.pointer_offset()).plus(dst.clone().pointer_offset());
+        Stmt::block(
+            vec![
+                object.assign(dst.pointer_object(), Location::none()),
+                offset.assign(new_offset, Location::none()),
+            ],
+            Location::none(),
+        )
+    }
+
+    /// The tracked byte is now initialized, or not, if it is one of the `size` bytes at `ptr`.
+    pub fn codegen_mark_init(&mut self, ptr: Expr, size: Expr, init: bool, loc: Location) -> Stmt {
+        if !self.uninit_checks_enabled() {
+            return Stmt::skip(loc);
+        }
+        let tracks = self.init_tracks(ptr, size);
+        let state = self.init_tracked_is_init();
+        let init = if init { Expr::bool_true() } else { Expr::bool_false() };
+        Stmt::if_then_else(tracks, state.assign(init, loc.clone()), None, loc)
+    }
+
+    /// Copies the initialization of the `size` bytes at `src` to the `size` bytes at `dst`.
+    pub fn codegen_copy_init(&mut self, src: Expr, dst: Expr, size: Expr, loc: Location) -> Stmt {
+        if !self.uninit_checks_enabled() {
+            return Stmt::skip(loc);
+        }
+        let tracks_src = self.init_tracks(src.clone(), size.clone());
+        let move_tracked = self.init_move_tracked(src, dst.clone());
+        let mark_dst = self.codegen_mark_init(dst, size, true, loc.clone());
+        Stmt::if_then_else(tracks_src.and(Type::bool().nondet()), move_tracked, Some(mark_dst), loc)
+    }
+
+    /// Asserts that the bytes at `ptr` that hold data for `ty` are initialized.
+    pub fn codegen_check_init(&mut self, ptr: Expr, ty: Ty<'tcx>, loc: Location) -> Option<Stmt> {
+        if !self.uninit_checks_enabled() || self.is_unsized(ty) {
+            return None;
+        }
+        let layout = self.layout_of(ty);
+        if layout.is_zst() {
+            return None;
+        }
+        let size = Expr::int_constant(layout.size.bytes(), Type::size_t());
+        let rel = self.init_tracked_offset().sub(ptr.clone().pointer_offset());
+        let is_data = self.codegen_data_byte(layout, rel);
+        let reads_tracked = self.init_tracks(ptr, size).and(is_data);
+        let state = self.init_tracked_is_init();
+        Some(self.codegen_assert(
+            reads_tracked.not().or(state),
+            PropertyClass::UndefinedBehavior,
+            "memory read is initialized",
+            loc,
+        ))
+    }
+
+    /// Whether the byte at offset `rel` of a value with layout `layout` holds data, as opposed to
+    /// padding or the contents of a union. Only the tag of enums with several variants is data,
+    /// since which fields are data depends on the variant.
+    fn codegen_data_byte(&mut self, layout: TyAndLayout<'tcx>, rel: Expr) -> Expr {
+        let in_range = |rel: &Expr, offset: u64, size: u64| {
+            let start = Expr::int_constant(offset, Type::ssize_t());
+            let end = Expr::int_constant(offset + size, Type::ssize_t());
+            rel.clone().ge(start).and(rel.clone().lt(end))
+        };
+        if layout.is_zst() || layout.ty.is_union() {
+            return Expr::bool_false();
+        }
+        if let Variants::Multiple { tag_field, .. } = &layout.variants {
+            let tag = layout.field(self, *tag_field);
+            return in_range(&rel, layout.fields.offset(*tag_field).bytes(), tag.size.bytes());
+        }
+        match &layout.fields {
+            FieldsShape::Primitive => Expr::bool_true(),
+            FieldsShape::Union(_) => Expr::bool_false(),
+            FieldsShape::Array { stride, .. } => {
+                let elem = layout.field(self, 0);
+                let elem_rel = rel.rem(Expr::int_constant(stride.bytes(), Type::ssize_t()));
+                let in_elem = in_range(&elem_rel, 0, elem.size.bytes());
+                in_elem.and(self.codegen_data_byte(elem, elem_rel))
+            }
+            FieldsShape::Arbitrary { offsets, .. } => {
+                let mut is_data = Expr::bool_false();
+                for (i, offset) in offsets.iter().enumerate() {
+                    let field = layout.field(self, i);
+                    if field.is_zst() {
+                        continue;
+                    }
+                    let offset = offset.bytes();
+                    let field_rel = rel.clone().sub(Expr::int_constant(offset, Type::ssize_t()));
+                    let in_field = in_range(&rel, offset, field.size.bytes());
+                    is_data = is_data.or(in_field.and(self.codegen_data_byte(field, field_rel)));
+                }
+                is_data
+            }
+        }
+    }
+
+    /// The address and size in bytes of a sized place, if it is not a ZST.
+    fn init_place_bytes(&mut self, place: &Place<'tcx>) -> Option<(Expr, Expr)> {
+        let ty = self.place_ty(place);
+        if self.is_unsized(ty) || self.layout_of(ty).is_zst() {
+            return None;
+        }
+        let size = Expr::int_constant(self.layout_of(ty).size.bytes(), Type::size_t());
+        Some((self.codegen_place(place).goto_expr.address_of(), size))
+    }
+
+    /// Checks the reads done by a statement, which are the places it copies, moves or inspects,
+    /// like `Rvalue::Discriminant`. Reads of locals are not checked, since locals that are not
+    /// unions are always initialized when read.
+    pub fn codegen_init_reads(&mut self, stmt: &Statement<'tcx>, loc: Location) -> Vec<Stmt> {
+        if !self.uninit_checks_enabled() {
+            return vec![];
+        }
+        let mut reads = ReadPlaces { places: vec![] };
+        if let StatementKind::Assign(box (_, rvalue)) = &stmt.kind {
+            reads.visit_rvalue(rvalue, mir::Location::START);
+        }
+        self.codegen_init_checks_of_places(reads.places, loc)
+    }
+
+    fn codegen_init_checks_of_places(
+        &mut self,
+        places: Vec<Place<'tcx>>,
+        loc: Location,
+    ) -> Vec<Stmt> {
+        let mut checks = vec![];
+        for place in places {
+            if !place.is_indirect() && !self.place_reads_union_field(&place) {
+                continue;
+            }
+            let ty = self.place_ty(&place);
+            if self.is_unsized(ty) {
+                continue;
+            }
+            let ptr = self.codegen_place(&place).goto_expr.address_of();
+            checks.extend(self.codegen_check_init(ptr, ty, loc.clone()));
+        }
+        checks
+    }
+
+    /// Updates the initialization of the memory a statement writes.
+    /// This runs before the statement, so that the addresses of the places are not affected by it.
+    pub fn codegen_init_writes(&mut self, stmt: &Statement<'tcx>, loc: Location) -> Vec<Stmt> {
+        if !self.uninit_checks_enabled() {
+            return vec![];
+        }
+        match &stmt.kind {
+            StatementKind::Assign(box (lhs, rvalue)) => {
+                let (dst, size) = match self.init_place_bytes(lhs) {
+                    Some(bytes) => bytes,
+                    None => return vec![],
+                };
+                match rvalue {
+                    Rvalue::Use(Operand::Copy(rhs) | Operand::Move(rhs)) => {
+                        let src = self.codegen_place(rhs).goto_expr.address_of();
+                        vec![self.codegen_copy_init(src, dst, size, loc)]
+                    }
+                    _ => vec![self.codegen_mark_init(dst, size, true, loc)],
+                }
+            }
+            StatementKind::SetDiscriminant { place, .. } => match self.init_place_bytes(place) {
+                Some((dst, size)) => vec![self.codegen_mark_init(dst, size, true, loc)],
+                None => vec![],
+            },
+            StatementKind::CopyNonOverlapping(box mir::CopyNonOverlapping {
+                ref src,
+                ref dst,
+                ref count,
+            }) => {
+                let pointee = self.operand_ty(dst).builtin_deref(true).unwrap().ty;
+                let elem_size = self.layout_of(pointee).size.bytes();
+                let size = Expr::int_constant(elem_size, Type::size_t())
+                    .mul(self.codegen_operand(count).cast_to(Type::size_t()));
+                let src = self.codegen_operand(src);
+                let dst = self.codegen_operand(dst);
+                vec![self.codegen_copy_init(src, dst, size, loc)]
+            }
+            _ => vec![],
+        }
+    }
+
+    /// Before a call: checks the arguments read through pointers, and may move the tracked byte
+    /// into the channel of the argument that holds it.
+    pub fn codegen_init_call_args(
+        &mut self,
+        args: &[Operand<'tcx>],
+        untupled: bool,
+        loc: Location,
+    ) -> Vec<Stmt> {
+        if !self.uninit_checks_enabled() {
+            return vec![];
+        }
+        let places: Vec<_> = args.iter().filter_map(|arg| arg.place()).collect();
+        let mut stmts = self.codegen_init_checks_of_places(places, loc.clone());
+        // The callee of the "rust-call" ABI receives the fields of the last argument, a tuple,
+        // as separate arguments, so we do not move the tracked byte through it.
+        let moved = if untupled { args.len().saturating_sub(1) } else { args.len() };
+        for (i, arg) in args.iter().take(moved).enumerate() {
+            let place = match arg.place() {
+                Some(place) => place,
+                None => continue,
+            };
+            if let Some((src, size)) = self.init_place_bytes(&place) {
+                let always = matches!(arg, Operand::Move(_));
+                let channel = ARG + i as u64;
+                stmts.push(self.codegen_init_to_channel(src, size, channel, always, loc.clone()));
+            }
+        }
+        stmts
+    }
+
+    /// After a call: claims the tracked byte if the callee returned it, and otherwise drops it
+    /// if it is still in a channel because the callee did not claim it.
+    pub fn codegen_init_call_result(&mut self, dst: &Place<'tcx>, loc: Location) -> Stmt {
+        if !self.uninit_checks_enabled() {
+            return Stmt::skip(loc);
+        }
+        let object = self.init_tracked_object();
+        let lost = object.clone().assign(self.init_channel(NOWHERE), loc.clone());
+        let claim = match self.init_place_bytes(dst) {
+            Some((dst, size)) => {
+                let claim = self.codegen_init_from_channel(dst.clone(), RETURN, loc.clone());
+                let mark_dst = self.codegen_mark_init(dst, size, true, loc.clone());
+                Stmt::if_then_else(
+                    object.clone().eq(self.init_channel(RETURN)),
+                    claim,
+                    Some(mark_dst),
+                    loc.clone(),
+                )
+            }
+            None => Stmt::skip(loc.clone()),
+        };
+        let in_channel = object.ge(self.init_channel(ARG + MAX_ARGS));
+        Stmt::block(vec![claim, Stmt::if_then_else(in_channel, lost, None, loc.clone())], loc)
+    }
+
+    /// Before returning: moves the tracked byte into the return channel if it is in the return
+    /// value.
+    pub fn codegen_init_return(&mut self, loc: Location) -> Stmt {
+        if !self.uninit_checks_enabled() {
+            return Stmt::skip(loc);
+        }
+        match self.init_place_bytes(&Place::from(mir::RETURN_PLACE)) {
+            Some((ret, size)) => self.codegen_init_to_channel(ret, size, RETURN, true, loc),
+            None => Stmt::skip(loc),
+        }
+    }
+
+    /// In the prelude of a function: claims the tracked byte if it is in the channel of one of
+    /// the arguments.
+    pub fn codegen_init_claim_args(&mut self) -> Vec<Stmt> {
+        if !self.uninit_checks_enabled() {
+            return vec![];
+        }
+        let mir = self.current_fn().mir();
+        let mut stmts = vec![];
+        for (i, arg) in mir.args_iter().enumerate() {
+            if Some(arg) == mir.spread_arg || i as u64 >= MAX_ARGS {
+                continue;
+            }
+            if let Some((dst, _)) = self.init_place_bytes(&Place::from(arg)) {
+                stmts.push(self.codegen_init_from_channel(dst, ARG + i as u64, Location::none()));
+            }
+        }
+        stmts
+    }
+
+    fn codegen_init_to_channel(
+        &mut self,
+        src: Expr,
+        size: Expr,
+        channel: u64,
+        always: bool,
+        loc: Location,
+    ) -> Stmt {
+        let object = self.init_tracked_object();
+        let offset = self.init_tracked_offset();
+        let mut cond = self.init_tracks(src.clone(), size);
+        if !always {
+            cond = cond.and(Type::bool().nondet());
+        }
+        let to_channel = Stmt::block(
+            vec![
+                object.assign(self.init_channel(channel), loc.clone()),
+                offset.clone().assign(offset.sub(src.pointer_offset()), loc.clone()),
+            ],
+            loc.clone(),
+        );
+        Stmt::if_then_else(cond, to_channel, None, loc)
+    }
+
+    fn codegen_init_from_channel(&mut self, dst: Expr, channel: u64, loc: Location) -> Stmt {
+        let object = self.init_tracked_object();
+        let offset = self.init_tracked_offset();
+        let from_channel = Stmt::block(
+            vec![
+                object.clone().assign(dst.clone().pointer_object(), loc.clone()),
+                offset.clone().assign(offset.plus(dst.pointer_offset()), loc.clone()),
+            ],
+            loc.clone(),
+        );
+        Stmt::if_then_else(object.eq(self.init_channel(channel)), from_channel, None, loc)
+    }
+}
+
+/// Collects the places an rvalue reads.
+struct ReadPlaces<'tcx> {
+    places: Vec<Place<'tcx>>,
+}
+
+impl<'tcx> Visitor<'tcx> for ReadPlaces<'tcx> {
+    fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, _: mir::Location) {
+        match context {
+            PlaceContext::NonMutatingUse(
+                NonMutatingUseContext::Copy
+                | NonMutatingUseContext::Move
+                | NonMutatingUseContext::Inspect,
+            ) => self.places.push(*place),
+            _ => {}
+        }
+    }
+}
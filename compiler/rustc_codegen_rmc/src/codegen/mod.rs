@@ -4,16 +4,23 @@
 //! This module does that actual translation of MIR constructs to goto constructs.
 //! Each subfile is named for the MIR construct it translates.
 
+mod asm;
+mod assert;
 mod assumptions;
 mod block;
 mod function;
 mod intrinsic;
+mod memory_init;
 mod operand;
 mod place;
 mod rvalue;
 mod span;
 mod statement;
 mod static_var;
+mod sync;
+mod thread;
 mod typ;
+mod unwind;
 
+pub use function::is_rmctool_root;
 pub use typ::TypeExt;
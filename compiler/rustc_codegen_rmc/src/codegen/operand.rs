@@ -504,6 +504,14 @@ fn codegen_alloc_in_memory(&mut self, alloc: &'tcx Allocation, name: String) {
                 .collect(),
             &self.symbol_table,
         );
+        // CBMC initializes the copy of a thread local in a new thread with the value of its
+        // symbol, rather than with its initializer.
+        let symbol = self.symbol_table.lookup(&name).unwrap();
+        if symbol.is_thread_local {
+            let value = val.clone().transmute_to(var_typ.clone(), &self.symbol_table);
+            let symbol = symbol.clone().with_value(value);
+            self.symbol_table.replace(|_| true, symbol);
+        }
         let fn_name = Self::initializer_fn_name(&name);
         let temp_var = self.gen_function_local_variable(0, &fn_name, alloc_typ_ref).to_expr();
         let body = Stmt::block(
@@ -576,6 +584,7 @@ fn codegen_niche_literal(&mut self, ty: Ty<'tcx>, offset: usize, init: Expr) ->
     }
 
     pub fn codegen_func_expr(&mut self, instance: Instance<'tcx>, span: Option<&Span>) -> Expr {
+        self.record_stub_target(instance);
         let func = self.symbol_name(instance);
         let funct = self.codegen_function_sig(self.fn_sig_of_instance(instance).unwrap());
         // make sure the functions imported from other modules are in the symbol table
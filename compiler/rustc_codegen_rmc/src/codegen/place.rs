@@ -8,14 +8,15 @@
 use super::typ::TypeExt;
 use crate::utils::slice_fat_ptr;
 use crate::GotocCtx;
-use cbmc::goto_program::{Expr, Type};
+use cbmc::goto_program::{Expr, Location, PropertyClass, Stmt, Type};
 use rustc_hir::Mutability;
+use rustc_middle::mir::visit::{MutatingUseContext, NonMutatingUseContext, PlaceContext, Visitor};
 use rustc_middle::ty::layout::LayoutOf;
 use rustc_middle::{
-    mir::{Field, Local, Place, ProjectionElem},
+    mir::{self, Field, Local, Place, ProjectionElem, Statement, StatementKind},
     ty::{self, Ty, TyS, TypeAndMut, VariantDef},
 };
-use rustc_target::abi::{TagEncoding, Variants};
+use rustc_target::abi::{TagEncoding, VariantIdx, Variants};
 use tracing::{debug, warn};
 
 /// A projection in RMC can either be to a type (the normal case),
@@ -24,6 +25,7 @@
 pub enum TypeOrVariant<'tcx> {
     Type(Ty<'tcx>),
     Variant(&'tcx VariantDef),
+    GeneratorVariant(VariantIdx),
 }
 
 /// Relevent information about a projected place (i.e. an lvalue).
@@ -81,7 +83,7 @@ fn check_expr_typ_mismatch(
                 if expr_ty != type_from_mir { Some((expr_ty, type_from_mir)) } else { None }
             }
             // TODO: handle Variant https://github.com/model-checking/rmc/issues/448
-            TypeOrVariant::Variant(_) => None,
+            TypeOrVariant::Variant(_) | TypeOrVariant::GeneratorVariant(_) => None,
         }
     }
 
@@ -141,7 +143,7 @@ impl<'tcx> TypeOrVariant<'tcx> {
     pub fn monomorphize(self, ctx: &GotocCtx<'tcx>) -> Self {
         match self {
             TypeOrVariant::Type(t) => TypeOrVariant::Type(ctx.monomorphize(t)),
-            TypeOrVariant::Variant(_) => self,
+            TypeOrVariant::Variant(_) | TypeOrVariant::GeneratorVariant(_) => self,
         }
     }
 }
@@ -151,6 +153,9 @@ pub fn expect_type(&self) -> Ty<'tcx> {
         match self {
             TypeOrVariant::Type(t) => t,
             TypeOrVariant::Variant(v) => panic!("expect a type but variant is found: {:?}", v),
+            TypeOrVariant::GeneratorVariant(v) => {
+                panic!("expect a type but generator variant is found: {:?}", v)
+            }
         }
     }
 
@@ -159,6 +164,9 @@ pub fn expect_variant(&self) -> &'tcx VariantDef {
         match self {
             TypeOrVariant::Type(t) => panic!("expect a variant but type is found: {:?}", t),
             TypeOrVariant::Variant(v) => v,
+            TypeOrVariant::GeneratorVariant(v) => {
+                panic!("expect a variant but generator variant is found: {:?}", v)
+            }
         }
     }
 }
@@ -208,12 +216,10 @@ fn codegen_field(&mut self, res: Expr, t: TypeOrVariant<'tcx>, f: &Field) -> Exp
                         res.member(&field.ident.name.to_string(), &self.symbol_table)
                     }
                     ty::Closure(..) => res.member(&f.index().to_string(), &self.symbol_table),
-                    ty::Generator(..) => self.codegen_unimplemented(
-                        "ty::Generator",
-                        Type::code(vec![], Type::empty()),
-                        res.location().clone(),
-                        "https://github.com/model-checking/rmc/issues/416",
-                    ),
+                    // the fields of a generator outside of its variants are its upvars
+                    ty::Generator(..) => res
+                        .member("direct_fields", &self.symbol_table)
+                        .member(&Self::tuple_fld_name(f.index()), &self.symbol_table),
                     _ => unimplemented!(),
                 }
             }
@@ -222,6 +228,10 @@ fn codegen_field(&mut self, res: Expr, t: TypeOrVariant<'tcx>, f: &Field) -> Exp
                 let field = &v.fields[f.index()];
                 res.member(&field.ident.name.to_string(), &self.symbol_table)
             }
+            // the fields of a variant of a generator are the locals saved in that state
+            TypeOrVariant::GeneratorVariant(_) => {
+                res.member(&Self::tuple_fld_name(f.index()), &self.symbol_table)
+            }
         }
     }
 
@@ -263,6 +273,11 @@ pub fn codegen_local(&mut self, l: Local) -> Expr {
         }
 
         // Otherwise, simply look up the local by the var name.
+        self.codegen_local_symbol(l)
+    }
+
+    /// The symbol that represents the local `l`, even if it is a function definition.
+    pub fn codegen_local_symbol(&mut self, l: Local) -> Expr {
         let vname = self.codegen_var_name(&l);
         Expr::symbol_expression(vname, self.codegen_ty(self.local_ty(l)))
     }
@@ -448,6 +463,17 @@ fn codegen_projection(
                             self,
                         )
                     }
+                    ty::Generator(..) => {
+                        let variant_name = Self::generator_variant_name(idx);
+                        let expr = before.goto_expr.member(&variant_name, &self.symbol_table);
+                        ProjectedPlace::new(
+                            expr,
+                            TypeOrVariant::GeneratorVariant(idx),
+                            before.fat_ptr_goto_expr,
+                            before.fat_ptr_mir_typ,
+                            self,
+                        )
+                    }
                     _ => unreachable!("it's a bug to reach here!"),
                 }
             }
@@ -532,4 +558,95 @@ fn codegen_constant_index(
     pub fn codegen_idx_array(&mut self, arr: Expr, idx: Expr) -> Expr {
         arr.member("0", &self.symbol_table).index_array(idx)
     }
+
+    /// Whether the place is, or is in, a field of a union.
+    pub fn place_reads_union_field(&self, p: &Place<'tcx>) -> bool {
+        p.iter_projections().any(|(base, proj)| {
+            let base_ty = base.ty(self.current_fn().mir().local_decls(), self.tcx).ty;
+            matches!(proj, ProjectionElem::Field(..)) && base_ty.is_union()
+        })
+    }
+
+    /// Accessing memory through a pointer is undefined behavior unless the pointer is aligned
+    /// for the type accessed, even when no bytes are accessed.
+    /// We assume that the base address of every object is aligned, so this checks the offset
+    /// of the pointer within its object against the alignment of `pointee` from its layout.
+    /// Unsized pointees, whose alignment is only known at runtime, are not checked.
+    pub fn codegen_alignment_check(
+        &mut self,
+        ptr: Expr,
+        pointee: Ty<'tcx>,
+        msg: &str,
+        loc: Location,
+    ) -> Option<Stmt> {
+        if self.is_unsized(pointee) {
+            return None;
+        }
+        let align = self.layout_of(pointee).align.abi.bytes();
+        if align == 1 {
+            return None;
+        }
+        let mask = Expr::int_constant(align - 1, Type::ssize_t());
+        let aligned = ptr.pointer_offset().bitand(mask).is_zero();
+        Some(self.codegen_assert(aligned, PropertyClass::UndefinedBehavior, msg, loc))
+    }
+
+    /// Checks that every raw pointer dereferenced by the places `stmt` reads or writes is
+    /// aligned. References are aligned by construction, so they are not checked, and neither are
+    /// places that only have their address taken, as in `ptr::addr_of!((*p).field)`.
+    pub fn codegen_alignment_checks(&mut self, stmt: &Statement<'tcx>, loc: Location) -> Vec<Stmt> {
+        if !matches!(stmt.kind, StatementKind::Assign(..) | StatementKind::SetDiscriminant { .. }) {
+            return vec![];
+        }
+        let mut accessed = AccessedPlaces { places: vec![] };
+        accessed.visit_statement(stmt, mir::Location::START);
+        let mut checks = vec![];
+        for place in accessed.places {
+            for (base, proj) in place.iter_projections() {
+                if proj != ProjectionElem::Deref {
+                    continue;
+                }
+                let base_ty = Place::ty_from(
+                    base.local,
+                    base.projection,
+                    self.current_fn().mir().local_decls(),
+                    self.tcx,
+                )
+                .ty;
+                let base_ty = self.monomorphize(base_ty);
+                if !base_ty.is_unsafe_ptr() {
+                    continue;
+                }
+                let base = Place {
+                    local: base.local,
+                    projection: self.tcx.intern_place_elems(base.projection),
+                };
+                let ptr = self.codegen_place(&base).goto_expr;
+                let pointee = base_ty.builtin_deref(true).unwrap().ty;
+                checks.extend(self.codegen_alignment_check(
+                    ptr,
+                    pointee,
+                    "dereferenced pointer is aligned",
+                    loc.clone(),
+                ));
+            }
+        }
+        checks
+    }
+}
+
+/// Collects the places a MIR statement reads or writes.
+struct AccessedPlaces<'tcx> {
+    places: Vec<Place<'tcx>>,
+}
+
+impl<'tcx> Visitor<'tcx> for AccessedPlaces<'tcx> {
+    fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, _: mir::Location) {
+        match context {
+            PlaceContext::MutatingUse(MutatingUseContext::AddressOf)
+            | PlaceContext::NonMutatingUse(NonMutatingUseContext::AddressOf)
+            | PlaceContext::NonUse(_) => {}
+            _ => self.places.push(*place),
+        }
+    }
 }
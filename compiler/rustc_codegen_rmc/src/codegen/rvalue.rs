@@ -5,14 +5,13 @@
 use crate::GotocCtx;
 use cbmc::goto_program::{BuiltinFn, Expr, Location, Stmt, Symbol, Type};
 use cbmc::utils::{aggr_tag, BUG_REPORT_URL};
-use cbmc::MachineModel;
 use cbmc::NO_PRETTY_NAME;
 use cbmc::{btree_string_map, InternString, InternedString};
 use num::bigint::BigInt;
 use rustc_middle::mir::{AggregateKind, BinOp, CastKind, NullOp, Operand, Place, Rvalue, UnOp};
 use rustc_middle::ty::adjustment::PointerCast;
 use rustc_middle::ty::layout::LayoutOf;
-use rustc_middle::ty::{self, Instance, IntTy, Ty, UintTy, VtblEntry, COMMON_VTABLE_ENTRIES};
+use rustc_middle::ty::{self, FloatTy, Instance, Ty, VtblEntry, COMMON_VTABLE_ENTRIES};
 use rustc_target::abi::{FieldsShape, Primitive, TagEncoding, Variants};
 use tracing::{debug, warn};
 
@@ -59,6 +58,8 @@ fn codegen_unchecked_scalar_binop(
         }
     }
 
+    /// Arithmetic without overflow checks wraps around in Rust, which is also what CBMC does as
+    /// long as it is not asked to check for overflows.
     fn codegen_scalar_binop(&mut self, op: &BinOp, e1: &Operand<'tcx>, e2: &Operand<'tcx>) -> Expr {
         let ce1 = self.codegen_operand(e1);
         let ce2 = self.codegen_operand(e2);
@@ -66,18 +67,36 @@ fn codegen_scalar_binop(&mut self, op: &BinOp, e1: &Operand<'tcx>, e2: &Operand<
             BinOp::Add => ce1.plus(ce2),
             BinOp::Sub => ce1.sub(ce2),
             BinOp::Mul => ce1.mul(ce2),
-            BinOp::Shl => ce1.shl(ce2),
-            BinOp::Shr => {
-                if self.operand_ty(e1).is_signed() {
-                    ce1.ashr(ce2)
-                } else {
-                    ce1.lshr(ce2)
-                }
-            }
+            BinOp::Shl | BinOp::Shr => self.codegen_masked_shift(op, e1, ce1, ce2),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Shifts that are not checked for overflow only use the low bits of the shift distance, as
+    /// many as are needed to shift by less than the width of the shifted value.
+    /// c.f. https://doc.rust-lang.org/std/primitive.u32.html#method.wrapping_shl
+    fn codegen_masked_shift(&self, op: &BinOp, e1: &Operand<'tcx>, ce1: Expr, ce2: Expr) -> Expr {
+        let t1 = self.operand_ty(e1);
+        let bits = self.layout_of(t1).size.bits();
+        let distance_typ = ce2.typ().clone();
+        let distance = ce2.bitand(Expr::int_constant(bits - 1, distance_typ));
+        match op {
+            BinOp::Shl => ce1.shl(distance),
+            BinOp::Shr if t1.is_signed() => ce1.ashr(distance),
+            BinOp::Shr => ce1.lshr(distance),
             _ => unreachable!(),
         }
     }
 
+    /// Whether shifting a value of type `t` by `distance` overflows, i.e. whether `distance` is
+    /// negative or not less than the width of `t`. The width is a power of two, so this is the
+    /// case iff `distance` has any bit set other than the ones that can encode a smaller distance.
+    pub fn codegen_shift_overflows(&self, t: Ty<'tcx>, distance: Expr) -> Expr {
+        let bits = self.layout_of(t).size.bits();
+        let typ = distance.typ().clone();
+        distance.bitand(Expr::int_constant(bits - 1, typ.clone()).bitnot()).neq(typ.zero())
+    }
+
     /// Given a mir object denoted by a mir place, codegen a pointer to this object.
     pub fn codegen_rvalue_ref(&mut self, place: &Place<'tcx>, result_mir_type: Ty<'tcx>) -> Expr {
         let place_mir_type = self.place_ty(place);
@@ -220,28 +239,6 @@ fn codegen_rvalue_checked_binary_op(
         let ce1 = self.codegen_operand(e1);
         let ce2 = self.codegen_operand(e2);
 
-        fn shift_max(t: Ty<'_>, mm: &MachineModel) -> Expr {
-            match t.kind() {
-                ty::Int(k) => match k {
-                    IntTy::I8 => Expr::int_constant(7, Type::signed_int(8)),
-                    IntTy::I16 => Expr::int_constant(15, Type::signed_int(16)),
-                    IntTy::I32 => Expr::int_constant(31, Type::signed_int(32)),
-                    IntTy::I64 => Expr::int_constant(63, Type::signed_int(64)),
-                    IntTy::I128 => Expr::int_constant(127, Type::signed_int(128)),
-                    IntTy::Isize => Expr::int_constant(mm.pointer_width() - 1, Type::ssize_t()),
-                },
-                ty::Uint(k) => match k {
-                    UintTy::U8 => Expr::int_constant(7, Type::unsigned_int(8)),
-                    UintTy::U16 => Expr::int_constant(15, Type::unsigned_int(16)),
-                    UintTy::U32 => Expr::int_constant(31, Type::unsigned_int(32)),
-                    UintTy::U64 => Expr::int_constant(63, Type::unsigned_int(64)),
-                    UintTy::U128 => Expr::int_constant(127, Type::unsigned_int(128)),
-                    UintTy::Usize => Expr::int_constant(mm.pointer_width() - 1, Type::size_t()),
-                },
-                _ => unreachable!(),
-            }
-        }
-
         match op {
             BinOp::Add => {
                 let res = ce1.add_overflow(ce2);
@@ -267,27 +264,13 @@ fn shift_max(t: Ty<'_>, mm: &MachineModel) -> Expr {
                     &self.symbol_table,
                 )
             }
-            BinOp::Shl => {
-                let t1 = self.operand_ty(e1);
-                let max = shift_max(t1, self.symbol_table.machine_model());
-                Expr::struct_expr_from_values(
-                    self.codegen_ty(res_ty),
-                    vec![
-                        ce1.shl(ce2.clone()),
-                        ce2.cast_to(self.codegen_ty(t1)).gt(max).cast_to(Type::c_bool()),
-                    ],
-                    &self.symbol_table,
-                )
-            }
-            BinOp::Shr => {
+            BinOp::Shl | BinOp::Shr => {
                 let t1 = self.operand_ty(e1);
-                let max = shift_max(t1, self.symbol_table.machine_model());
+                let overflowed = self.codegen_shift_overflows(t1, ce2.clone());
+                let result = self.codegen_masked_shift(op, e1, ce1, ce2);
                 Expr::struct_expr_from_values(
                     self.codegen_ty(res_ty),
-                    vec![
-                        if t1.is_signed() { ce1.ashr(ce2.clone()) } else { ce1.lshr(ce2.clone()) },
-                        ce2.cast_to(self.codegen_ty(t1)).gt(max).cast_to(Type::c_bool()),
-                    ],
+                    vec![result, overflowed.cast_to(Type::c_bool())],
                     &self.symbol_table,
                 )
             }
@@ -431,14 +414,9 @@ pub fn codegen_rvalue(&mut self, rv: &Rvalue<'tcx>) -> Expr {
             Rvalue::Aggregate(ref k, operands) => {
                 self.codegen_rvalue_aggregate(&*k, operands, res_ty)
             }
-            Rvalue::ThreadLocalRef(_) => {
+            Rvalue::ThreadLocalRef(def_id) => {
                 let typ = self.codegen_ty(res_ty);
-                self.codegen_unimplemented(
-                    "Rvalue::ThreadLocalRef",
-                    typ,
-                    Location::none(),
-                    "https://github.com/model-checking/rmc/issues/541",
-                )
+                self.codegen_thread_local_pointer(*def_id).cast_to(typ)
             }
         }
     }
@@ -457,7 +435,7 @@ pub fn codegen_get_discriminant(&mut self, e: Expr, ty: Ty<'tcx>, res_ty: Ty<'tc
             }
             Variants::Multiple { tag, tag_encoding, .. } => match tag_encoding {
                 TagEncoding::Direct => {
-                    e.member("case", &self.symbol_table).cast_to(self.codegen_ty(res_ty))
+                    self.codegen_discriminant_field(e, ty).cast_to(self.codegen_ty(res_ty))
                 }
                 TagEncoding::Niche { dataful_variant, niche_variants, niche_start } => {
                     let offset = match &layout.fields {
@@ -502,6 +480,17 @@ pub fn codegen_get_discriminant(&mut self, e: Expr, ty: Ty<'tcx>, res_ty: Ty<'tc
         }
     }
 
+    /// The field that stores the discriminant of `place`, of type `ty`, with a direct tag.
+    /// A generator stores it next to its upvars, see `codegen_ty_generator`.
+    pub fn codegen_discriminant_field(&self, place: Expr, ty: Ty<'tcx>) -> Expr {
+        match ty.kind() {
+            ty::Generator(..) => {
+                place.member("direct_fields", &self.symbol_table).member("case", &self.symbol_table)
+            }
+            _ => place.member("case", &self.symbol_table),
+        }
+    }
+
     pub fn codegen_fat_ptr_to_fat_ptr_cast(
         &mut self,
         src: &Operand<'tcx>,
@@ -551,6 +540,78 @@ pub fn codegen_fat_ptr_to_thin_ptr_cast(
         src_goto_expr.member("data", &self.symbol_table).cast_to(dst_goto_typ)
     }
 
+    /// A float of type `float_ty` as a goto constant.
+    fn codegen_float_constant(float_ty: Ty<'tcx>, value: f64) -> Expr {
+        match float_ty.kind() {
+            ty::Float(FloatTy::F32) => Expr::float_constant(value as f32),
+            ty::Float(FloatTy::F64) => Expr::double_constant(value),
+            _ => unreachable!("Expected a float type, got {:?}", float_ty),
+        }
+    }
+
+    /// The floats of type `float_ty` that are the smallest value of the integer type `int_ty`,
+    /// and the smallest power of two above its largest value. Both are exact (or infinity for
+    /// the upper bound of `u128` as `f32`), so a float `x` can be converted to `int_ty` by
+    /// truncation iff `lower <= trunc(x) && x < upper`.
+    fn codegen_float_to_int_bounds(&self, float_ty: Ty<'tcx>, int_ty: Ty<'tcx>) -> (Expr, Expr) {
+        let bits = self.layout_of(int_ty).size.bits() as i32;
+        let (lower, upper) = if int_ty.is_signed() {
+            (-(2f64.powi(bits - 1)), 2f64.powi(bits - 1))
+        } else {
+            (0.0, 2f64.powi(bits))
+        };
+        (
+            Self::codegen_float_constant(float_ty, lower),
+            Self::codegen_float_constant(float_ty, upper),
+        )
+    }
+
+    /// Whether the float `src` of type `float_ty` can be converted to the integer type `int_ty`,
+    /// i.e. whether it is not NaN or infinite, and fits in `int_ty` after truncation.
+    pub fn codegen_float_to_int_in_range(
+        &self,
+        src: Expr,
+        float_ty: Ty<'tcx>,
+        int_ty: Ty<'tcx>,
+    ) -> Expr {
+        let (lower, upper) = self.codegen_float_to_int_bounds(float_ty, int_ty);
+        let mantissa_digits = match float_ty.kind() {
+            ty::Float(FloatTy::F32) => f32::MANTISSA_DIGITS,
+            ty::Float(FloatTy::F64) => f64::MANTISSA_DIGITS,
+            _ => unreachable!("Expected a float type, got {:?}", float_ty),
+        };
+        let bits = self.layout_of(int_ty).size.bits();
+        // The floats above `lower - 1` truncate to at least `lower`. If `lower - 1` is not
+        // representable, there is no float between it and `lower`.
+        let above_lower = if !int_ty.is_signed() {
+            src.clone().gt(Self::codegen_float_constant(float_ty, -1.0))
+        } else if bits <= mantissa_digits as u64 {
+            let lower_value = -(2f64.powi(bits as i32 - 1));
+            src.clone().gt(Self::codegen_float_constant(float_ty, lower_value - 1.0))
+        } else {
+            src.clone().ge(lower)
+        };
+        // NaN is not in range since comparisons with NaN are false.
+        above_lower.and(src.lt(upper))
+    }
+
+    /// Rust float to integer casts saturate: values below the range of the integer type become
+    /// its minimum, values above become its maximum, and NaN becomes 0.
+    /// https://doc.rust-lang.org/reference/expressions/operator-expr.html#numeric-cast
+    fn codegen_float_to_int_cast(&mut self, src: Expr, src_t: Ty<'tcx>, dst_t: Ty<'tcx>) -> Expr {
+        let (lower, upper) = self.codegen_float_to_int_bounds(src_t, dst_t);
+        let typ = self.codegen_ty(dst_t);
+        let mm = self.symbol_table.machine_model();
+        let is_nan = src.clone().fneq(src.clone());
+        is_nan.ternary(
+            typ.zero(),
+            src.clone().lt(lower).ternary(
+                typ.min_int_expr(mm),
+                src.clone().ge(upper).ternary(typ.max_int_expr(mm), src.cast_to(typ.clone())),
+            ),
+        )
+    }
+
     fn codegen_misc_cast(&mut self, src: &Operand<'tcx>, dst_t: Ty<'tcx>) -> Expr {
         let src_t = self.operand_ty(src);
         debug!(
@@ -558,6 +619,12 @@ fn codegen_misc_cast(&mut self, src: &Operand<'tcx>, dst_t: Ty<'tcx>) -> Expr {
             src, src_t, dst_t
         );
 
+        // Float to integer casts saturate, while they are undefined in C for out of range values
+        if src_t.is_floating_point() && dst_t.is_integral() {
+            let src = self.codegen_operand(src);
+            return self.codegen_float_to_int_cast(src, src_t, dst_t);
+        }
+
         // number casting
         if src_t.is_numeric() && dst_t.is_numeric() {
             return self.codegen_operand(src).cast_to(self.codegen_ty(dst_t));
@@ -637,13 +704,8 @@ pub fn codegen_pointer_cast(
             PointerCast::ReifyFnPointer => self.codegen_operand(o).address_of(),
             PointerCast::UnsafeFnPointer => self.codegen_operand(o),
             PointerCast::ClosureFnPointer(_) => {
-                let dest_typ = self.codegen_ty(t);
-                self.codegen_unimplemented(
-                    "PointerCast::ClosureFnPointer",
-                    dest_typ,
-                    Location::none(),
-                    "https://github.com/model-checking/rmc/issues/274",
-                )
+                let closure_ty = self.operand_ty(o);
+                self.codegen_closure_fn_pointer(closure_ty, t)
             }
             PointerCast::MutToConstPointer => self.codegen_operand(o),
             PointerCast::ArrayToPointer => {
@@ -705,6 +767,58 @@ fn cast_to_unsized_expr(
         }
     }
 
+    /// A closure that captures nothing can be coerced to a function pointer. The function of the
+    /// closure also takes the (empty) environment as its first argument, so the pointer points to
+    /// a shim that only takes the arguments of the closure, and calls it with an empty environment.
+    fn codegen_closure_fn_pointer(&mut self, closure_ty: Ty<'tcx>, fn_ptr_ty: Ty<'tcx>) -> Expr {
+        let closure = match closure_ty.kind() {
+            ty::Closure(def_id, substs) => {
+                Instance::resolve_closure(self.tcx, *def_id, substs, ty::ClosureKind::FnOnce)
+            }
+            _ => unreachable!("Expected a closure, got {:?}", closure_ty),
+        };
+        let sig = self.tcx.normalize_erasing_late_bound_regions(
+            ty::ParamEnv::reveal_all(),
+            fn_ptr_ty.fn_sig(self.tcx),
+        );
+        let shim_name = format!("{}::fn_ptr_shim", self.symbol_name(closure));
+        let shim = self
+            .ensure(shim_name, |ctx, name| {
+                let env = Expr::struct_expr_from_values(
+                    ctx.codegen_ty(closure_ty),
+                    vec![],
+                    &ctx.symbol_table,
+                );
+                let mut params = vec![];
+                let mut args = vec![env];
+                for (i, t) in sig.inputs().iter().enumerate() {
+                    if ctx.ignore_var_ty(t) {
+                        continue;
+                    }
+                    let param_name = format!("{}::1::var{:?}", name, i);
+                    let param_sym = Symbol::variable(
+                        param_name.clone(),
+                        param_name,
+                        ctx.codegen_ty(t),
+                        Location::none(),
+                    );
+                    ctx.symbol_table.insert(param_sym.clone());
+                    params.push(param_sym.to_function_parameter());
+                    args.push(param_sym.to_expr());
+                }
+                let call = ctx.codegen_func_expr(closure, None).call(args);
+                Symbol::function(
+                    name,
+                    Type::code(params, ctx.codegen_ty(sig.output())),
+                    Some(Stmt::block(vec![call.ret(Location::none())], Location::none())),
+                    NO_PRETTY_NAME,
+                    Location::none(),
+                )
+            })
+            .to_expr();
+        shim.address_of().cast_to(self.codegen_ty(fn_ptr_ty))
+    }
+
     fn codegen_vtable_method_field(
         &mut self,
         instance: Instance<'tcx>,
@@ -1012,7 +1126,7 @@ fn cast_sized_expr_to_unsized_expr(
     /// Cast a pointer to a sized object to a fat pointer to an unsized object.
     /// Return the result of the cast as Some(expr) and return None if no cast
     /// was required.
-    fn cast_sized_pointer_to_fat_pointer(
+    pub fn cast_sized_pointer_to_fat_pointer(
         &mut self,
         src_goto_expr: Expr,
         src_mir_type: Ty<'tcx>,
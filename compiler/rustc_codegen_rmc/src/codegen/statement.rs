@@ -1,19 +1,23 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0 OR MIT
+use super::assert::property_class_of_assert_kind;
 use super::typ::TypeExt;
 use super::typ::FN_RETURN_VOID_VAR_NAME;
 use crate::GotocCtx;
-use cbmc::goto_program::{BuiltinFn, Expr, Location, Stmt, Type};
+use cbmc::goto_program::{BuiltinFn, Expr, Location, PropertyClass, Stmt, Type};
 use rustc_hir::def_id::DefId;
 use rustc_middle::mir;
+use rustc_middle::mir::interpret::{read_target_uint, Allocation, ConstValue, GlobalAlloc, Scalar};
+use rustc_middle::mir::visit::{MutatingUseContext, PlaceContext, Visitor};
 use rustc_middle::mir::{
-    BasicBlock, Operand, Place, Statement, StatementKind, SwitchTargets, Terminator, TerminatorKind,
+    AggregateKind, BasicBlock, Constant, ConstantKind, Local, Operand, Place, ProjectionElem,
+    Rvalue, Statement, StatementKind, SwitchTargets, Terminator, TerminatorKind,
 };
 use rustc_middle::ty;
 use rustc_middle::ty::layout::LayoutOf;
-use rustc_middle::ty::{Instance, InstanceDef, Ty};
+use rustc_middle::ty::{ConstKind, Instance, InstanceDef, Ty};
 use rustc_span::Span;
-use rustc_target::abi::{FieldsShape, Primitive, TagEncoding, Variants};
+use rustc_target::abi::{FieldsShape, Primitive, Size, TagEncoding, VariantIdx, Variants};
 use smallvec::SmallVec;
 use std::convert::TryInto;
 use tracing::debug;
@@ -38,42 +42,42 @@ pub fn codegen_terminator(&mut self, term: &Terminator<'tcx>) -> Stmt {
         //TODO: Instead of doing location::none(), and updating, just putit in when we make the stmt.
         match &term.kind {
             TerminatorKind::Goto { target } => {
-                Stmt::goto(self.current_fn().find_label(target), loc)
+                let goto = Stmt::goto(self.current_fn().find_label(target), loc);
+                let bb = self.current_fn().current_bb();
+                match self.current_fn_mut().take_loop_contracts(bb) {
+                    Some((invariant, decreases)) => goto.with_loop_contracts(invariant, decreases),
+                    None => goto,
+                }
             }
             TerminatorKind::SwitchInt { discr, switch_ty, targets } => match targets {
                 SwitchTargets { values, targets } => {
                     self.codegen_switch_int(discr, switch_ty, values, targets)
                 }
             },
-            TerminatorKind::Resume => Stmt::assert_false("resume instruction", loc),
-            TerminatorKind::Abort => Stmt::assert_false("abort instruction", loc),
-            TerminatorKind::Return => {
-                let rty = self.current_fn().sig().unwrap().skip_binder().output();
-                if rty.is_unit() {
-                    self.codegen_ret_unit()
-                } else {
-                    let p = Place::from(mir::RETURN_PLACE);
-                    let v = self.codegen_place(&p).goto_expr;
-                    if self.place_ty(&p).is_bool() {
-                        v.cast_to(Type::c_bool()).ret(loc)
-                    } else {
-                        v.ret(loc)
-                    }
-                }
+            TerminatorKind::Resume => self.codegen_resume(loc),
+            TerminatorKind::Abort => {
+                self.codegen_assert_false(PropertyClass::Assertion, "abort instruction", loc)
             }
+            TerminatorKind::Return => self.codegen_return(loc),
             TerminatorKind::Unreachable => Stmt::block(
                 vec![
-                    Stmt::assert_false("unreachable code", loc.clone()),
+                    self.codegen_assert_false(
+                        PropertyClass::UndefinedBehavior,
+                        "unreachable code",
+                        loc.clone(),
+                    ),
                     Stmt::assume(Expr::bool_false(), loc.clone()),
                 ],
                 loc,
             ),
-            TerminatorKind::Drop { place, target, unwind: _ } => self.codegen_drop(place, target),
+            TerminatorKind::Drop { place, target, unwind } => {
+                self.codegen_drop(place, target, *unwind)
+            }
             TerminatorKind::DropAndReplace { .. } => {
                 unreachable!("this instruction is unreachable")
             }
-            TerminatorKind::Call { func, args, destination, .. } => {
-                self.codegen_funcall(func, args, destination, term.source_info.span)
+            TerminatorKind::Call { func, args, destination, cleanup, .. } => {
+                self.codegen_funcall(func, args, destination, *cleanup, term.source_info.span)
             }
             TerminatorKind::Assert { cond, expected, msg, target, .. } => {
                 let cond = {
@@ -81,6 +85,20 @@ pub fn codegen_terminator(&mut self, term: &Terminator<'tcx>) -> Stmt {
                     if *expected { r } else { Expr::not(r) }
                 };
 
+                let property_class = property_class_of_assert_kind(msg);
+                let msg = format!("{:?}", msg);
+                // A failed check panics, so with unwinding it continues in the cleanup block.
+                let failure = if self.unwinding_enabled() {
+                    self.codegen_unwinding_panic(property_class, &msg, loc.clone())
+                } else {
+                    Stmt::block(
+                        vec![
+                            self.codegen_assert_false(property_class, &msg, loc.clone()),
+                            Stmt::goto(self.current_fn().find_label(target), loc.clone()),
+                        ],
+                        loc.clone(),
+                    )
+                };
                 Stmt::block(
                     vec![
                         cond.cast_to(Type::bool()).if_then_else(
@@ -88,8 +106,7 @@ pub fn codegen_terminator(&mut self, term: &Terminator<'tcx>) -> Stmt {
                             None,
                             loc.clone(),
                         ),
-                        Stmt::assert_false(&format!("{:?}", msg), loc.clone()),
-                        Stmt::goto(self.current_fn().find_label(target), loc.clone()),
+                        failure,
                     ],
                     loc,
                 )
@@ -98,20 +115,35 @@ pub fn codegen_terminator(&mut self, term: &Terminator<'tcx>) -> Stmt {
             | TerminatorKind::GeneratorDrop
             | TerminatorKind::FalseEdge { .. }
             | TerminatorKind::FalseUnwind { .. } => unreachable!("we should not hit these cases"),
-            TerminatorKind::InlineAsm { .. } => self
-                .codegen_unimplemented(
-                    "InlineAsm",
-                    Type::empty(),
-                    loc.clone(),
-                    "https://github.com/model-checking/rmc/issues/2",
-                )
-                .as_stmt(loc),
+            TerminatorKind::InlineAsm { operands, options, destination, .. } => {
+                self.codegen_inline_asm(operands, *options, *destination, term.source_info.span)
+            }
+        }
+    }
+
+    /// Returns the value of the return place of the current function.
+    pub fn codegen_return(&mut self, loc: Location) -> Stmt {
+        let rty = self.current_fn().sig().unwrap().skip_binder().output();
+        if rty.is_unit() {
+            self.codegen_ret_unit()
+        } else {
+            let p = Place::from(mir::RETURN_PLACE);
+            let v = self.codegen_place(&p).goto_expr;
+            let ret = if self.place_ty(&p).is_bool() {
+                v.cast_to(Type::c_bool()).ret(loc.clone())
+            } else {
+                v.ret(loc.clone())
+            };
+            Stmt::block(vec![self.codegen_init_return(loc.clone()), ret], loc)
         }
     }
 
-    // TODO: this function doesn't handle unwinding which begins if the destructor panics
-    // https://github.com/model-checking/rmc/issues/221
-    fn codegen_drop(&mut self, location: &Place<'tcx>, target: &BasicBlock) -> Stmt {
+    fn codegen_drop(
+        &mut self,
+        location: &Place<'tcx>,
+        target: &BasicBlock,
+        unwind: Option<BasicBlock>,
+    ) -> Stmt {
         let loc_ty = self.place_ty(location);
         let drop_instance = Instance::resolve_drop_in_place(self.tcx, loc_ty);
         if let Some(hk) = self.hooks.hook_applies(self.tcx, drop_instance) {
@@ -170,8 +202,9 @@ fn codegen_drop(&mut self, location: &Place<'tcx>, target: &BasicBlock) -> Stmt
                             if Expr::typecheck_call(&func, &args) {
                                 func.call(args)
                             } else {
-                                self.codegen_unimplemented(
-                                    format!("drop_in_place call for {:?}", func).as_str(),
+                                self.codegen_unimplemented_with_detail(
+                                    "drop_in_place call",
+                                    Some(format!("{:?}", func)),
                                     func.typ().return_type().unwrap().clone(),
                                     Location::none(),
                                     "https://github.com/model-checking/rmc/issues/426",
@@ -182,8 +215,11 @@ fn codegen_drop(&mut self, location: &Place<'tcx>, target: &BasicBlock) -> Stmt
                     }
                 }
             };
+            let unwind = self.codegen_unwind_check(unwind, Location::none());
             let goto_target = Stmt::goto(self.current_fn().find_label(target), Location::none());
-            let block = vec![drop_implementation, goto_target];
+            let mut block = vec![drop_implementation];
+            block.extend(unwind);
+            block.push(goto_target);
             Stmt::block(block, Location::none())
         }
     }
@@ -303,6 +339,7 @@ fn codegen_funcall(
         func: &Operand<'tcx>,
         args: &[Operand<'tcx>],
         destination: &Option<(Place<'tcx>, BasicBlock)>,
+        cleanup: Option<BasicBlock>,
         span: Span,
     ) -> Stmt {
         let loc = self.codegen_span(&span);
@@ -345,7 +382,8 @@ fn codegen_funcall(
                 if destination.is_none() {
                     // No target block means this function doesn't return.
                     // This should have been handled by the Nevers hook.
-                    return Stmt::assert_false(
+                    return self.codegen_assert_false(
+                        PropertyClass::SanityCheck,
                         &format!("reach some nonterminating function: {:?}", func),
                         loc.clone(),
                     );
@@ -353,7 +391,9 @@ fn codegen_funcall(
 
                 let (p, target) = destination.unwrap();
 
-                let mut stmts: Vec<Stmt> = match instance.def {
+                let untupled = self.ty_needs_closure_untupled(funct);
+                let mut stmts = self.codegen_init_call_args(args, untupled, loc.clone());
+                stmts.extend(match instance.def {
                     // Here an empty drop glue is invoked; we just ignore it.
                     InstanceDef::DropGlue(_, None) => {
                         return Stmt::goto(self.current_fn().find_label(&target), Location::none());
@@ -395,7 +435,9 @@ fn codegen_funcall(
                                 .with_location(loc.clone()),
                         ]
                     }
-                };
+                });
+                stmts.push(self.codegen_init_call_result(&p, loc.clone()));
+                stmts.extend(self.codegen_unwind_check(cleanup, loc.clone()));
                 stmts.push(Stmt::goto(self.current_fn().find_label(&target), loc.clone()));
                 return Stmt::block(stmts, loc);
             }
@@ -404,14 +446,15 @@ fn codegen_funcall(
                 let (p, target) = destination.unwrap();
                 let func_expr = self.codegen_operand(func).dereference();
                 // Actually generate the function call and return.
-                return Stmt::block(
-                    vec![
-                        self.codegen_expr_to_place(&p, func_expr.call(fargs))
-                            .with_location(loc.clone()),
-                        Stmt::goto(self.current_fn().find_label(&target), loc.clone()),
-                    ],
-                    loc,
+                let mut stmts = self.codegen_init_call_args(args, false, loc.clone());
+                stmts.push(
+                    self.codegen_expr_to_place(&p, func_expr.call(fargs))
+                        .with_location(loc.clone()),
                 );
+                stmts.push(self.codegen_init_call_result(&p, loc.clone()));
+                stmts.extend(self.codegen_unwind_check(cleanup, loc.clone()));
+                stmts.push(Stmt::goto(self.current_fn().find_label(&target), loc.clone()));
+                return Stmt::block(stmts, loc);
             }
             x => unreachable!("Function call where the function was of unexpected type: {:?}", x),
         };
@@ -445,7 +488,12 @@ fn codegen_virtual_funcall(
         // could be vacuously true.
         let call_is_nonnull = fn_ptr.clone().is_nonnull();
         let assert_msg = format!("Non-null virtual function call for {:?}", vtable_field_name);
-        let assert_nonnull = Stmt::assert(call_is_nonnull, &assert_msg, loc.clone());
+        let assert_nonnull = self.codegen_assert(
+            call_is_nonnull,
+            PropertyClass::SanityCheck,
+            &assert_msg,
+            loc.clone(),
+        );
 
         // Virtual function call and corresponding nonnull assertion.
         let func_exp: Expr = fn_ptr.dereference();
@@ -467,18 +515,17 @@ pub fn codegen_expr_to_place(&mut self, p: &Place<'tcx>, e: Expr) -> Stmt {
         }
     }
 
-    pub fn codegen_panic(&mut self, span: Option<Span>, fargs: Vec<Expr>) -> Stmt {
+    pub fn codegen_panic(&mut self, span: Option<Span>, instance: Instance<'tcx>) -> Stmt {
         // CBMC requires that the argument to the assertion must be a string constant.
-        // If there is one in the MIR, use it; otherwise, explain that we can't.
-        // TODO: give a better message here.
-        let arg = match fargs[0].struct_expr_values() {
-            Some(values) => values[0].clone(),
-            _ => Expr::string_constant(
-                "This is a placeholder assertion message; the rust message requires dynamic string formatting, which is not supported by CBMC",
-            ),
-        };
+        // If the message can be folded into one from the MIR, use it; otherwise, explain that we can't.
+        let msg = self.fold_panic_message(instance).unwrap_or_else(|| {
+            "This is a placeholder assertion message; the rust message requires dynamic string formatting, which is not supported by CBMC".to_string()
+        });
 
         let loc = self.codegen_span_option(span);
+        if self.unwinding_enabled() {
+            return self.codegen_unwinding_panic(PropertyClass::Assertion, &msg, loc);
+        }
         let cbb = self.current_fn().current_bb();
 
         // TODO: is it proper?
@@ -494,12 +541,12 @@ pub fn codegen_panic(&mut self, span: Option<Span>, fargs: Vec<Expr>) -> Stmt {
             let pred_bbd = &self.current_fn().mir()[*pred];
             let pterm = pred_bbd.terminator();
             match pterm.successors().find(|bb| **bb != cbb) {
-                None => self.codegen_assert_false(arg, loc),
+                None => self.codegen_assert_false(PropertyClass::Assertion, &msg, loc),
                 Some(alt) => {
                     let loc = self.codegen_span(&pterm.source_info.span);
                     Stmt::block(
                         vec![
-                            self.codegen_assert_false(arg, loc.clone()),
+                            self.codegen_assert_false(PropertyClass::Assertion, &msg, loc.clone()),
                             Stmt::goto(self.current_fn().find_label(alt), Location::none()),
                         ],
                         loc,
@@ -507,19 +554,331 @@ pub fn codegen_panic(&mut self, span: Option<Span>, fargs: Vec<Expr>) -> Stmt {
                 }
             }
         } else {
-            self.codegen_assert_false(arg, loc)
+            self.codegen_assert_false(PropertyClass::Assertion, &msg, loc)
+        }
+    }
+
+    /// The message that the panicking function `instance` prints, if it only depends on constants
+    /// of the current function. The values of formatted arguments are replaced by `{}`.
+    fn fold_panic_message(&self, instance: Instance<'tcx>) -> Option<String> {
+        let args = self.current_call_args();
+        match &*self.tcx.item_name(instance.def_id()).as_str() {
+            "begin_panic" | "panic" | "panic_display" | "panic_str" => self.fold_str(args.first()?),
+            "panic_fmt" => self.fold_fmt_arguments(args.first()?),
+            "assert_failed" => self.fold_assert_failed_message(args),
+            _ => None,
+        }
+    }
+
+    /// The `&str` constant passed as argument `index` of the call that ends the current block.
+    pub fn fold_call_arg_str(&self, index: usize) -> Option<String> {
+        self.fold_str(self.current_call_args().get(index)?)
+    }
+
+    /// The MIR arguments of the call that ends the current basic block.
+    fn current_call_args(&self) -> &'tcx [Operand<'tcx>] {
+        let mir = self.current_fn().mir();
+        match &mir[self.current_fn().current_bb()].terminator().kind {
+            TerminatorKind::Call { args, .. } => args,
+            _ => &[],
         }
     }
 
-    // By the time we get this, the string constant has been codegenned.
-    // TODO: make this case also use the Stmt::assert_false() constructor
-    pub fn codegen_assert_false(&mut self, err: Expr, loc: Location) -> Stmt {
-        BuiltinFn::CProverAssert.call(vec![Expr::bool_false(), err], loc.clone()).as_stmt(loc)
+    /// The rvalue assigned to `local`, if the current function assigns it exactly once.
+    fn unique_rvalue(&self, local: Local) -> Option<&'tcx Rvalue<'tcx>> {
+        let mut rvalues = self
+            .current_fn()
+            .mir()
+            .basic_blocks()
+            .iter()
+            .flat_map(|bbd| &bbd.statements)
+            .filter_map(|stmt| match &stmt.kind {
+                StatementKind::Assign(box (place, rvalue)) if place.as_local() == Some(local) => {
+                    Some(rvalue)
+                }
+                _ => None,
+            });
+        let rvalue = rvalues.next()?;
+        if rvalues.next().is_none() { Some(rvalue) } else { None }
+    }
+
+    /// Follows copies and casts of uniquely assigned locals back to the operand they come from.
+    fn operand_origin<'a>(&self, mut operand: &'a Operand<'tcx>) -> &'a Operand<'tcx> {
+        while let Some(Rvalue::Use(op) | Rvalue::Cast(_, op, _)) =
+            operand.place().and_then(|p| p.as_local()).and_then(|l| self.unique_rvalue(l))
+        {
+            operand = op;
+        }
+        operand
+    }
+
+    fn eval_constant(&self, c: &Constant<'tcx>) -> Option<(ConstValue<'tcx>, Ty<'tcx>)> {
+        match self.monomorphize(c.literal) {
+            ConstantKind::Val(val, ty) => Some((val, ty)),
+            ConstantKind::Ty(ct) => match ct.val {
+                ConstKind::Value(val) => Some((val, ct.ty)),
+                ConstKind::Unevaluated(unevaluated) => self
+                    .tcx
+                    .const_eval_resolve(ty::ParamEnv::reveal_all(), unevaluated, None)
+                    .ok()
+                    .map(|val| (val, ct.ty)),
+                _ => None,
+            },
+        }
+    }
+
+    /// The `&str` constant `operand` evaluates to. References to locals holding such a constant,
+    /// as in `panic_display(&msg)`, are looked through.
+    fn fold_str(&self, operand: &Operand<'tcx>) -> Option<String> {
+        let only_derefs = |place: &Place<'tcx>| {
+            place.projection.iter().all(|elem| matches!(elem, ProjectionElem::Deref))
+        };
+        let is_str_ref = |ty: Ty<'tcx>| matches!(ty.kind(), ty::Ref(_, inner, _) if inner.is_str());
+        match self.operand_origin(operand) {
+            Operand::Constant(c) => match self.eval_constant(c)? {
+                (ConstValue::Slice { data, start, end }, ty) if is_str_ref(ty) => {
+                    let bytes = data.inspect_with_uninit_and_ptr_outside_interpreter(start..end);
+                    std::str::from_utf8(bytes).ok().map(String::from)
+                }
+                _ => None,
+            },
+            Operand::Copy(place) | Operand::Move(place) if only_derefs(place) => {
+                match self.unique_rvalue(place.local)? {
+                    Rvalue::Ref(_, _, referent) if only_derefs(referent) => {
+                        self.fold_str(&Operand::Copy(Place::from(referent.local)))
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// The format string of a `fmt::Arguments` built by `Arguments::new_v1`, with `{}` in place
+    /// of each formatted argument. With `Arguments::new_v1_formatted`, an argument can be
+    /// formatted several times, so there is a placeholder for each of its format specs instead.
+    fn fold_fmt_arguments(&self, operand: &Operand<'tcx>) -> Option<String> {
+        let local = self.operand_origin(operand).place()?.as_local()?;
+        let (func, args) =
+            self.current_fn().mir().basic_blocks().iter().find_map(|bbd| {
+                match &bbd.terminator().kind {
+                    TerminatorKind::Call { func, args, destination: Some((place, _)), .. }
+                        if place.as_local() == Some(local) =>
+                    {
+                        Some((func, args))
+                    }
+                    _ => None,
+                }
+            })?;
+        let def_id = match self.operand_ty(func).kind() {
+            ty::FnDef(def_id, _) => *def_id,
+            _ => return None,
+        };
+        let num_placeholders = match &*self.tcx.item_name(def_id).as_str() {
+            "new_v1" => self.fold_array_len(&args[1])?,
+            "new_v1_formatted" => self.fold_array_len(&args[2])?,
+            _ => return None,
+        };
+        let pieces = self.fold_str_array(&args[0])?;
+        let mut msg = String::new();
+        for (i, piece) in pieces.iter().enumerate() {
+            msg.push_str(piece);
+            if (i as u64) < num_placeholders {
+                msg.push_str("{}");
+            }
+        }
+        Some(msg)
+    }
+
+    /// The length `N` of an operand of type `&[T; N]`, or of the array it is coerced from.
+    fn fold_array_len(&self, operand: &Operand<'tcx>) -> Option<u64> {
+        match self.operand_ty(self.operand_origin(operand)).kind() {
+            ty::Ref(_, array, _) => match array.kind() {
+                ty::Array(_, len) => len.try_eval_usize(self.tcx, ty::ParamEnv::reveal_all()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// The strings of a constant `&[&str; N]`, such as the pieces of a format string.
+    fn fold_str_array(&self, operand: &Operand<'tcx>) -> Option<Vec<String>> {
+        let c = match self.operand_origin(operand) {
+            Operand::Constant(c) => c,
+            _ => return None,
+        };
+        let (val, ty) = self.eval_constant(c)?;
+        let len = match ty.kind() {
+            ty::Ref(_, array, _) => match array.kind() {
+                ty::Array(elem, len) if matches!(elem.kind(), ty::Ref(_, s, _) if s.is_str()) => {
+                    len.try_eval_usize(self.tcx, ty::ParamEnv::reveal_all())?
+                }
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let (alloc_id, offset) = match val {
+            ConstValue::Scalar(Scalar::Ptr(ptr, _)) => ptr.into_parts(),
+            _ => return None,
+        };
+        let alloc = match self.tcx.global_alloc(alloc_id) {
+            GlobalAlloc::Memory(alloc) => alloc,
+            _ => return None,
+        };
+        // Each element is a fat pointer: a pointer to the bytes followed by the length.
+        let pointer_size = self.symbol_table.machine_model().pointer_width() as usize / 8;
+        (0..len as usize)
+            .map(|i| self.read_str(alloc, offset.bytes_usize() + i * 2 * pointer_size))
+            .collect()
+    }
+
+    /// Reads the `&str` stored at `offset` in `alloc`.
+    fn read_str(&self, alloc: &'tcx Allocation, offset: usize) -> Option<String> {
+        let pointer_size = self.symbol_table.machine_model().pointer_width() as usize / 8;
+        let read_usize = |start: usize| {
+            let bytes =
+                alloc.inspect_with_uninit_and_ptr_outside_interpreter(start..start + pointer_size);
+            read_target_uint(self.tcx.sess.target.options.endian, bytes).ok()
+        };
+        let data_id = *alloc.relocations().get(&Size::from_bytes(offset))?;
+        let data_offset = read_usize(offset)? as usize;
+        let len = read_usize(offset + pointer_size)? as usize;
+        match self.tcx.global_alloc(data_id) {
+            GlobalAlloc::Memory(data) => {
+                std::str::from_utf8(data.inspect_with_uninit_and_ptr_outside_interpreter(
+                    data_offset..data_offset + len,
+                ))
+                .ok()
+                .map(String::from)
+            }
+            _ => None,
+        }
+    }
+
+    /// The message of `core::panicking::assert_failed`, which backs `assert_eq!` and
+    /// `assert_ne!`, without the values of `left` and `right`.
+    fn fold_assert_failed_message(&self, args: &[Operand<'tcx>]) -> Option<String> {
+        let kind = args.first()?;
+        let variant = self.fold_variant(kind)?;
+        let adt = self.operand_ty(kind).ty_adt_def()?;
+        let op = match &*adt.variants[variant].ident.name.as_str() {
+            "Eq" => "==",
+            "Ne" => "!=",
+            "Match" => "matches",
+            _ => return None,
+        };
+        let msg = format!("assertion failed: `(left {} right)`", op);
+        match args.get(3).and_then(|arg| self.fold_some_payload(arg)) {
+            Some(payload) => match self.fold_fmt_arguments(payload) {
+                Some(user_msg) => Some(format!("{}: {}", msg, user_msg)),
+                None => Some(msg),
+            },
+            None => Some(msg),
+        }
+    }
+
+    /// The variant of the fieldless enum value `operand`.
+    fn fold_variant(&self, operand: &Operand<'tcx>) -> Option<VariantIdx> {
+        match self.operand_origin(operand) {
+            Operand::Constant(c) => {
+                let (val, ty) = self.eval_constant(c)?;
+                let bits = match val {
+                    ConstValue::Scalar(s) => s.to_bits(self.layout_of(ty).size).ok()?,
+                    _ => return None,
+                };
+                let adt = ty.ty_adt_def()?;
+                adt.discriminants(self.tcx).find(|(_, discr)| discr.val == bits).map(|(idx, _)| idx)
+            }
+            Operand::Copy(place) | Operand::Move(place) => {
+                let local = place.as_local()?;
+                let mut variants =
+                    self.dominating_writes(local)?.into_iter().filter_map(|kind| match kind {
+                        StatementKind::SetDiscriminant { place, variant_index }
+                            if place.as_local() == Some(local) =>
+                        {
+                            Some(Some(*variant_index))
+                        }
+                        StatementKind::Assign(box (
+                            place,
+                            Rvalue::Aggregate(box AggregateKind::Adt(_, variant_index, ..), _),
+                        )) if place.as_local() == Some(local) => Some(Some(*variant_index)),
+                        // Writes to the fields of a variant do not change the variant.
+                        StatementKind::Assign(box (place, _))
+                            if matches!(
+                                place.projection.first(),
+                                Some(ProjectionElem::Downcast(..))
+                            ) =>
+                        {
+                            None
+                        }
+                        _ => Some(None),
+                    });
+                // Only fold a variant that is set exactly once.
+                let variant = variants.next()??;
+                if variants.next().is_none() { Some(variant) } else { None }
+            }
+        }
+    }
+
+    /// The payload of `operand` if it is built as `Some(payload)` in the current function.
+    fn fold_some_payload(&self, operand: &Operand<'tcx>) -> Option<&'tcx Operand<'tcx>> {
+        let local = self.operand_origin(operand).place()?.as_local()?;
+        let mut payloads =
+            self.dominating_writes(local)?.into_iter().filter_map(|kind| match kind {
+                StatementKind::Assign(box (
+                    place,
+                    Rvalue::Aggregate(box AggregateKind::Adt(..), ops),
+                )) if place.as_local() == Some(local) => Some(ops.first()),
+                // Deaggregated form: `((_n as Some).0: T) = move _m`
+                StatementKind::Assign(box (place, Rvalue::Use(op)))
+                    if matches!(
+                        place.projection[..],
+                        [ProjectionElem::Downcast(..), ProjectionElem::Field(..)]
+                    ) =>
+                {
+                    Some(Some(op))
+                }
+                // Followed by `discriminant(_n) = 1`, which does not change the payload.
+                StatementKind::SetDiscriminant { place, .. } if place.as_local() == Some(local) => {
+                    None
+                }
+                _ => Some(None),
+            });
+        // Only fold a payload that is set exactly once.
+        let payload = payloads.next()??;
+        if payloads.next().is_none() { Some(payload) } else { None }
+    }
+
+    /// The statements that write to `local`, if they all dominate the call that ends the current
+    /// block and `local` is not modified in any other way, e.g. through a mutable borrow or as the
+    /// destination of a call. The value of `local` at the call then only depends on them.
+    fn dominating_writes(&self, local: Local) -> Option<Vec<&'tcx StatementKind<'tcx>>> {
+        let mir = self.current_fn().mir();
+        let mut visitor = LocalWrites { local, writes: vec![], other_mutation: false };
+        visitor.visit_body(mir);
+        let use_loc = mir.terminator_loc(self.current_fn().current_bb());
+        let dominators = mir.dominators();
+        if visitor.other_mutation
+            || !visitor.writes.iter().all(|loc| loc.dominates(use_loc, &dominators))
+        {
+            return None;
+        }
+        Some(
+            visitor
+                .writes
+                .iter()
+                .map(|loc| &mir[loc.block].statements[loc.statement_index].kind)
+                .collect(),
+        )
     }
 
     pub fn codegen_statement(&mut self, stmt: &Statement<'tcx>) -> Stmt {
         debug!("handling statement {:?}", stmt);
-        match &stmt.kind {
+        let loc = self.codegen_span(&stmt.source_info.span);
+        let mut checks = self.codegen_alignment_checks(stmt, loc.clone());
+        checks.extend(self.codegen_init_reads(stmt, loc.clone()));
+        checks.extend(self.codegen_init_writes(stmt, loc.clone()));
+        let code = match &stmt.kind {
             StatementKind::Assign(box (l, r)) => {
                 let lty = self.place_ty(l);
                 let rty = self.rvalue_ty(r);
@@ -543,33 +902,20 @@ pub fn codegen_statement(&mut self, stmt: &Statement<'tcx>) -> Stmt {
                 }
             }
             StatementKind::SetDiscriminant { place, variant_index } => {
-                // this requires place points to an enum type.
+                // this requires place points to an enum or a generator type.
                 let pt = self.place_ty(place);
-                let (def, _) = match pt.kind() {
-                    ty::Adt(def, substs) => (def, substs),
-                    ty::Generator(..) => {
-                        return self
-                            .codegen_unimplemented(
-                                "ty::Generator",
-                                Type::code(vec![], Type::empty()),
-                                Location::none(),
-                                "https://github.com/model-checking/rmc/issues/416",
-                            )
-                            .as_stmt(Location::none());
-                    }
-                    _ => unreachable!(),
-                };
+                assert!(matches!(pt.kind(), ty::Adt(..) | ty::Generator(..)));
                 let layout = self.layout_of(pt);
                 match &layout.variants {
                     Variants::Single { .. } => Stmt::skip(Location::none()),
                     Variants::Multiple { tag, tag_encoding, .. } => match tag_encoding {
                         TagEncoding::Direct => {
-                            let discr = def.discriminant_for_variant(self.tcx, *variant_index);
+                            let discr = pt.discriminant_for_variant(self.tcx, *variant_index);
                             let discr_t = self.codegen_enum_discr_typ(pt);
-                            let discr = Expr::int_constant(discr.val, self.codegen_ty(discr_t));
-                            self.codegen_place(place)
-                                .goto_expr
-                                .member("case", &self.symbol_table)
+                            let discr =
+                                Expr::int_constant(discr.unwrap().val, self.codegen_ty(discr_t));
+                            let place = self.codegen_place(place).goto_expr;
+                            self.codegen_discriminant_field(place, pt)
                                 .assign(discr, Location::none())
                         }
                         TagEncoding::Niche { dataful_variant, niche_variants, niche_start } => {
@@ -600,21 +946,22 @@ pub fn codegen_statement(&mut self, stmt: &Statement<'tcx>) -> Stmt {
                     },
                 }
             }
-            StatementKind::StorageLive(_) => Stmt::skip(Location::none()), // TODO: fix me
-            StatementKind::StorageDead(_) => Stmt::skip(Location::none()), // TODO: fix me
-            StatementKind::LlvmInlineAsm(_) => self
-                .codegen_unimplemented(
-                    "InlineAsm",
-                    Type::empty(),
-                    Location::none(),
-                    "https://github.com/model-checking/rmc/issues/2",
-                )
-                .as_stmt(Location::none()),
+            // All locals are declared at the start of the function. We also declare a local
+            // again when its storage becomes live, and mark it dead when its storage is
+            // deallocated, so that CBMC reports accesses through pointers to dead locals.
+            StatementKind::StorageLive(l) => {
+                Stmt::decl(self.codegen_local_symbol(*l), None, Location::none())
+            }
+            StatementKind::StorageDead(l) => {
+                Stmt::dead(self.codegen_local_symbol(*l), Location::none())
+            }
+            StatementKind::LlvmInlineAsm(asm) => self.codegen_llvm_inline_asm(asm, loc.clone()),
             StatementKind::CopyNonOverlapping(box mir::CopyNonOverlapping {
                 ref src,
                 ref dst,
                 ref count,
             }) => {
+                let pointee = self.operand_ty(dst).builtin_deref(true).unwrap().ty;
                 let src = self.codegen_operand(src).cast_to(Type::void_pointer());
                 let dst = self.codegen_operand(dst);
                 let count = self.codegen_operand(count);
@@ -622,6 +969,21 @@ pub fn codegen_statement(&mut self, stmt: &Statement<'tcx>) -> Stmt {
                 let sz = Expr::int_constant(sz, Type::size_t());
                 let n = sz.mul(count);
                 let dst = dst.cast_to(Type::void_pointer());
+                for ptr in [&src, &dst] {
+                    checks.extend(self.codegen_alignment_check(
+                        ptr.clone(),
+                        pointee,
+                        "copy_nonoverlapping: pointer is aligned",
+                        loc.clone(),
+                    ));
+                }
+                checks.push(self.codegen_nonoverlapping_check(
+                    "copy_nonoverlapping",
+                    src.clone(),
+                    dst.clone(),
+                    n.clone(),
+                    loc.clone(),
+                ));
                 let e = BuiltinFn::Memcpy.call(vec![dst, src, n.clone()], Location::none());
 
                 // The C implementation of memcpy does not allow an invalid pointer for
@@ -643,6 +1005,38 @@ pub fn codegen_statement(&mut self, stmt: &Statement<'tcx>) -> Stmt {
             | StatementKind::Nop
             | StatementKind::Coverage { .. } => Stmt::skip(Location::none()),
         }
-        .with_location(self.codegen_span(&stmt.source_info.span))
+        .with_location(loc.clone());
+        let validity_check = self.codegen_union_read_validity_check(stmt, loc.clone());
+        if checks.is_empty() && validity_check.is_none() {
+            code
+        } else {
+            checks.push(code);
+            checks.extend(validity_check);
+            Stmt::block(checks, loc)
+        }
+    }
+}
+
+/// Collects the statements that write to `local`, i.e. assign it or set its discriminant.
+struct LocalWrites {
+    local: Local,
+    writes: Vec<mir::Location>,
+    /// Whether `local` is also modified by something else than a statement, or may be modified
+    /// through a pointer.
+    other_mutation: bool,
+}
+
+impl<'tcx> Visitor<'tcx> for LocalWrites {
+    fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, location: mir::Location) {
+        if place.local != self.local {
+            return;
+        }
+        match context {
+            PlaceContext::MutatingUse(MutatingUseContext::Store) => self.writes.push(location),
+            // Dropping a value does not change what it was before.
+            PlaceContext::MutatingUse(MutatingUseContext::Drop) => {}
+            PlaceContext::MutatingUse(_) => self.other_mutation = true,
+            PlaceContext::NonMutatingUse(_) | PlaceContext::NonUse(_) => {}
+        }
     }
 }
@@ -0,0 +1,456 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module models the pthread mutexes, read-write locks and condition variables that
+//! `std::sync::Mutex`, `RwLock` and `Condvar` are built on.
+//!
+//! The state of each object is kept in its own bytes, as words of `size_t`, which start at zero
+//! like the pthread initializers used by the standard library:
+//!  * a mutex holds its lock count, the thread that holds it, and whether it is recursive;
+//!  * a read-write lock holds its number of readers, whether it is write locked, and the writer;
+//!  * a condition variable counts the notifications it received.
+//!
+//! Every operation is an atomic block. A thread that must wait blocks until another thread
+//! changes the state. Without `-Z concurrency` there is no other thread, so waiting is reported
+//! as a deadlock, and so is a thread that locks a non-recursive mutex it already holds. With
+//! `-Z concurrency`, a thread that starts waiting counts itself as blocked, and a deadlock is
+//! reported when every live thread is blocked. A state change that may end a wait marks the
+//! blocked threads as runnable again.
+
+use crate::GotocCtx;
+use cbmc::goto_program::{Expr, Location, PropertyClass, Stmt, Type};
+use rustc_middle::mir::Place;
+
+// Error codes and mutex types of Linux.
+const EBUSY: i64 = 16;
+const EDEADLK: i64 = 35;
+const PTHREAD_MUTEX_RECURSIVE: i64 = 1;
+
+const MUTEX_COUNT: u64 = 0;
+const MUTEX_OWNER: u64 = 1;
+const MUTEX_RECURSIVE: u64 = 2;
+const RWLOCK_READERS: u64 = 0;
+const RWLOCK_WRITE_LOCKED: u64 = 1;
+const RWLOCK_WRITER: u64 = 2;
+const COND_NOTIFICATIONS: u64 = 0;
+
+/// The word `index` of the state of the object `obj` points to.
+fn state_word(obj: &Expr, index: u64) -> Expr {
+    obj.clone()
+        .cast_to(Type::size_t().to_pointer())
+        .plus(Expr::int_constant(index, Type::size_t()))
+        .dereference()
+}
+
+impl<'tcx> GotocCtx<'tcx> {
+    /// Blocks the current thread until `cond` holds, then runs `then` atomically with the last
+    /// check of `cond`. Reports a deadlock if no other thread can run while it waits.
+    pub fn codegen_wait_until(
+        &mut self,
+        cond: Expr,
+        then: Vec<Stmt>,
+        msg: &str,
+        loc: Location,
+    ) -> Stmt {
+        let mut wait = vec![];
+        if !self.concurrency_enabled() {
+            wait.push(self.codegen_assert(
+                cond.clone(),
+                PropertyClass::Deadlock,
+                msg,
+                loc.clone(),
+            ));
+            wait.push(Stmt::assume(cond, loc.clone()));
+            wait.extend(then);
+            return Stmt::atomic_block(wait, loc);
+        }
+        let live = self.codegen_live_threads();
+        let blocked = self.codegen_blocked_threads();
+        let block = Stmt::block(
+            vec![
+                blocked.clone().assign(blocked.clone().plus(Type::size_t().one()), loc.clone()),
+                self.codegen_assert(blocked.lt(live), PropertyClass::Deadlock, msg, loc.clone()),
+            ],
+            loc.clone(),
+        );
+        let announce = Stmt::if_then_else(cond.clone().not(), block, None, loc.clone());
+        wait.push(Stmt::assume(cond, loc.clone()));
+        wait.extend(then);
+        Stmt::block(
+            vec![
+                Stmt::atomic_block(vec![announce], loc.clone()),
+                Stmt::atomic_block(wait, loc.clone()),
+            ],
+            loc,
+        )
+    }
+
+    fn codegen_return_code(&mut self, p: &Place<'tcx>, code: i64) -> Stmt {
+        let ret_typ = self.codegen_ty(self.place_ty(p));
+        self.codegen_expr_to_place(p, Expr::int_constant(code, ret_typ))
+    }
+
+    /// `pthread_mutex_init(mutex, attr)`, where `attr` is null or holds the type of the mutex.
+    pub fn codegen_mutex_init(
+        &mut self,
+        mutex: Expr,
+        attr: Expr,
+        p: &Place<'tcx>,
+        loc: Location,
+    ) -> Stmt {
+        let zero = Type::size_t().zero();
+        let kind = attr.clone().cast_to(Type::c_int().to_pointer()).dereference();
+        let recursive = attr
+            .clone()
+            .neq(attr.typ().null())
+            .and(kind.eq(Expr::int_constant(PTHREAD_MUTEX_RECURSIVE, Type::c_int())));
+        Stmt::block(
+            vec![
+                state_word(&mutex, MUTEX_COUNT).assign(zero.clone(), loc.clone()),
+                state_word(&mutex, MUTEX_OWNER).assign(zero, loc.clone()),
+                state_word(&mutex, MUTEX_RECURSIVE)
+                    .assign(recursive.cast_to(Type::size_t()), loc.clone()),
+                self.codegen_return_code(p, 0),
+            ],
+            loc,
+        )
+    }
+
+    /// `pthread_mutexattr_settype(attr, kind)`
+    pub fn codegen_mutexattr_settype(
+        &mut self,
+        attr: Expr,
+        kind: Expr,
+        p: &Place<'tcx>,
+        loc: Location,
+    ) -> Stmt {
+        let attr_kind = attr.cast_to(Type::c_int().to_pointer()).dereference();
+        Stmt::block(
+            vec![
+                attr_kind.assign(kind.cast_to(Type::c_int()), loc.clone()),
+                self.codegen_return_code(p, 0),
+            ],
+            loc,
+        )
+    }
+
+    /// Locks `mutex`, waiting until it is unlocked unless the current thread holds it and it is
+    /// recursive.
+    fn codegen_mutex_acquire(&mut self, mutex: &Expr, loc: Location) -> Stmt {
+        let count = state_word(mutex, MUTEX_COUNT);
+        let owner = state_word(mutex, MUTEX_OWNER);
+        let recursive = state_word(mutex, MUTEX_RECURSIVE);
+        let zero = Type::size_t().zero();
+        let me = self.codegen_current_thread();
+        let held_by_me = count.clone().neq(zero.clone()).and(owner.clone().eq(me.clone()));
+        let self_deadlock = Stmt::block(
+            vec![
+                self.codegen_assert_false(
+                    PropertyClass::Deadlock,
+                    "deadlock: a thread locks a mutex it holds",
+                    loc.clone(),
+                ),
+                Stmt::assume(Expr::bool_false(), loc.clone()),
+            ],
+            loc.clone(),
+        );
+        let can_lock = count.clone().eq(zero.clone()).or(held_by_me.clone());
+        let wait = self.codegen_wait_until(
+            can_lock,
+            vec![
+                count.clone().assign(count.plus(Type::size_t().one()), loc.clone()),
+                owner.assign(me, loc.clone()),
+            ],
+            "deadlock: a thread waits for a mutex that is never unlocked",
+            loc.clone(),
+        );
+        // Only the current thread can lock the mutex or unlock it while it holds it, so the
+        // check for a self deadlock need not be atomic with the wait.
+        Stmt::block(
+            vec![
+                Stmt::atomic_block(
+                    vec![Stmt::if_then_else(
+                        held_by_me.and(recursive.eq(zero)),
+                        self_deadlock,
+                        None,
+                        loc.clone(),
+                    )],
+                    loc.clone(),
+                ),
+                wait,
+            ],
+            loc,
+        )
+    }
+
+    fn codegen_mutex_release(&mut self, mutex: &Expr, loc: Location) -> Stmt {
+        let count = state_word(mutex, MUTEX_COUNT);
+        Stmt::block(
+            vec![
+                count.clone().assign(count.sub(Type::size_t().one()), loc.clone()),
+                self.codegen_wake_blocked_threads(loc.clone()),
+            ],
+            loc,
+        )
+    }
+
+    /// `pthread_mutex_lock(mutex)`
+    pub fn codegen_mutex_lock(&mut self, mutex: Expr, p: &Place<'tcx>, loc: Location) -> Stmt {
+        Stmt::block(
+            vec![self.codegen_mutex_acquire(&mutex, loc.clone()), self.codegen_return_code(p, 0)],
+            loc,
+        )
+    }
+
+    /// `pthread_mutex_trylock(mutex)` fails with `EBUSY` instead of waiting.
+    pub fn codegen_mutex_trylock(&mut self, mutex: Expr, p: &Place<'tcx>, loc: Location) -> Stmt {
+        let count = state_word(&mutex, MUTEX_COUNT);
+        let owner = state_word(&mutex, MUTEX_OWNER);
+        let recursive = state_word(&mutex, MUTEX_RECURSIVE);
+        let zero = Type::size_t().zero();
+        let me = self.codegen_current_thread();
+        let can_lock = count
+            .clone()
+            .eq(zero.clone())
+            .or(recursive.neq(zero).and(owner.clone().eq(me.clone())));
+        let lock = Stmt::block(
+            vec![
+                count.clone().assign(count.plus(Type::size_t().one()), loc.clone()),
+                owner.assign(me, loc.clone()),
+                self.codegen_return_code(p, 0),
+            ],
+            loc.clone(),
+        );
+        let busy = self.codegen_return_code(p, EBUSY);
+        Stmt::atomic_block(vec![Stmt::if_then_else(can_lock, lock, Some(busy), loc.clone())], loc)
+    }
+
+    /// `pthread_mutex_unlock(mutex)`
+    pub fn codegen_mutex_unlock(&mut self, mutex: Expr, p: &Place<'tcx>, loc: Location) -> Stmt {
+        Stmt::block(
+            vec![
+                Stmt::atomic_block(
+                    vec![self.codegen_mutex_release(&mutex, loc.clone())],
+                    loc.clone(),
+                ),
+                self.codegen_return_code(p, 0),
+            ],
+            loc,
+        )
+    }
+
+    /// Runs `lock` once `unlocked` holds, or, if `try_lock`, fails with `EBUSY` unless it holds.
+    /// Fails with `EDEADLK` if the current thread holds the write lock of `rwlock`.
+    fn codegen_rwlock_acquire(
+        &mut self,
+        rwlock: Expr,
+        unlocked: Expr,
+        lock: Stmt,
+        try_lock: bool,
+        p: &Place<'tcx>,
+        loc: Location,
+    ) -> Stmt {
+        let write_locked = state_word(&rwlock, RWLOCK_WRITE_LOCKED);
+        let writer = state_word(&rwlock, RWLOCK_WRITER);
+        let me = self.codegen_current_thread();
+        let held_by_me = write_locked.neq(Type::size_t().zero()).and(writer.eq(me));
+        let deadlock = self.codegen_return_code(p, EDEADLK);
+        if try_lock {
+            let busy = self.codegen_return_code(p, EBUSY);
+            let acquire = Stmt::if_then_else(unlocked, lock, Some(busy), loc.clone());
+            return Stmt::atomic_block(
+                vec![Stmt::if_then_else(held_by_me, deadlock, Some(acquire), loc.clone())],
+                loc,
+            );
+        }
+        // Only the current thread can release the write lock while it holds it, so the check
+        // need not be atomic with the wait.
+        let mine = self.gen_temp_variable(Type::bool(), loc.clone()).to_expr();
+        let wait = self.codegen_wait_until(
+            unlocked,
+            vec![lock],
+            "deadlock: a thread waits for a read-write lock that is never unlocked",
+            loc.clone(),
+        );
+        Stmt::block(
+            vec![
+                Stmt::decl(mine.clone(), None, loc.clone()),
+                Stmt::atomic_block(
+                    vec![mine.clone().assign(held_by_me, loc.clone())],
+                    loc.clone(),
+                ),
+                Stmt::if_then_else(mine, deadlock, Some(wait), loc.clone()),
+            ],
+            loc,
+        )
+    }
+
+    /// `pthread_rwlock_rdlock(rwlock)` and `pthread_rwlock_tryrdlock(rwlock)`. Like glibc, read
+    /// locking fails with `EDEADLK` if the current thread holds the write lock.
+    pub fn codegen_rwlock_rdlock(
+        &mut self,
+        rwlock: Expr,
+        try_lock: bool,
+        p: &Place<'tcx>,
+        loc: Location,
+    ) -> Stmt {
+        let readers = state_word(&rwlock, RWLOCK_READERS);
+        let write_locked = state_word(&rwlock, RWLOCK_WRITE_LOCKED);
+        let unlocked = write_locked.eq(Type::size_t().zero());
+        let lock = Stmt::block(
+            vec![
+                readers.clone().assign(readers.plus(Type::size_t().one()), loc.clone()),
+                self.codegen_return_code(p, 0),
+            ],
+            loc.clone(),
+        );
+        self.codegen_rwlock_acquire(rwlock, unlocked, lock, try_lock, p, loc)
+    }
+
+    /// `pthread_rwlock_wrlock(rwlock)` and `pthread_rwlock_trywrlock(rwlock)`. Like glibc, write
+    /// locking fails with `EDEADLK` if the current thread holds the write lock.
+    pub fn codegen_rwlock_wrlock(
+        &mut self,
+        rwlock: Expr,
+        try_lock: bool,
+        p: &Place<'tcx>,
+        loc: Location,
+    ) -> Stmt {
+        let readers = state_word(&rwlock, RWLOCK_READERS);
+        let write_locked = state_word(&rwlock, RWLOCK_WRITE_LOCKED);
+        let writer = state_word(&rwlock, RWLOCK_WRITER);
+        let zero = Type::size_t().zero();
+        let me = self.codegen_current_thread();
+        let unlocked = write_locked.clone().eq(zero.clone()).and(readers.eq(zero));
+        let lock = Stmt::block(
+            vec![
+                write_locked.assign(Type::size_t().one(), loc.clone()),
+                writer.assign(me, loc.clone()),
+                self.codegen_return_code(p, 0),
+            ],
+            loc.clone(),
+        );
+        self.codegen_rwlock_acquire(rwlock, unlocked, lock, try_lock, p, loc)
+    }
+
+    /// `pthread_rwlock_unlock(rwlock)` releases the write lock if it is held, and a read lock
+    /// otherwise.
+    pub fn codegen_rwlock_unlock(&mut self, rwlock: Expr, p: &Place<'tcx>, loc: Location) -> Stmt {
+        let readers = state_word(&rwlock, RWLOCK_READERS);
+        let write_locked = state_word(&rwlock, RWLOCK_WRITE_LOCKED);
+        let zero = Type::size_t().zero();
+        let unlock = Stmt::if_then_else(
+            write_locked.clone().neq(zero.clone()),
+            write_locked.assign(zero, loc.clone()),
+            Some(readers.clone().assign(readers.sub(Type::size_t().one()), loc.clone())),
+            loc.clone(),
+        );
+        let wake = self.codegen_wake_blocked_threads(loc.clone());
+        Stmt::block(
+            vec![
+                Stmt::atomic_block(vec![unlock, wake], loc.clone()),
+                self.codegen_return_code(p, 0),
+            ],
+            loc,
+        )
+    }
+
+    /// `pthread_cond_signal(cond)` and `pthread_cond_broadcast(cond)` wake up every thread that
+    /// waits on `cond`, which POSIX allows for both.
+    pub fn codegen_cond_notify(&mut self, cond: Expr, p: &Place<'tcx>, loc: Location) -> Stmt {
+        let notifications = state_word(&cond, COND_NOTIFICATIONS);
+        let notify =
+            notifications.clone().assign(notifications.plus(Type::size_t().one()), loc.clone());
+        let wake = self.codegen_wake_blocked_threads(loc.clone());
+        Stmt::block(
+            vec![
+                Stmt::atomic_block(vec![notify, wake], loc.clone()),
+                self.codegen_return_code(p, 0),
+            ],
+            loc,
+        )
+    }
+
+    /// Unlocks `mutex` and waits on `cond` until it is notified, or, if `timed_out` is given,
+    /// until it times out, which it may do at any time. Then locks `mutex` again. Spurious
+    /// wakeups are not modeled.
+    fn codegen_cond_wait_inner(
+        &mut self,
+        cond: Expr,
+        mutex: Expr,
+        timed_out: Option<Expr>,
+        loc: Location,
+    ) -> Stmt {
+        let notifications = state_word(&cond, COND_NOTIFICATIONS);
+        let seen = self.gen_temp_variable(Type::size_t(), loc.clone()).to_expr();
+        let release = Stmt::atomic_block(
+            vec![
+                seen.clone().assign(notifications.clone(), loc.clone()),
+                self.codegen_mutex_release(&mutex, loc.clone()),
+            ],
+            loc.clone(),
+        );
+        let notified = notifications.neq(seen.clone());
+        let wait = match timed_out {
+            // A wait that may time out never deadlocks.
+            Some(timed_out) => Stmt::if_then_else(
+                timed_out.not(),
+                Stmt::assume(notified, loc.clone()),
+                None,
+                loc.clone(),
+            ),
+            None => self.codegen_wait_until(
+                notified,
+                vec![],
+                "deadlock: a thread waits for a condition variable that is never notified",
+                loc.clone(),
+            ),
+        };
+        Stmt::block(
+            vec![
+                Stmt::decl(seen, None, loc.clone()),
+                release,
+                wait,
+                self.codegen_mutex_acquire(&mutex, loc.clone()),
+            ],
+            loc,
+        )
+    }
+
+    /// `pthread_cond_wait(cond, mutex)`
+    pub fn codegen_cond_wait(
+        &mut self,
+        cond: Expr,
+        mutex: Expr,
+        p: &Place<'tcx>,
+        loc: Location,
+    ) -> Stmt {
+        Stmt::block(
+            vec![
+                self.codegen_cond_wait_inner(cond, mutex, None, loc.clone()),
+                self.codegen_return_code(p, 0),
+            ],
+            loc,
+        )
+    }
+
+    /// `Condvar::wait_timeout(&self, mutex, dur)` of `std::sys` returns whether it timed out.
+    /// The duration is ignored: the wait may time out whenever it has not been notified.
+    pub fn codegen_cond_wait_timeout(
+        &mut self,
+        cond: Expr,
+        mutex: Expr,
+        p: &Place<'tcx>,
+        loc: Location,
+    ) -> Stmt {
+        let timed_out = self.gen_temp_variable(Type::bool(), loc.clone()).to_expr();
+        Stmt::block(
+            vec![
+                Stmt::decl(timed_out.clone(), Some(Type::bool().nondet()), loc.clone()),
+                self.codegen_cond_wait_inner(cond, mutex, Some(timed_out.clone()), loc.clone()),
+                self.codegen_expr_to_place(p, timed_out.cast_to(Type::c_bool())),
+            ],
+            loc,
+        )
+    }
+}
@@ -0,0 +1,139 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module models threads, with `-Z concurrency`.
+//!
+//! `std::thread::spawn` creates its thread with `pthread_create`, which we replace with a CBMC
+//! thread (`START_THREAD`) that runs the start routine. CBMC then explores the interleavings of
+//! the threads. Atomic intrinsics are atomic blocks, which CBMC does not interleave, so the
+//! interleavings are the sequentially consistent executions of the program.
+//!
+//! The id of a thread is the address of a flag, allocated when the thread is created, that the
+//! thread sets when its start routine returns. `pthread_join` waits until the flag is set. Each
+//! thread keeps its id in the thread local `__rmc_current_thread`, which is 0 in the main thread.
+//!
+//! To report deadlocks, the globals `__rmc_live_threads` and `__rmc_blocked_threads` count the
+//! threads that have not exited, and the threads that wait for a state change (see `sync.rs`).
+
+use crate::GotocCtx;
+use cbmc::goto_program::{BuiltinFn, Expr, Location, Stmt, Symbol, Type};
+use rustc_middle::mir::Place;
+
+const CURRENT_THREAD: &str = "__rmc_current_thread";
+const LIVE_THREADS: &str = "__rmc_live_threads";
+const BLOCKED_THREADS: &str = "__rmc_blocked_threads";
+
+impl<'tcx> GotocCtx<'tcx> {
+    pub fn concurrency_enabled(&self) -> bool {
+        self.tcx.sess.opts.debugging_opts.concurrency
+    }
+
+    /// The id of the thread that runs the current code.
+    pub fn codegen_current_thread(&mut self) -> Expr {
+        self.ensure(CURRENT_THREAD, |_, name| {
+            Symbol::static_variable(name, name, Type::size_t(), Location::none())
+                .with_is_thread_local(true)
+                .with_value(Type::size_t().zero())
+        })
+        .to_expr()
+    }
+
+    /// The number of threads that have not exited, starting with the main thread.
+    pub fn codegen_live_threads(&mut self) -> Expr {
+        self.ensure(LIVE_THREADS, |_, name| {
+            Symbol::static_variable(name, name, Type::size_t(), Location::none())
+                .with_value(Type::size_t().one())
+        })
+        .to_expr()
+    }
+
+    /// The number of threads that wait for another thread to change the state they wait on.
+    pub fn codegen_blocked_threads(&mut self) -> Expr {
+        self.ensure(BLOCKED_THREADS, |_, name| {
+            Symbol::static_variable(name, name, Type::size_t(), Location::none())
+                .with_value(Type::size_t().zero())
+        })
+        .to_expr()
+    }
+
+    /// Marks every blocked thread as runnable, after a state change that may let it go on. A
+    /// thread that still cannot go on is not counted again, so a deadlock that only happens
+    /// after such a change may be missed, but no deadlock is reported where a thread can run.
+    pub fn codegen_wake_blocked_threads(&mut self, loc: Location) -> Stmt {
+        if !self.concurrency_enabled() {
+            return Stmt::skip(loc);
+        }
+        self.codegen_blocked_threads().assign(Type::size_t().zero(), loc)
+    }
+
+    /// `pthread_create(thread, attr, start_routine, arg)` runs `start_routine(arg)` in a new
+    /// thread, and stores the id of the new thread in `*thread`. It always succeeds.
+    pub fn codegen_pthread_create(
+        &mut self,
+        mut fargs: Vec<Expr>,
+        p: &Place<'tcx>,
+        loc: Location,
+    ) -> Stmt {
+        let thread = fargs.remove(0).dereference();
+        let _attr = fargs.remove(0);
+        let start_routine = fargs.remove(0);
+        let arg = fargs.remove(0);
+        let flag_typ = Type::c_bool().to_pointer();
+        let exited = self.gen_temp_variable(flag_typ.clone(), loc.clone()).to_expr();
+        let alloc = BuiltinFn::Malloc
+            .call(vec![Expr::int_constant(1, Type::size_t())], loc.clone())
+            .cast_to(flag_typ);
+        let current_thread = self.codegen_current_thread();
+        let live = self.codegen_live_threads();
+        let body = Stmt::block(
+            vec![
+                current_thread.assign(exited.clone().cast_to(Type::size_t()), loc.clone()),
+                start_routine.dereference().call(vec![arg]).as_stmt(loc.clone()),
+                Stmt::atomic_block(
+                    vec![
+                        exited.clone().dereference().assign(Expr::c_true(), loc.clone()),
+                        live.clone().assign(live.clone().sub(Type::size_t().one()), loc.clone()),
+                        self.codegen_wake_blocked_threads(loc.clone()),
+                    ],
+                    loc.clone(),
+                ),
+            ],
+            loc.clone(),
+        );
+        let ret_typ = self.codegen_ty(self.place_ty(p));
+        Stmt::block(
+            vec![
+                Stmt::decl(exited.clone(), Some(alloc), loc.clone()),
+                exited.clone().dereference().assign(Expr::c_false(), loc.clone()),
+                thread.clone().assign(exited.cast_to(thread.typ().clone()), loc.clone()),
+                Stmt::atomic_block(
+                    vec![live.clone().assign(live.plus(Type::size_t().one()), loc.clone())],
+                    loc.clone(),
+                ),
+                Stmt::start_thread(body, loc.clone()),
+                self.codegen_expr_to_place(p, ret_typ.zero()),
+            ],
+            loc,
+        )
+    }
+
+    /// `pthread_join(thread, retval)` waits until `thread` exits. The standard library passes a
+    /// null `retval`, so we do not write the result of the start routine.
+    pub fn codegen_pthread_join(
+        &mut self,
+        mut fargs: Vec<Expr>,
+        p: &Place<'tcx>,
+        loc: Location,
+    ) -> Stmt {
+        let thread = fargs.remove(0);
+        let exited = thread.cast_to(Type::c_bool().to_pointer()).dereference();
+        let wait = self.codegen_wait_until(
+            exited.cast_to(Type::bool()),
+            vec![],
+            "deadlock: a thread waits for a thread that never exits",
+            loc.clone(),
+        );
+        let ret_typ = self.codegen_ty(self.place_ty(p));
+        Stmt::block(vec![wait, self.codegen_expr_to_place(p, ret_typ.zero())], loc)
+    }
+}
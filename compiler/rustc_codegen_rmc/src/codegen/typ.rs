@@ -6,6 +6,7 @@
 use cbmc::{btree_map, NO_PRETTY_NAME};
 use cbmc::{InternString, InternedString};
 use rustc_ast::ast::Mutability;
+use rustc_hir::{LangItem, Unsafety};
 use rustc_index::vec::IndexVec;
 use rustc_middle::mir::{HasLocalDecls, Local, Operand, Place, Rvalue};
 use rustc_middle::ty::layout::LayoutOf;
@@ -197,6 +198,46 @@ fn closure_sig(
         self.sig_with_closure_untupled(sig)
     }
 
+    /// The body of a generator is its `resume` function, which takes the pinned generator and
+    /// the resume argument, and returns whether it yielded or completed:
+    ///     fn(Pin<&mut Gen>, R) -> GeneratorState<Y, Ret>
+    /// This follows `fn_sig_for_fn_abi` in rustc_middle/src/ty/layout.rs.
+    fn generator_sig(
+        &self,
+        gen_ty: Ty<'tcx>,
+        substs: ty::subst::SubstsRef<'tcx>,
+    ) -> ty::PolyFnSig<'tcx> {
+        let sig = substs.as_generator().poly_sig();
+        let bound_vars = self.tcx.mk_bound_variable_kinds(
+            sig.bound_vars().iter().chain(iter::once(ty::BoundVariableKind::Region(ty::BrEnv))),
+        );
+        let br = ty::BoundRegion {
+            var: ty::BoundVar::from_usize(bound_vars.len() - 1),
+            kind: ty::BoundRegionKind::BrEnv,
+        };
+        let env_region = ty::ReLateBound(ty::INNERMOST, br);
+        let env_ty = self.tcx.mk_mut_ref(self.tcx.mk_region(env_region), gen_ty);
+
+        let pin_did = self.tcx.require_lang_item(LangItem::Pin, None);
+        let pin_substs = self.tcx.intern_substs(&[env_ty.into()]);
+        let env_ty = self.tcx.mk_adt(self.tcx.adt_def(pin_did), pin_substs);
+
+        let sig = sig.skip_binder();
+        let state_did = self.tcx.require_lang_item(LangItem::GeneratorState, None);
+        let state_substs = self.tcx.intern_substs(&[sig.yield_ty.into(), sig.return_ty.into()]);
+        let ret_ty = self.tcx.mk_adt(self.tcx.adt_def(state_did), state_substs);
+        ty::Binder::bind_with_vars(
+            self.tcx.mk_fn_sig(
+                [env_ty, sig.resume_ty].iter(),
+                &ret_ty,
+                false,
+                Unsafety::Normal,
+                Abi::Rust,
+            ),
+            bound_vars,
+        )
+    }
+
     pub fn fn_sig_of_instance(&self, instance: Instance<'tcx>) -> Option<ty::PolyFnSig<'tcx>> {
         let fntyp = instance.ty(self.tcx, ty::ParamEnv::reveal_all());
         self.monomorphize(match fntyp.kind() {
@@ -211,7 +252,7 @@ pub fn fn_sig_of_instance(&self, instance: Instance<'tcx>) -> Option<ty::PolyFnS
                 }
                 Some(sig)
             }
-            ty::Generator(_def_id, _substs, _movability) => None,
+            ty::Generator(_, substs, _) => Some(self.generator_sig(fntyp, substs)),
             _ => unreachable!("Can't get function signature of type: {:?}", fntyp),
         })
     }
@@ -548,7 +589,7 @@ fn codegen_ty_inner(&mut self, ty: Ty<'tcx>) -> Type {
             }
             ty::FnPtr(sig) => self.codegen_function_sig(*sig).to_pointer(),
             ty::Closure(_, subst) => self.codegen_ty_closure(ty, subst),
-            ty::Generator(_, subst, _) => self.codegen_ty_generator(subst),
+            ty::Generator(def_id, subst, _) => self.codegen_ty_generator(ty, *def_id, subst),
             ty::Never => {
                 self.ensure_struct(NEVER_TYPE_EMPTY_STRUCT_NAME, NO_PRETTY_NAME, |_, _| vec![])
             }
@@ -736,13 +777,65 @@ fn codegen_ty_closure(&mut self, t: Ty<'tcx>, substs: ty::subst::SubstsRef<'tcx>
         })
     }
 
-    /// Preliminary support for the Generator type kind. The core functionality remains
-    /// unimplemented, but this way we fail at verification time only if paths that
-    /// rely on Generator types are used.
-    fn codegen_ty_generator(&mut self, substs: ty::subst::SubstsRef<'tcx>) -> Type {
-        let tys = substs.as_generator().upvar_tys().map(|t| self.codegen_ty(t)).collect();
-        let output = self.codegen_ty(substs.as_generator().return_ty());
-        Type::code_with_unnamed_parameters(tys, output)
+    /// A generator is a state machine, whose layout is computed by `generator_layout` in
+    /// rustc_middle/src/ty/layout.rs. Its upvars and the discriminant of its state are stored
+    /// directly, and each state (variant) stores the locals that are live across the
+    /// corresponding suspension point. The states overlap, so a generator is translated to
+    /// union Gen {
+    ///     struct Gen::DirectFields {
+    ///         T1 0; T2 1; u8 case;
+    ///     } direct_fields;
+    ///     struct Gen::Unresumed {
+    ///     } Unresumed;
+    ///     ...
+    ///     struct Gen::Suspend0 {
+    ///         $pad0; T3 0; T4 1;
+    ///     } Suspend0;
+    ///     ...
+    /// }
+    /// where every field is at the offset given by the layout of the generator. A local that is
+    /// live across several suspension points is at the same offset in all the states it is in.
+    fn codegen_ty_generator(
+        &mut self,
+        ty: Ty<'tcx>,
+        def_id: DefId,
+        substs: ty::subst::SubstsRef<'tcx>,
+    ) -> Type {
+        self.ensure_union(self.ty_mangled_name(ty), Some(self.ty_pretty_name(ty)), |ctx, name| {
+            let layout = ctx.layout_of(ty).layout;
+            let variants = match &layout.variants {
+                Variants::Multiple { tag_encoding: TagEncoding::Direct, variants, .. } => variants,
+                _ => unreachable!("a generator has a direct tag"),
+            };
+            let discr_t = ctx.codegen_enum_discr_typ(ty);
+            let direct_flds: Vec<_> = substs
+                .as_generator()
+                .prefix_tys()
+                .enumerate()
+                .map(|(i, t)| (GotocCtx::tuple_fld_name(i), t))
+                .chain(iter::once(("case".to_string(), discr_t)))
+                .collect();
+            let direct_fields =
+                ctx.ensure_struct(&format!("{}::DirectFields", name), NO_PRETTY_NAME, |ctx, _| {
+                    ctx.codegen_struct_fields(direct_flds, layout, 0)
+                });
+            let mut fields = vec![Type::datatype_component("direct_fields", direct_fields)];
+            let state_tys: Vec<Vec<Ty<'tcx>>> =
+                substs.as_generator().state_tys(def_id, ctx.tcx).map(|tys| tys.collect()).collect();
+            for (idx, tys) in state_tys.into_iter().enumerate() {
+                let idx = VariantIdx::from_usize(idx);
+                let variant_name = GotocCtx::generator_variant_name(idx);
+                let flds =
+                    tys.into_iter().enumerate().map(|(i, t)| (GotocCtx::tuple_fld_name(i), t));
+                let variant = ctx.ensure_struct(
+                    &format!("{}::{}", name, variant_name),
+                    NO_PRETTY_NAME,
+                    |ctx, _| ctx.codegen_struct_fields(flds.collect(), &variants[idx], 0),
+                );
+                fields.push(Type::datatype_component(&variant_name, variant));
+            }
+            fields
+        })
     }
 
     pub fn codegen_fat_ptr(&mut self, mir_type: Ty<'tcx>) -> Type {
@@ -808,6 +901,7 @@ pub fn codegen_ty_ref(&mut self, pointee_type: Ty<'tcx>) -> Type {
             | ty::Closure(..)
             | ty::Float(_)
             | ty::Foreign(_)
+            | ty::Generator(..)
             | ty::Int(_)
             | ty::RawPtr(_)
             | ty::Ref(..)
@@ -827,7 +921,6 @@ pub fn codegen_ty_ref(&mut self, pointee_type: Ty<'tcx>) -> Type {
             // For soundess, hold off on generating them till we have test-cases.
             ty::Bound(_, _) => todo!("{:?} {:?}", pointee_type, pointee_type.kind()),
             ty::Error(_) => todo!("{:?} {:?}", pointee_type, pointee_type.kind()),
-            ty::Generator(_, _, _) => todo!("{:?} {:?}", pointee_type, pointee_type.kind()),
             ty::GeneratorWitness(_) => todo!("{:?} {:?}", pointee_type, pointee_type.kind()),
             ty::Infer(_) => todo!("{:?} {:?}", pointee_type, pointee_type.kind()),
             ty::Param(_) => todo!("{:?} {:?}", pointee_type, pointee_type.kind()),
@@ -0,0 +1,207 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module models the unwinding of panics, with `-Z panic-unwinding`.
+//!
+//! Without it, a panic is an assertion failure, and the cleanup blocks of the MIR are never
+//! reached. With it, a panic continues in the cleanup block of the terminator that panicked,
+//! which runs the drop glue of the live locals and ends with `Resume`, and then in the cleanup
+//! blocks of its callers, until the `try` intrinsic that implements `catch_unwind` catches it.
+//!
+//! A function that unwinds returns with the global flag `__rmc_unwinding` set, and every call
+//! that may unwind checks the flag when the callee returns. The global `__rmc_catch_depth`
+//! counts the `try` intrinsics being executed, so that a panic is only reported as a failure
+//! when nothing catches it. The flag `__rmc_panicking` is set from the panic until it is caught,
+//! which is what `std::thread::panicking` returns, e.g. to poison the locks that are released
+//! while unwinding. All three are thread locals, so that each thread unwinds on its own.
+
+use crate::GotocCtx;
+use cbmc::goto_program::{Expr, Location, PropertyClass, Stmt, Symbol, Type};
+use rustc_middle::mir::{BasicBlock, Place, TerminatorKind};
+
+const UNWINDING: &str = "__rmc_unwinding";
+const CATCH_DEPTH: &str = "__rmc_catch_depth";
+const PANICKING: &str = "__rmc_panicking";
+
+impl<'tcx> GotocCtx<'tcx> {
+    pub fn unwinding_enabled(&self) -> bool {
+        self.tcx.sess.opts.debugging_opts.panic_unwinding
+    }
+
+    /// The unwinding state is thread local, and starts with the value `init` in each thread.
+    fn unwinding_state(&mut self, name: &str, init: Expr) -> Expr {
+        self.ensure(name, |_, name| {
+            Symbol::static_variable(name, name, init.typ().clone(), Location::none())
+                .with_is_thread_local(true)
+                .with_value(init)
+        })
+        .to_expr()
+    }
+
+    fn unwinding_flag(&mut self) -> Expr {
+        self.unwinding_state(UNWINDING, Expr::bool_false())
+    }
+
+    fn catch_depth(&mut self) -> Expr {
+        self.unwinding_state(CATCH_DEPTH, Type::unsigned_int(32).zero())
+    }
+
+    fn panicking_flag(&mut self) -> Expr {
+        self.unwinding_state(PANICKING, Expr::bool_false())
+    }
+
+    /// Whether the current thread is unwinding because of a panic. Without unwinding, a panic
+    /// ends the execution, so code never runs while panicking.
+    pub fn codegen_panicking(&mut self) -> Expr {
+        if !self.unwinding_enabled() {
+            return Expr::bool_false();
+        }
+        self.panicking_flag()
+    }
+
+    /// The cleanup block of the terminator of the current block, if it has one.
+    fn current_cleanup(&self) -> Option<BasicBlock> {
+        let bb = self.current_fn().current_bb();
+        match self.current_fn().mir()[bb].terminator().kind {
+            TerminatorKind::Call { cleanup, .. } | TerminatorKind::Assert { cleanup, .. } => {
+                cleanup
+            }
+            TerminatorKind::Drop { unwind, .. } => unwind,
+            _ => None,
+        }
+    }
+
+    /// Unwinds to the cleanup block `cleanup` if there is one, and otherwise to the caller.
+    fn codegen_unwind_to(&mut self, cleanup: Option<BasicBlock>, loc: Location) -> Stmt {
+        match cleanup {
+            Some(bb) => Stmt::goto(self.current_fn().find_label(&bb), loc),
+            None => {
+                let flag = self.unwinding_flag();
+                Stmt::block(
+                    vec![
+                        flag.assign(Expr::bool_true(), loc.clone()),
+                        self.codegen_return(loc.clone()),
+                    ],
+                    loc,
+                )
+            }
+        }
+    }
+
+    /// A panic fails the assertion `msg` unless a `catch_unwind` catches it, and starts
+    /// unwinding. This covers both calls to the panic functions and failed `Assert` terminators,
+    /// e.g. bounds and overflow checks.
+    pub fn codegen_unwinding_panic(
+        &mut self,
+        property_class: PropertyClass,
+        msg: &str,
+        loc: Location,
+    ) -> Stmt {
+        let depth = self.catch_depth();
+        let caught = depth.clone().gt(depth.typ().zero());
+        let cleanup = self.current_cleanup();
+        let panicking = self.panicking_flag();
+        Stmt::block(
+            vec![
+                self.codegen_assert(caught, property_class, msg, loc.clone()),
+                panicking.assign(Expr::bool_true(), loc.clone()),
+                self.codegen_unwind_to(cleanup, loc.clone()),
+            ],
+            loc,
+        )
+    }
+
+    /// `Resume` ends the cleanup blocks of a function, and continues unwinding in its caller.
+    pub fn codegen_resume(&mut self, loc: Location) -> Stmt {
+        if !self.unwinding_enabled() {
+            return self.codegen_assert_false(
+                PropertyClass::UnsupportedConstruct,
+                "resume instruction",
+                loc,
+            );
+        }
+        self.codegen_unwind_to(None, loc)
+    }
+
+    /// After a call or a drop returns: if the callee unwinds, continues unwinding in the cleanup
+    /// block `cleanup`, or in the caller. A panic in a cleanup block aborts the program.
+    pub fn codegen_unwind_check(
+        &mut self,
+        cleanup: Option<BasicBlock>,
+        loc: Location,
+    ) -> Option<Stmt> {
+        if !self.unwinding_enabled() {
+            return None;
+        }
+        let flag = self.unwinding_flag();
+        let bb = self.current_fn().current_bb();
+        let unwind = if self.current_fn().mir()[bb].is_cleanup {
+            Stmt::block(
+                vec![
+                    self.codegen_assert_false(
+                        PropertyClass::Assertion,
+                        "panic while unwinding aborts the program",
+                        loc.clone(),
+                    ),
+                    Stmt::assume(Expr::bool_false(), loc.clone()),
+                ],
+                loc.clone(),
+            )
+        } else if cleanup.is_some() {
+            // The flag only records that a callee returns because it unwinds.
+            Stmt::block(
+                vec![
+                    flag.clone().assign(Expr::bool_false(), loc.clone()),
+                    self.codegen_unwind_to(cleanup, loc.clone()),
+                ],
+                loc.clone(),
+            )
+        } else {
+            self.codegen_return(loc.clone())
+        };
+        Some(Stmt::if_then_else(flag, unwind, None, loc))
+    }
+
+    /// `try(try_fn, data, catch_fn)` calls `try_fn(data)` and, if it unwinds,
+    /// `catch_fn(data, payload)`. It returns 1 if it caught a panic, and 0 otherwise.
+    pub fn codegen_try(&mut self, mut fargs: Vec<Expr>, p: &Place<'tcx>, loc: Location) -> Stmt {
+        let try_fn = fargs.remove(0);
+        let data = fargs.remove(0);
+        let catch_fn = fargs.remove(0);
+        let ret_typ = self.codegen_ty(self.place_ty(p));
+        let call_try = try_fn.dereference().call(vec![data.clone()]).as_stmt(loc.clone());
+        if !self.unwinding_enabled() {
+            // Panics do not unwind, so there is nothing to catch.
+            return Stmt::block(vec![call_try, self.codegen_expr_to_place(p, ret_typ.zero())], loc);
+        }
+        let depth = self.catch_depth();
+        let flag = self.unwinding_flag();
+        let panicking = self.panicking_flag();
+        let one = depth.typ().one();
+        // The payload is produced by the `__rust_panic_cleanup` hook, which ignores it.
+        let payload = Type::unsigned_int(8).to_pointer().null();
+        let caught = Stmt::block(
+            vec![
+                flag.clone().assign(Expr::bool_false(), loc.clone()),
+                panicking.assign(Expr::bool_false(), loc.clone()),
+                catch_fn.dereference().call(vec![data, payload]).as_stmt(loc.clone()),
+                self.codegen_expr_to_place(p, ret_typ.one()),
+            ],
+            loc.clone(),
+        );
+        Stmt::block(
+            vec![
+                depth.clone().assign(depth.clone().plus(one.clone()), loc.clone()),
+                call_try,
+                depth.clone().assign(depth.sub(one), loc.clone()),
+                Stmt::if_then_else(
+                    flag,
+                    caught,
+                    Some(self.codegen_expr_to_place(p, ret_typ.zero())),
+                    loc.clone(),
+                ),
+            ],
+            loc,
+        )
+    }
+}
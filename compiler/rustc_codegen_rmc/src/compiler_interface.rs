@@ -3,14 +3,18 @@
 
 //! This file contains the code necessary to interface with the compiler backend
 
+use crate::codegen::is_rmctool_root;
+use crate::context::{RmcMetadata, UnsupportedConstruct};
 use crate::GotocCtx;
 
 use bitflags::_core::any::Any;
 use cbmc::goto_program::symtab_transformer;
 use cbmc::goto_program::SymbolTable;
+use rustc_codegen_ssa::back::symbol_export;
 use rustc_codegen_ssa::traits::CodegenBackend;
 use rustc_data_structures::fx::FxHashMap;
 use rustc_errors::ErrorReported;
+use rustc_hir::def_id::DefId;
 use rustc_metadata::EncodedMetadata;
 use rustc_middle::dep_graph::{WorkProduct, WorkProductId};
 use rustc_middle::mir::mono::{CodegenUnit, MonoItem};
@@ -30,6 +34,8 @@ pub struct GotocCodegenResult {
     pub type_map: BTreeMap<String, String>,
     pub symtab: SymbolTable,
     pub crate_name: rustc_span::Symbol,
+    pub metadata: RmcMetadata,
+    pub unsupported_constructs: Vec<UnsupportedConstruct>,
 }
 
 #[derive(Clone)]
@@ -46,7 +52,18 @@ fn metadata_loader(&self) -> Box<MetadataLoaderDyn> {
         Box::new(rustc_codegen_ssa::back::metadata::DefaultMetadataLoader)
     }
 
-    fn provide(&self, _providers: &mut Providers) {}
+    fn provide(&self, providers: &mut Providers) {
+        // Some functions annotated by the `rmc` macros are not called by the user's code, so we
+        // make them roots of the codegen, like exported functions (see `is_rmctool_root`).
+        providers.is_reachable_non_generic = |tcx: TyCtxt<'_>, def_id: DefId| {
+            let threshold = symbol_export::crates_export_threshold(&tcx.sess.crate_types());
+            is_rmctool_root(tcx, def_id)
+                || tcx
+                    .reachable_non_generics(def_id.krate)
+                    .get(&def_id)
+                    .map_or(false, |level| level.is_below_threshold(threshold))
+        };
+    }
 
     fn provide_extern(&self, _providers: &mut ty::query::ExternProviders) {}
 
@@ -82,11 +99,13 @@ fn codegen_crate<'tcx>(
                             def_id,
                         );
                     }
-                    MonoItem::GlobalAsm(_) => {
+                    MonoItem::GlobalAsm(item_id) => {
                         warn!(
                             "Crate {} contains global ASM, which is not handled by RMC",
                             c.short_crate_name()
                         );
+                        let loc = c.codegen_span(&tcx.def_span(item_id.def_id));
+                        c.record_unsupported_construct("GlobalAsm", None, &loc, None);
                     }
                 }
             }
@@ -120,18 +139,49 @@ fn codegen_crate<'tcx>(
             }
         }
 
+        // CBMC does not allow function calls inside quantifiers, so we inline them
+        let symbol_table = if c.has_quantifiers {
+            match symtab_transformer::QuantifierTransformer::transform(&c.symbol_table) {
+                Ok(symbol_table) => symbol_table,
+                Err(error) => tcx.sess.fatal(&format!(
+                    "the predicate of `rmc::forall!` or `rmc::exists!` cannot be inlined: {}",
+                    error
+                )),
+            }
+        } else {
+            c.symbol_table
+        };
+
         // perform post-processing symbol table passes
         let symbol_table = symtab_transformer::do_passes(
-            c.symbol_table,
+            symbol_table,
             &tcx.sess.opts.debugging_opts.symbol_table_passes,
         );
 
         let type_map = BTreeMap::from_iter(c.type_map.into_iter().map(|(k, v)| (k, v.to_string())));
 
+        let unsupported_constructs: Vec<_> = c.unsupported_constructs.into_iter().collect();
+        if !unsupported_constructs.is_empty() {
+            warn_unsupported_constructs(tcx.sess, &unsupported_constructs);
+        }
+
+        let mut proof_harnesses = c.proof_harnesses;
+        for harness in &mut proof_harnesses {
+            harness.stubs = c.stubs.remove(&harness.mangled_name).unwrap_or_default();
+        }
+        let metadata = RmcMetadata {
+            proof_harnesses,
+            contracts: c.contracts,
+            loop_bounds: c.loop_bounds,
+            has_loop_contracts: c.has_loop_contracts,
+        };
+
         Box::new(GotocCodegenResult {
             type_map,
             symtab: symbol_table,
             crate_name: tcx.crate_name(LOCAL_CRATE) as rustc_span::Symbol,
+            metadata,
+            unsupported_constructs,
         })
     }
 
@@ -159,12 +209,32 @@ fn link(
             let base_filename = outputs.path(OutputType::Object);
             write_file(&base_filename, "symtab.json", &result.symtab);
             write_file(&base_filename, "type_map.json", &result.type_map);
+            write_file(&base_filename, "rmc-metadata.json", &result.metadata);
+            write_file(&base_filename, "unsupported.json", &result.unsupported_constructs);
         }
 
         Ok(())
     }
 }
 
+/// Warns the user about the constructs that RMC does not support, grouped by kind. They are
+/// replaced by assertions that fail if they are reached, so they can make verification fail.
+fn warn_unsupported_constructs(sess: &Session, constructs: &[UnsupportedConstruct]) {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for construct in constructs {
+        *counts.entry(&construct.construct).or_default() += 1;
+    }
+    let mut diag = sess.struct_warn(&format!(
+        "found {} construct(s) that RMC does not support; verification fails if a harness reaches one of them",
+        constructs.len()
+    ));
+    for (construct, count) in counts {
+        diag.note(&format!("{}: {} occurrence(s)", construct, count));
+    }
+    diag.note("the location of each construct is listed in the `.unsupported.json` file");
+    diag.emit();
+}
+
 fn write_file<T>(base_filename: &PathBuf, extension: &str, source: &T)
 where
     T: serde::Serialize,
@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use crate::GotocCtx;
-use cbmc::goto_program::Stmt;
+use cbmc::goto_program::{Expr, Stmt};
+use rustc_data_structures::fx::FxHashMap;
 use rustc_middle::mir::BasicBlock;
 use rustc_middle::mir::Body;
 use rustc_middle::ty::Instance;
@@ -18,6 +19,9 @@ pub struct CurrentFnCtx<'tcx> {
     instance: Instance<'tcx>,
     /// The goto labels for all blocks
     labels: Vec<String>,
+    /// The loop contracts (invariant and optional decreases clause) to attach to the back edge
+    /// that ends in the given block, declared with `rmc::loop_invariant!`
+    loop_contracts: FxHashMap<BasicBlock, (Expr, Option<Expr>)>,
     /// The mir for the current instance
     mir: &'tcx Body<'tcx>,
     /// The symbol name of the current function
@@ -38,6 +42,7 @@ pub fn new(instance: Instance<'tcx>, gcx: &GotocCtx<'tcx>) -> Self {
             current_bb: None,
             instance,
             labels: vec![],
+            loop_contracts: FxHashMap::default(),
             mir: gcx.tcx.instance_mir(instance.def),
             name: gcx.symbol_name(instance),
             readable_name: gcx.readable_instance_name(instance),
@@ -60,6 +65,10 @@ pub fn get_and_incr_counter(&mut self) -> u64 {
         rval
     }
 
+    pub fn add_loop_contracts(&mut self, bb: BasicBlock, invariant: Expr, decreases: Option<Expr>) {
+        self.loop_contracts.insert(bb, (invariant, decreases));
+    }
+
     pub fn push_onto_block(&mut self, s: Stmt) {
         self.block.push(s)
     }
@@ -76,6 +85,11 @@ pub fn set_labels(&mut self, labels: Vec<String>) {
         assert!(self.labels.is_empty());
         self.labels = labels;
     }
+
+    /// Removes and returns the loop contracts to attach to the back edge that ends `bb`, if any.
+    pub fn take_loop_contracts(&mut self, bb: BasicBlock) -> Option<(Expr, Option<Expr>)> {
+        self.loop_contracts.remove(&bb)
+    }
 }
 
 /// Getters
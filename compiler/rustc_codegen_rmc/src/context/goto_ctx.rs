@@ -14,6 +14,9 @@
 //! Any MIR specific functionality (e.g. codegen etc) should live in specialized files that use
 //! this structure as input.
 use super::current_fn::CurrentFnCtx;
+use super::metadata::{
+    ContractMetadata, HarnessMetadata, LoopBoundMetadata, StubMetadata, UnsupportedConstruct,
+};
 use crate::overrides::{fn_hooks, GotocHooks};
 use crate::utils::full_crate_name;
 use cbmc::goto_program::{DatatypeComponent, Expr, Location, Stmt, Symbol, SymbolTable, Type};
@@ -22,8 +25,10 @@
 use cbmc::{MachineModel, RoundingMode};
 use rustc_data_structures::owning_ref::OwningRef;
 use rustc_data_structures::rustc_erase_owner;
+use rustc_data_structures::fx::FxIndexSet;
 use rustc_data_structures::stable_map::FxHashMap;
 use rustc_data_structures::sync::MetadataRef;
+use rustc_hir::def_id::DefId;
 use rustc_middle::mir::interpret::Allocation;
 use rustc_middle::span_bug;
 use rustc_middle::ty::layout::{HasParamEnv, HasTyCtxt, LayoutError, LayoutOfHelpers, TyAndLayout};
@@ -50,6 +55,25 @@ pub struct GotocCtx<'tcx> {
     pub alloc_map: FxHashMap<&'tcx Allocation, String>,
     pub current_fn: Option<CurrentFnCtx<'tcx>>,
     pub type_map: FxHashMap<String, Ty<'tcx>>,
+    /// the functions annotated with `#[rmc::proof]`
+    pub proof_harnesses: Vec<HarnessMetadata>,
+    /// the functions annotated with `#[rmc::requires]` or `#[rmc::ensures]`
+    pub contracts: Vec<ContractMetadata>,
+    /// the functions, other than proof harnesses, annotated with `#[rmc::unwind]`
+    pub loop_bounds: Vec<LoopBoundMetadata>,
+    /// the models given with `#[rmc::asm_model]` for the inline assembly of functions
+    pub asm_models: FxHashMap<DefId, Instance<'tcx>>,
+    /// the functions that proof harnesses stub with `#[rmc::stub]`, with the name of each harness
+    /// in the symbol table and its replacement
+    pub stub_targets: FxHashMap<DefId, Vec<(String, Instance<'tcx>)>>,
+    /// the calls that each proof harness replaces, by the name of the harness in the symbol table
+    pub stubs: FxHashMap<String, Vec<StubMetadata>>,
+    /// whether any loop is annotated with `rmc::loop_invariant!`
+    pub has_loop_contracts: bool,
+    /// whether any function uses `rmc::forall!` or `rmc::exists!`
+    pub has_quantifiers: bool,
+    /// the constructs that were replaced by an assertion because RMC does not support them
+    pub unsupported_constructs: FxIndexSet<UnsupportedConstruct>,
 }
 
 /// Constructor
@@ -67,6 +91,15 @@ pub fn new(tcx: TyCtxt<'tcx>) -> GotocCtx<'tcx> {
             alloc_map: FxHashMap::default(),
             current_fn: None,
             type_map: FxHashMap::default(),
+            proof_harnesses: vec![],
+            contracts: vec![],
+            loop_bounds: vec![],
+            asm_models: FxHashMap::default(),
+            stub_targets: FxHashMap::default(),
+            stubs: FxHashMap::default(),
+            has_loop_contracts: false,
+            has_quantifiers: false,
+            unsupported_constructs: FxIndexSet::default(),
         }
     }
 }
@@ -111,6 +144,13 @@ pub fn gen_temp_variable(&mut self, t: Type, loc: Location) -> Symbol {
         let c = self.current_fn_mut().get_and_incr_counter();
         self.gen_stack_variable(c, &self.current_fn().name(), "temp", t, loc)
     }
+
+    /// Generate a new function local variable that holds a value returned by `rmc::nondet`.
+    /// Concrete playback finds these values in CBMC traces by the `rmc_nondet` prefix of the name.
+    pub fn gen_nondet_variable(&mut self, t: Type, loc: Location) -> Symbol {
+        let c = self.current_fn_mut().get_and_incr_counter();
+        self.gen_stack_variable(c, &self.current_fn().name(), "rmc_nondet", t, loc)
+    }
 }
 
 /// Symbol table related
@@ -0,0 +1,92 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module contains the metadata RMC collects during codegen that is not part of the goto
+//! program itself. It is written next to the symbol table so that the `rmc` and `cargo rmc`
+//! drivers can use it to decide how to run CBMC.
+
+use serde::Serialize;
+
+/// Information about a function annotated with `#[rmc::proof]`.
+#[derive(Serialize, Debug, Clone)]
+pub struct HarnessMetadata {
+    /// The name the user gave to the function.
+    pub pretty_name: String,
+    /// The name of the function in the CBMC symbol table.
+    pub mangled_name: String,
+    /// The (currently full-) path to the file this proof harness was declared within, if known.
+    pub original_file: Option<String>,
+    /// The line in that file where the proof harness begins, if known.
+    pub original_line: Option<u64>,
+    /// The loop unwinding bound given with `#[rmc::unwind(N)]`, if any.
+    pub unwind_value: Option<u32>,
+    /// For harnesses annotated with `#[rmc::proof_for_contract]`, the symbol of the function whose
+    /// contract is under verification.
+    pub for_contract: Option<String>,
+    /// The calls that the proof harness replaces with `#[rmc::stub]`.
+    pub stubs: Vec<StubMetadata>,
+}
+
+/// A function whose calls a proof harness replaces with `#[rmc::stub(original, replacement)]`.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct StubMetadata {
+    /// The name of the function to replace in the CBMC symbol table.
+    pub original_name: String,
+    /// The name of the replacement in the CBMC symbol table.
+    pub replacement_name: String,
+}
+
+/// Information about a function with a contract (`#[rmc::requires]` / `#[rmc::ensures]`).
+#[derive(Serialize, Debug, Clone)]
+pub struct ContractMetadata {
+    /// The name the user gave to the function.
+    pub pretty_name: String,
+    /// The name of the function in the CBMC symbol table.
+    pub mangled_name: String,
+    /// The symbol of the function that checks the contract against the body of the function.
+    pub check_name: String,
+    /// The symbol of the function that replaces calls to the function by its contract.
+    pub replace_name: String,
+}
+
+/// A function that is not a proof harness, whose loops are bounded with `#[rmc::unwind(N)]`.
+#[derive(Serialize, Debug, Clone)]
+pub struct LoopBoundMetadata {
+    /// The name the user gave to the function.
+    pub pretty_name: String,
+    /// The name of the function in the CBMC symbol table.
+    pub mangled_name: String,
+    /// The loop unwinding bound of the loops of the function.
+    pub unwind_value: u32,
+}
+
+/// A construct that RMC does not support, and that it replaced by an assertion that fails if the
+/// construct is reached. The constructs found in a crate are written to `<crate>.unsupported.json`.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UnsupportedConstruct {
+    /// A short description of the construct, e.g. `InlineAsm`.
+    pub construct: String,
+    /// More information on this occurrence of the construct, if any.
+    pub detail: Option<String>,
+    /// The function that contains the construct, if any.
+    pub function: Option<String>,
+    /// The source location of the construct.
+    pub location: String,
+    /// The issue that tracks support for the construct, if any.
+    pub url: Option<String>,
+}
+
+/// The metadata written to `<crate>.rmc-metadata.json`.
+#[derive(Serialize, Debug, Default)]
+pub struct RmcMetadata {
+    /// The proof harnesses (`#[rmc::proof]`) found in the crate.
+    pub proof_harnesses: Vec<HarnessMetadata>,
+    /// The functions with a contract found in the crate.
+    pub contracts: Vec<ContractMetadata>,
+    /// The functions whose loops have their own unwinding bound, which the driver passes to CBMC
+    /// with `--unwindset`.
+    pub loop_bounds: Vec<LoopBoundMetadata>,
+    /// Whether any loop in the crate has a contract (`rmc::loop_invariant!`), in which case the
+    /// goto program must be instrumented with `goto-instrument --apply-loop-contracts`.
+    pub has_loop_contracts: bool,
+}
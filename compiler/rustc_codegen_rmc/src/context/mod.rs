@@ -8,5 +8,10 @@
 
 mod current_fn;
 mod goto_ctx;
+mod metadata;
 
 pub use goto_ctx::GotocCtx;
+pub use metadata::{
+    ContractMetadata, HarnessMetadata, LoopBoundMetadata, RmcMetadata, StubMetadata,
+    UnsupportedConstruct,
+};
@@ -10,9 +10,9 @@
 
 use crate::utils::{instance_name_is, instance_name_starts_with};
 use crate::GotocCtx;
-use cbmc::goto_program::{BuiltinFn, Expr, Location, Stmt, Symbol, Type};
+use cbmc::goto_program::{BuiltinFn, Expr, Location, PropertyClass, Stmt, Symbol, Type};
 use cbmc::NO_PRETTY_NAME;
-use rustc_middle::mir::{BasicBlock, Place};
+use rustc_middle::mir::{BasicBlock, Place, TerminatorKind};
 use rustc_middle::ty::layout::LayoutOf;
 use rustc_middle::ty::print::with_no_trimmed_paths;
 use rustc_middle::ty::{self, Instance, InstanceDef, TyCtxt};
@@ -97,12 +97,15 @@ fn handle(
         assert_eq!(fargs.len(), 2);
         let target = target.unwrap();
         let cond = fargs.remove(0).cast_to(Type::bool());
-        //TODO: actually use the error message passed by the user.
-        let msg = "EXPECTED FAIL";
+        // The user's message can only be used when it is a constant.
+        let msg = match tcx.fold_call_arg_str(1) {
+            Some(user_msg) => format!("EXPECTED FAIL: {}", user_msg),
+            None => "EXPECTED FAIL".to_string(),
+        };
         let loc = tcx.codegen_span_option(span);
         Stmt::block(
             vec![
-                Stmt::assert(cond, msg, loc.clone()),
+                tcx.codegen_assert(cond, PropertyClass::ExpectFail, &msg, loc.clone()),
                 Stmt::goto(tcx.current_fn().find_label(&target), loc.clone()),
             ],
             loc,
@@ -146,6 +149,116 @@ fn handle(
     }
 }
 
+/// Stores the closure `env` in a temporary, and returns the declaration of the temporary along
+/// with a call to the closure of type `closure_ty` with the given arguments.
+fn codegen_closure_call(
+    tcx: &mut GotocCtx<'tcx>,
+    closure_ty: ty::Ty<'tcx>,
+    env: Expr,
+    mut args: Vec<Expr>,
+    loc: Location,
+) -> (Stmt, Expr) {
+    let closure = match closure_ty.kind() {
+        ty::Closure(def_id, substs) => {
+            Instance::resolve_closure(tcx.tcx, *def_id, substs, ty::ClosureKind::Fn)
+        }
+        _ => unreachable!("Expected a closure, got {:?}", closure_ty),
+    };
+    let temp = tcx.gen_temp_variable(env.typ().clone(), loc.clone()).to_expr();
+    let decl = Stmt::decl(temp.clone(), Some(env), loc);
+    args.insert(0, temp.address_of());
+    (decl, tcx.codegen_func_expr(closure, None).call(args))
+}
+
+/// `rmc::loop_invariant!(inv)` and `rmc::loop_invariant!(inv, decreases: measure)` pass the
+/// invariant and the measure as closures. We store the closures in temporaries, and attach calls
+/// to them to the back edge of the loop that follows, so they get evaluated at the loop head.
+struct LoopInvariant;
+
+impl LoopInvariant {
+    /// Finds the block ending with the back edge of the first loop that starts at or after `bb`.
+    ///
+    /// A goto `source -> head` is a back edge if `head` dominates `source`. The loops that follow
+    /// `bb` are those whose head `bb` dominates, and the first one is the loop whose head
+    /// dominates the heads of all the others. If this loop has several back edges, we pick the
+    /// last one in the goto program.
+    fn find_back_edge(tcx: &GotocCtx<'tcx>, bb: BasicBlock) -> Option<BasicBlock> {
+        let mir = tcx.current_fn().mir();
+        let dominators = mir.dominators();
+        let back_edges: Vec<(BasicBlock, BasicBlock)> = mir
+            .basic_blocks()
+            .iter_enumerated()
+            .filter_map(|(source, data)| match data.terminator().kind {
+                // CBMC only recognizes loops whose back edge jumps backwards in the goto program,
+                // where blocks are in MIR order.
+                TerminatorKind::Goto { target: head }
+                    if head <= source
+                        && dominators.is_dominated_by(source, head)
+                        && dominators.is_dominated_by(head, bb) =>
+                {
+                    Some((head, source))
+                }
+                _ => None,
+            })
+            .collect();
+        let (first_head, _) = back_edges.iter().find(|(head, _)| {
+            back_edges.iter().all(|(other, _)| dominators.is_dominated_by(*other, *head))
+        })?;
+        back_edges.iter().filter(|(head, _)| head == first_head).map(|(_, source)| *source).max()
+    }
+}
+
+impl<'tcx> GotocHook<'tcx> for LoopInvariant {
+    fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+        matches_function(tcx, instance, "RmcLoopInvariant")
+            || matches_function(tcx, instance, "RmcLoopInvariantDecreases")
+    }
+
+    fn handle(
+        &self,
+        tcx: &mut GotocCtx<'tcx>,
+        instance: Instance<'tcx>,
+        mut fargs: Vec<Expr>,
+        _assign_to: Option<Place<'tcx>>,
+        target: Option<BasicBlock>,
+        span: Option<Span>,
+    ) -> Stmt {
+        let loc = tcx.codegen_span_option(span);
+        let target = target.unwrap();
+        let mut stmts = vec![];
+
+        let invariant_ty = instance.substs.type_at(0);
+        let (decl, invariant) =
+            codegen_closure_call(tcx, invariant_ty, fargs.remove(0), vec![], loc.clone());
+        stmts.push(decl);
+        let decreases = if fargs.is_empty() {
+            None
+        } else {
+            let measure_ty = instance.substs.type_at(1);
+            let (decl, decreases) =
+                codegen_closure_call(tcx, measure_ty, fargs.remove(0), vec![], loc.clone());
+            stmts.push(decl);
+            Some(decreases)
+        };
+
+        match LoopInvariant::find_back_edge(tcx, target) {
+            Some(back_edge) => tcx.current_fn_mut().add_loop_contracts(
+                back_edge,
+                invariant.cast_to(Type::bool()),
+                decreases,
+            ),
+            None => tcx.tcx.sess.span_err(
+                span.unwrap(),
+                "`rmc::loop_invariant!` must be immediately followed by a loop",
+            ),
+        }
+        tcx.has_loop_contracts = true;
+
+        stmts.push(Stmt::goto(tcx.current_fn().find_label(&target), loc.clone()));
+        Stmt::block(stmts, loc)
+    }
+}
+
 struct Nondet;
 
 impl<'tcx> GotocHook<'tcx> for Nondet {
@@ -173,13 +286,20 @@ fn handle(
         let p = assign_to.unwrap();
         let target = target.unwrap();
         let pt = tcx.place_ty(&p);
-        if pt.is_unit() {
+        // A zero-sized value has a single possible value and no bytes, so it is not part of the
+        // counterexample (see `next_value` in `rmc::concrete_playback`).
+        if tcx.layout_of(pt).is_zst() {
             Stmt::goto(tcx.current_fn().find_label(&target), loc)
         } else {
             let pe = tcx.codegen_place(&p).goto_expr;
+            // The value goes through a dedicated variable so that concrete playback can find it
+            // in the trace of a failure.
+            let typ = tcx.codegen_ty(pt);
+            let value = tcx.gen_nondet_variable(typ, loc.clone()).to_expr();
             Stmt::block(
                 vec![
-                    pe.clone().assign(tcx.codegen_ty(pt).nondet(), loc.clone()),
+                    Stmt::decl(value.clone(), Some(value.typ().nondet()), loc.clone()),
+                    pe.clone().assign(value, loc.clone()),
                     // we should potentially generate an assumption
                     match tcx.codegen_assumption(pt) {
                         None => Stmt::skip(loc.clone()),
@@ -198,20 +318,115 @@ fn handle(
 impl<'tcx> GotocHook<'tcx> for Panic {
     fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
         output_of_instance_is_never(tcx, instance)
-            && (instance_name_is(tcx, instance, "begin_panic")
-                || instance_name_is(tcx, instance, "panic"))
+            && ["assert_failed", "begin_panic", "panic", "panic_display", "panic_fmt", "panic_str"]
+                .iter()
+                .any(|name| instance_name_is(tcx, instance, name))
     }
 
     fn handle(
         &self,
         tcx: &mut GotocCtx<'tcx>,
-        _instance: Instance<'tcx>,
-        fargs: Vec<Expr>,
+        instance: Instance<'tcx>,
+        _fargs: Vec<Expr>,
         _assign_to: Option<Place<'tcx>>,
         _target: Option<BasicBlock>,
         span: Option<Span>,
     ) -> Stmt {
-        tcx.codegen_panic(span, fargs)
+        tcx.codegen_panic(span, instance)
+    }
+}
+
+/// With `-Z panic-unwinding`, `catch_unwind` gets the payload of the panic it caught from
+/// `__rust_panic_cleanup`. The panic hooks do not build payloads, so we return a boxed `()`.
+struct PanicCleanup;
+
+impl<'tcx> GotocHook<'tcx> for PanicCleanup {
+    fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+        let name = tcx.symbol_name(instance).name.to_string();
+        name == "__rust_panic_cleanup"
+    }
+
+    fn handle(
+        &self,
+        tcx: &mut GotocCtx<'tcx>,
+        _instance: Instance<'tcx>,
+        _fargs: Vec<Expr>,
+        assign_to: Option<Place<'tcx>>,
+        target: Option<BasicBlock>,
+        span: Option<Span>,
+    ) -> Stmt {
+        let loc = tcx.codegen_span_option(span);
+        let p = assign_to.unwrap();
+        let target = target.unwrap();
+        // A box of a ZST holds a dangling pointer, and is never deallocated.
+        let unit_ptr_ty = tcx.tcx.mk_mut_ptr(tcx.tcx.mk_unit());
+        let dangling = Type::size_t().one().cast_to(tcx.codegen_ty(unit_ptr_ty));
+        let payload =
+            tcx.cast_sized_pointer_to_fat_pointer(dangling, unit_ptr_ty, tcx.place_ty(&p)).unwrap();
+        Stmt::block(
+            vec![
+                tcx.codegen_place(&p).goto_expr.assign(payload, loc.clone()),
+                Stmt::goto(tcx.current_fn().find_label(&target), loc.clone()),
+            ],
+            loc,
+        )
+    }
+}
+
+/// The panic hooks do not count panics, so `catch_unwind` must not decrease the count.
+struct PanicCount;
+
+impl<'tcx> GotocHook<'tcx> for PanicCount {
+    fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+        let name = with_no_trimmed_paths(|| tcx.def_path_str(instance.def_id()));
+        name == "std::panicking::panic_count::decrease"
+    }
+
+    fn handle(
+        &self,
+        tcx: &mut GotocCtx<'tcx>,
+        _instance: Instance<'tcx>,
+        _fargs: Vec<Expr>,
+        _assign_to: Option<Place<'tcx>>,
+        target: Option<BasicBlock>,
+        span: Option<Span>,
+    ) -> Stmt {
+        let loc = tcx.codegen_span_option(span);
+        Stmt::goto(tcx.current_fn().find_label(&target.unwrap()), loc)
+    }
+}
+
+/// `std::thread::panicking` reads the panic count, which the panic hooks do not update, so we
+/// return whether the current thread is unwinding instead. Dropping a `MutexGuard` while
+/// unwinding then poisons the mutex.
+struct Panicking;
+
+impl<'tcx> GotocHook<'tcx> for Panicking {
+    fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+        let name = with_no_trimmed_paths(|| tcx.def_path_str(instance.def_id()));
+        name == "std::panicking::panicking"
+    }
+
+    fn handle(
+        &self,
+        tcx: &mut GotocCtx<'tcx>,
+        _instance: Instance<'tcx>,
+        _fargs: Vec<Expr>,
+        assign_to: Option<Place<'tcx>>,
+        target: Option<BasicBlock>,
+        span: Option<Span>,
+    ) -> Stmt {
+        let loc = tcx.codegen_span_option(span);
+        let p = assign_to.unwrap();
+        let target = target.unwrap();
+        let panicking = tcx.codegen_panicking().cast_to(Type::c_bool());
+        Stmt::block(
+            vec![
+                tcx.codegen_expr_to_place(&p, panicking),
+                Stmt::goto(tcx.current_fn().find_label(&target), loc.clone()),
+            ],
+            loc,
+        )
     }
 }
 
@@ -233,7 +448,8 @@ fn handle(
     ) -> Stmt {
         let loc = tcx.codegen_span_option(span);
         // _target must be None due to how rust compiler considers it
-        Stmt::assert_false(
+        tcx.codegen_assert_false(
+            PropertyClass::Assertion,
             &format!(
                 "a panicking function {} is invoked",
                 with_no_trimmed_paths(|| tcx.tcx.def_path_str(instance.def_id()))
@@ -243,6 +459,76 @@ fn handle(
     }
 }
 
+/// The keys of `RandomState`, which `HashMap::new` initializes, are read from the operating
+/// system. We make them nondeterministic instead.
+struct HashmapRandomKeys;
+
+impl<'tcx> GotocHook<'tcx> for HashmapRandomKeys {
+    fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+        let name = with_no_trimmed_paths(|| tcx.def_path_str(instance.def_id()));
+        name == "std::sys::unix::rand::hashmap_random_keys"
+    }
+
+    fn handle(
+        &self,
+        tcx: &mut GotocCtx<'tcx>,
+        _instance: Instance<'tcx>,
+        _fargs: Vec<Expr>,
+        assign_to: Option<Place<'tcx>>,
+        target: Option<BasicBlock>,
+        span: Option<Span>,
+    ) -> Stmt {
+        let loc = tcx.codegen_span_option(span);
+        let target = target.unwrap();
+        let dest = tcx.codegen_place(&assign_to.unwrap()).goto_expr;
+        Stmt::block(
+            vec![
+                dest.clone().assign(dest.typ().nondet(), loc.clone()),
+                Stmt::goto(tcx.current_fn().find_label(&target), loc.clone()),
+            ],
+            loc,
+        )
+    }
+}
+
+/// `rmc::__havoc(target)` sets the object that `target` refers to to a nondeterministic value,
+/// without dropping its old value. The replacement of a function by its contract uses it on the
+/// `&mut` arguments of the function.
+struct Havoc;
+
+impl<'tcx> GotocHook<'tcx> for Havoc {
+    fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+        matches_function(tcx, instance, "RmcHavoc")
+    }
+
+    fn handle(
+        &self,
+        tcx: &mut GotocCtx<'tcx>,
+        instance: Instance<'tcx>,
+        mut fargs: Vec<Expr>,
+        _assign_to: Option<Place<'tcx>>,
+        target: Option<BasicBlock>,
+        span: Option<Span>,
+    ) -> Stmt {
+        let loc = tcx.codegen_span_option(span);
+        let target = target.unwrap();
+        let reference = fargs.remove(0);
+        // A reference to an unsized value is a fat pointer, whose data pointer points to the value.
+        let ptr = if tcx.is_unsized(instance.substs.type_at(0)) {
+            reference.member("data", &tcx.symbol_table)
+        } else {
+            reference
+        };
+        Stmt::block(
+            vec![
+                tcx.codegen_havoc_object(ptr, loc.clone()),
+                Stmt::goto(tcx.current_fn().find_label(&target), loc.clone()),
+            ],
+            loc,
+        )
+    }
+}
+
 struct Intrinsic;
 
 impl<'tcx> GotocHook<'tcx> for Intrinsic {
@@ -264,7 +550,7 @@ fn handle(
     ) -> Stmt {
         let loc = tcx.codegen_span_option(span);
         if tcx.symbol_name(instance) == "abort" {
-            Stmt::assert_false("abort intrinsic reached", loc)
+            tcx.codegen_assert_false(PropertyClass::Assertion, "abort intrinsic reached", loc)
         } else {
             let p = assign_to.unwrap();
             let target = target.unwrap();
@@ -386,6 +672,252 @@ fn handle(
     }
 }
 
+/// `ptr::read` and `ptr::write` require `ptr` to be aligned for the type they access; their
+/// `_unaligned` variants do not.
+fn codegen_ptr_access_alignment_check<'tcx>(
+    tcx: &mut GotocCtx<'tcx>,
+    instance: Instance<'tcx>,
+    ptr: Expr,
+    loc: Location,
+) -> Option<Stmt> {
+    let name = tcx.tcx.item_name(instance.def_id());
+    if name.as_str().ends_with("_unaligned") {
+        return None;
+    }
+    let pointee = tcx.monomorphize(instance.substs.type_at(0));
+    tcx.codegen_alignment_check(ptr, pointee, &format!("{}: pointer is aligned", name), loc)
+}
+
+/// With `-Z uninit-checks`, `MaybeUninit::uninit` returns a value whose bytes are all
+/// uninitialized.
+struct MaybeUninitUninit;
+
+impl<'tcx> GotocHook<'tcx> for MaybeUninitUninit {
+    fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+        if !tcx.sess.opts.debugging_opts.uninit_checks {
+            return false;
+        }
+        let name = with_no_trimmed_paths(|| tcx.def_path_str(instance.def_id()));
+        name == "core::mem::MaybeUninit::<T>::uninit"
+            || name == "core::mem::maybe_uninit::MaybeUninit::<T>::uninit"
+            || name == "std::mem::MaybeUninit::<T>::uninit"
+    }
+
+    fn handle(
+        &self,
+        tcx: &mut GotocCtx<'tcx>,
+        _instance: Instance<'tcx>,
+        _fargs: Vec<Expr>,
+        assign_to: Option<Place<'tcx>>,
+        target: Option<BasicBlock>,
+        span: Option<Span>,
+    ) -> Stmt {
+        let loc = tcx.codegen_span_option(span);
+        let p = assign_to.unwrap();
+        let target = target.unwrap();
+        let layout = tcx.layout_of(tcx.place_ty(&p));
+        let size = Expr::int_constant(layout.size.bytes(), Type::size_t());
+        let dst = tcx.codegen_place(&p).goto_expr;
+        Stmt::block(
+            vec![
+                dst.clone().assign(dst.typ().nondet(), loc.clone()),
+                tcx.codegen_mark_init(dst.address_of(), size, false, loc.clone()),
+                Stmt::goto(tcx.current_fn().find_label(&target), loc.clone()),
+            ],
+            loc,
+        )
+    }
+}
+
+/// With `-Z concurrency`, the threads of `std::thread` are CBMC threads. We model the pthread
+/// functions that create and join them, and let the ones that only configure them succeed.
+struct Pthread;
+
+impl<'tcx> GotocHook<'tcx> for Pthread {
+    fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+        if !tcx.sess.opts.debugging_opts.concurrency {
+            return false;
+        }
+        let name = tcx.symbol_name(instance).name.to_string();
+        name == "pthread_attr_destroy"
+            || name == "pthread_attr_init"
+            || name == "pthread_attr_setstacksize"
+            || name == "pthread_create"
+            || name == "pthread_detach"
+            || name == "pthread_getattr_np"
+            || name == "pthread_join"
+    }
+
+    fn handle(
+        &self,
+        tcx: &mut GotocCtx<'tcx>,
+        instance: Instance<'tcx>,
+        fargs: Vec<Expr>,
+        assign_to: Option<Place<'tcx>>,
+        target: Option<BasicBlock>,
+        span: Option<Span>,
+    ) -> Stmt {
+        let loc = tcx.codegen_span_option(span);
+        let p = assign_to.unwrap();
+        let target = target.unwrap();
+        let ret_typ = tcx.codegen_ty(tcx.place_ty(&p));
+        let code = match tcx.tcx.symbol_name(instance).name {
+            "pthread_create" => tcx.codegen_pthread_create(fargs, &p, loc.clone()),
+            "pthread_join" => tcx.codegen_pthread_join(fargs, &p, loc.clone()),
+            // Threads have no attributes, so the stack guard of `std::thread` is unknown.
+            "pthread_getattr_np" => tcx.codegen_expr_to_place(&p, ret_typ.one()),
+            _ => tcx.codegen_expr_to_place(&p, ret_typ.zero()),
+        };
+        Stmt::block(vec![code, Stmt::goto(tcx.current_fn().find_label(&target), loc.clone())], loc)
+    }
+}
+
+/// With `-Z concurrency`, the stack size of new threads does not matter, and computing its
+/// minimum reads environment variables and looks up glibc symbols, so we return a constant.
+struct MinStack;
+
+impl<'tcx> GotocHook<'tcx> for MinStack {
+    fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+        if !tcx.sess.opts.debugging_opts.concurrency {
+            return false;
+        }
+        let name = with_no_trimmed_paths(|| tcx.def_path_str(instance.def_id()));
+        name == "std::sys_common::thread::min_stack"
+            || name == "std::sys::unix::thread::min_stack_size"
+    }
+
+    fn handle(
+        &self,
+        tcx: &mut GotocCtx<'tcx>,
+        _instance: Instance<'tcx>,
+        _fargs: Vec<Expr>,
+        assign_to: Option<Place<'tcx>>,
+        target: Option<BasicBlock>,
+        span: Option<Span>,
+    ) -> Stmt {
+        let loc = tcx.codegen_span_option(span);
+        let p = assign_to.unwrap();
+        let target = target.unwrap();
+        let size = Expr::int_constant(2 * 1024 * 1024, Type::size_t());
+        Stmt::block(
+            vec![
+                tcx.codegen_expr_to_place(&p, size),
+                Stmt::goto(tcx.current_fn().find_label(&target), loc.clone()),
+            ],
+            loc,
+        )
+    }
+}
+
+/// The pthread mutexes, read-write locks and condition variables that `std::sync` is built on
+/// are replaced by the models of `codegen/sync.rs`, which check for deadlocks.
+struct PthreadSync;
+
+impl<'tcx> GotocHook<'tcx> for PthreadSync {
+    fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+        let name = tcx.symbol_name(instance).name.to_string();
+        name == "pthread_cond_broadcast"
+            || name == "pthread_cond_destroy"
+            || name == "pthread_cond_init"
+            || name == "pthread_cond_signal"
+            || name == "pthread_cond_wait"
+            || name == "pthread_condattr_destroy"
+            || name == "pthread_condattr_init"
+            || name == "pthread_condattr_setclock"
+            || name == "pthread_mutex_destroy"
+            || name == "pthread_mutex_init"
+            || name == "pthread_mutex_lock"
+            || name == "pthread_mutex_trylock"
+            || name == "pthread_mutex_unlock"
+            || name == "pthread_mutexattr_destroy"
+            || name == "pthread_mutexattr_init"
+            || name == "pthread_mutexattr_settype"
+            || name == "pthread_rwlock_destroy"
+            || name == "pthread_rwlock_rdlock"
+            || name == "pthread_rwlock_tryrdlock"
+            || name == "pthread_rwlock_trywrlock"
+            || name == "pthread_rwlock_unlock"
+            || name == "pthread_rwlock_wrlock"
+    }
+
+    fn handle(
+        &self,
+        tcx: &mut GotocCtx<'tcx>,
+        instance: Instance<'tcx>,
+        mut fargs: Vec<Expr>,
+        assign_to: Option<Place<'tcx>>,
+        target: Option<BasicBlock>,
+        span: Option<Span>,
+    ) -> Stmt {
+        let loc = tcx.codegen_span_option(span);
+        let p = assign_to.unwrap();
+        let target = target.unwrap();
+        let ret_typ = tcx.codegen_ty(tcx.place_ty(&p));
+        let obj = fargs.remove(0);
+        let code = match tcx.tcx.symbol_name(instance).name {
+            "pthread_cond_broadcast" | "pthread_cond_signal" => {
+                tcx.codegen_cond_notify(obj, &p, loc.clone())
+            }
+            "pthread_cond_wait" => tcx.codegen_cond_wait(obj, fargs.remove(0), &p, loc.clone()),
+            "pthread_mutex_init" => tcx.codegen_mutex_init(obj, fargs.remove(0), &p, loc.clone()),
+            "pthread_mutex_lock" => tcx.codegen_mutex_lock(obj, &p, loc.clone()),
+            "pthread_mutex_trylock" => tcx.codegen_mutex_trylock(obj, &p, loc.clone()),
+            "pthread_mutex_unlock" => tcx.codegen_mutex_unlock(obj, &p, loc.clone()),
+            "pthread_mutexattr_init" => {
+                let kind = Expr::int_constant(0, Type::c_int());
+                tcx.codegen_mutexattr_settype(obj, kind, &p, loc.clone())
+            }
+            "pthread_mutexattr_settype" => {
+                tcx.codegen_mutexattr_settype(obj, fargs.remove(0), &p, loc.clone())
+            }
+            "pthread_rwlock_rdlock" => tcx.codegen_rwlock_rdlock(obj, false, &p, loc.clone()),
+            "pthread_rwlock_tryrdlock" => tcx.codegen_rwlock_rdlock(obj, true, &p, loc.clone()),
+            "pthread_rwlock_trywrlock" => tcx.codegen_rwlock_wrlock(obj, true, &p, loc.clone()),
+            "pthread_rwlock_unlock" => tcx.codegen_rwlock_unlock(obj, &p, loc.clone()),
+            "pthread_rwlock_wrlock" => tcx.codegen_rwlock_wrlock(obj, false, &p, loc.clone()),
+            // Destroying, and initializing condition variables and their attributes, always succeeds.
+            _ => tcx.codegen_expr_to_place(&p, ret_typ.zero()),
+        };
+        Stmt::block(vec![code, Stmt::goto(tcx.current_fn().find_label(&target), loc.clone())], loc)
+    }
+}
+
+/// `wait_timeout` of the `Condvar` of `std::sys` computes a deadline from the clock before it
+/// calls `pthread_cond_timedwait`. The model ignores the duration, and may time out whenever it
+/// is not notified.
+struct CondvarWaitTimeout;
+
+impl<'tcx> GotocHook<'tcx> for CondvarWaitTimeout {
+    fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+        let name = with_no_trimmed_paths(|| tcx.def_path_str(instance.def_id()));
+        name == "std::sys::unix::condvar::Condvar::wait_timeout"
+    }
+
+    fn handle(
+        &self,
+        tcx: &mut GotocCtx<'tcx>,
+        _instance: Instance<'tcx>,
+        mut fargs: Vec<Expr>,
+        assign_to: Option<Place<'tcx>>,
+        target: Option<BasicBlock>,
+        span: Option<Span>,
+    ) -> Stmt {
+        let loc = tcx.codegen_span_option(span);
+        let p = assign_to.unwrap();
+        let target = target.unwrap();
+        // `Condvar` and `Mutex` only hold their pthread object, so they point to it.
+        let cond = fargs.remove(0);
+        let mutex = fargs.remove(0);
+        Stmt::block(
+            vec![
+                tcx.codegen_cond_wait_timeout(cond, mutex, &p, loc.clone()),
+                Stmt::goto(tcx.current_fn().find_label(&target), loc.clone()),
+            ],
+            loc,
+        )
+    }
+}
+
 struct PtrRead;
 
 impl<'tcx> GotocHook<'tcx> for PtrRead {
@@ -402,7 +934,7 @@ fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
     fn handle(
         &self,
         tcx: &mut GotocCtx<'tcx>,
-        _instance: Instance<'tcx>,
+        instance: Instance<'tcx>,
         mut fargs: Vec<Expr>,
         assign_to: Option<Place<'tcx>>,
         target: Option<BasicBlock>,
@@ -412,15 +944,22 @@ fn handle(
         let p = assign_to.unwrap();
         let target = target.unwrap();
         let src = fargs.remove(0);
-        Stmt::block(
-            vec![
-                tcx.codegen_place(&p)
-                    .goto_expr
-                    .assign(src.dereference().with_location(loc.clone()), loc.clone()),
-                Stmt::goto(tcx.current_fn().find_label(&target), loc.clone()),
-            ],
-            loc,
-        )
+        let mut stmts: Vec<Stmt> =
+            codegen_ptr_access_alignment_check(tcx, instance, src.clone(), loc.clone())
+                .into_iter()
+                .collect();
+        let name = tcx.tcx.item_name(instance.def_id());
+        let t = tcx.monomorphize(instance.substs.type_at(0));
+        stmts.extend(tcx.codegen_check_init(src.clone(), t, loc.clone()));
+        let dst = tcx.codegen_place(&p).goto_expr;
+        stmts.push(
+            dst.clone().assign(src.clone().dereference().with_location(loc.clone()), loc.clone()),
+        );
+        let size = Expr::int_constant(tcx.layout_of(t).size.bytes(), Type::size_t());
+        stmts.push(tcx.codegen_copy_init(src, dst.clone().address_of(), size, loc.clone()));
+        stmts.extend(tcx.codegen_validity_check(dst, t, &name.as_str(), loc.clone()));
+        stmts.push(Stmt::goto(tcx.current_fn().find_label(&target), loc.clone()));
+        Stmt::block(stmts, loc)
     }
 }
 
@@ -440,7 +979,7 @@ fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
     fn handle(
         &self,
         tcx: &mut GotocCtx<'tcx>,
-        _instance: Instance<'tcx>,
+        instance: Instance<'tcx>,
         mut fargs: Vec<Expr>,
         _assign_to: Option<Place<'tcx>>,
         target: Option<BasicBlock>,
@@ -450,9 +989,65 @@ fn handle(
         let target = target.unwrap();
         let dst = fargs.remove(0);
         let src = fargs.remove(0);
+        let mut stmts: Vec<Stmt> =
+            codegen_ptr_access_alignment_check(tcx, instance, dst.clone(), loc.clone())
+                .into_iter()
+                .collect();
+        let t = tcx.monomorphize(instance.substs.type_at(0));
+        let size = Expr::int_constant(tcx.layout_of(t).size.bytes(), Type::size_t());
+        stmts.push(tcx.codegen_mark_init(dst.clone(), size, true, loc.clone()));
+        stmts.push(dst.dereference().assign(src, loc.clone()).with_location(loc.clone()));
+        stmts.push(Stmt::goto(tcx.current_fn().find_label(&target), loc.clone()));
+        Stmt::block(stmts, loc)
+    }
+}
+
+/// `rmc::forall!(|i in lower..upper| pred)` and `rmc::exists!` pass the bounds of the range and
+/// the predicate as a closure. We turn them into a CBMC quantifier over a fresh bound variable,
+/// whose domain calls the closure. The calls are inlined after codegen, since CBMC does not
+/// allow side effects inside quantifiers.
+struct Quantifier;
+
+impl<'tcx> GotocHook<'tcx> for Quantifier {
+    fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+        matches_function(tcx, instance, "RmcForall") || matches_function(tcx, instance, "RmcExists")
+    }
+
+    fn handle(
+        &self,
+        tcx: &mut GotocCtx<'tcx>,
+        instance: Instance<'tcx>,
+        mut fargs: Vec<Expr>,
+        assign_to: Option<Place<'tcx>>,
+        target: Option<BasicBlock>,
+        span: Option<Span>,
+    ) -> Stmt {
+        let loc = tcx.codegen_span_option(span);
+        let target = target.unwrap();
+        let lower = fargs.remove(0);
+        let upper = fargs.remove(0);
+        let variable = tcx.gen_temp_variable(lower.typ().clone(), loc.clone()).to_expr();
+        let predicate_ty = instance.substs.type_at(0);
+        let (decl, predicate) = codegen_closure_call(
+            tcx,
+            predicate_ty,
+            fargs.remove(0),
+            vec![variable.clone()],
+            loc.clone(),
+        );
+        let in_range = variable.clone().ge(lower).and(variable.clone().lt(upper));
+        let quantifier = if matches_function(tcx.tcx, instance, "RmcForall") {
+            Expr::forall_expr(variable, in_range.implies(predicate))
+        } else {
+            Expr::exists_expr(variable, in_range.and(predicate))
+        };
+        tcx.has_quantifiers = true;
+
+        let place = tcx.codegen_place(&assign_to.unwrap()).goto_expr;
         Stmt::block(
             vec![
-                dst.dereference().assign(src, loc.clone()).with_location(loc.clone()),
+                decl,
+                place.assign(quantifier.cast_to(Type::c_bool()), loc.clone()),
                 Stmt::goto(tcx.current_fn().find_label(&target), loc.clone()),
             ],
             loc,
@@ -483,14 +1078,16 @@ fn handle(
         match (assign_to, target) {
             (Some(p), Some(target)) => {
                 let size = fargs.remove(0);
+                let ptr = tcx.codegen_place(&p).goto_expr;
                 Stmt::block(
                     vec![
-                        tcx.codegen_place(&p).goto_expr.assign(
+                        ptr.clone().assign(
                             BuiltinFn::Malloc
-                                .call(vec![size], loc.clone())
+                                .call(vec![size.clone()], loc.clone())
                                 .cast_to(Type::unsigned_int(8).to_pointer()),
-                            loc,
+                            loc.clone(),
                         ),
+                        tcx.codegen_mark_init(ptr, size, false, loc),
                         Stmt::goto(tcx.current_fn().find_label(&target), Location::none()),
                     ],
                     Location::none(),
@@ -558,17 +1155,29 @@ fn handle(
         let p = assign_to.unwrap();
         let target = target.unwrap();
         let ptr = fargs.remove(0).cast_to(Type::void_pointer());
-        fargs.remove(0); // old_size
+        let old_size = fargs.remove(0);
         fargs.remove(0); // align
         let size = fargs.remove(0);
+        let new_ptr = tcx.codegen_place(&p).goto_expr;
+        // The initialization of the old contents moves to the new allocation, and the bytes
+        // beyond them are uninitialized.
+        let tail = new_ptr.clone().plus(old_size.clone());
+        let tail_size = size.clone().sub(old_size.clone());
         Stmt::block(
             vec![
-                tcx.codegen_place(&p).goto_expr.assign(
+                new_ptr.clone().assign(
                     BuiltinFn::Realloc
-                        .call(vec![ptr, size], loc.clone())
+                        .call(vec![ptr.clone(), size.clone()], loc.clone())
                         .cast_to(Type::unsigned_int(8).to_pointer()),
                     loc.clone(),
                 ),
+                tcx.codegen_copy_init(ptr, new_ptr.clone(), old_size.clone(), loc.clone()),
+                Stmt::if_then_else(
+                    size.gt(old_size),
+                    tcx.codegen_mark_init(tail, tail_size, false, loc.clone()),
+                    None,
+                    loc.clone(),
+                ),
                 Stmt::goto(tcx.current_fn().find_label(&target), loc.clone()),
             ],
             loc,
@@ -597,14 +1206,16 @@ fn handle(
         let p = assign_to.unwrap();
         let target = target.unwrap();
         let size = fargs.remove(0);
+        let ptr = tcx.codegen_place(&p).goto_expr;
         Stmt::block(
             vec![
-                tcx.codegen_place(&p).goto_expr.assign(
+                ptr.clone().assign(
                     BuiltinFn::Calloc
-                        .call(vec![Type::size_t().one(), size], loc.clone())
+                        .call(vec![Type::size_t().one(), size.clone()], loc.clone())
                         .cast_to(Type::unsigned_int(8).to_pointer()),
                     loc.clone(),
                 ),
+                tcx.codegen_mark_init(ptr, size, true, loc.clone()),
                 Stmt::goto(tcx.current_fn().find_label(&target), loc.clone()),
             ],
             loc,
@@ -650,24 +1261,61 @@ fn handle(
     }
 }
 
+/// The destructors of thread locals run when their thread exits, which we do not model, so
+/// registering one does nothing.
+struct ThreadLocalDtor;
+
+impl<'tcx> GotocHook<'tcx> for ThreadLocalDtor {
+    fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+        let name = with_no_trimmed_paths(|| tcx.def_path_str(instance.def_id()));
+        name == "std::sys::unix::thread_local_dtor::register_dtor"
+    }
+
+    fn handle(
+        &self,
+        tcx: &mut GotocCtx<'tcx>,
+        _instance: Instance<'tcx>,
+        _fargs: Vec<Expr>,
+        _assign_to: Option<Place<'tcx>>,
+        target: Option<BasicBlock>,
+        span: Option<Span>,
+    ) -> Stmt {
+        let loc = tcx.codegen_span_option(span);
+        Stmt::goto(tcx.current_fn().find_label(&target.unwrap()), loc)
+    }
+}
+
 pub fn fn_hooks<'tcx>() -> GotocHooks<'tcx> {
     GotocHooks {
         hooks: vec![
             Rc::new(Panic), //Must go first, so it overrides Nevers
             Rc::new(Assume),
+            Rc::new(CondvarWaitTimeout),
             Rc::new(ExpectFail),
+            Rc::new(HashmapRandomKeys),
+            Rc::new(Havoc),
             Rc::new(Intrinsic),
+            Rc::new(LoopInvariant),
+            Rc::new(MaybeUninitUninit),
             Rc::new(MemReplace),
             Rc::new(MemSwap),
+            Rc::new(MinStack),
             Rc::new(Nevers),
             Rc::new(Nondet),
+            Rc::new(PanicCleanup),
+            Rc::new(PanicCount),
+            Rc::new(Panicking),
+            Rc::new(Pthread),
+            Rc::new(PthreadSync),
             Rc::new(PtrRead),
             Rc::new(PtrWrite),
+            Rc::new(Quantifier),
             Rc::new(RustAlloc),
             Rc::new(RustAllocZeroed),
             Rc::new(RustDealloc),
             Rc::new(RustRealloc),
             Rc::new(SliceFromRawPart),
+            Rc::new(ThreadLocalDtor),
         ],
     }
 }
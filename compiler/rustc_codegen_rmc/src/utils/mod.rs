@@ -5,11 +5,13 @@
 
 mod debug;
 mod names;
+mod resolve;
 mod utils;
 
 // TODO clean this up
 
 pub use names::*;
+pub use resolve::*;
 pub use utils::*;
 
 pub fn init() {
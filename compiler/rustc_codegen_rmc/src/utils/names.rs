@@ -11,7 +11,8 @@
 use rustc_middle::mir::mono::CodegenUnitNameBuilder;
 use rustc_middle::mir::Local;
 use rustc_middle::ty::print::with_no_trimmed_paths;
-use rustc_middle::ty::{Instance, TyCtxt};
+use rustc_middle::ty::{GeneratorSubsts, Instance, TyCtxt};
+use rustc_target::abi::VariantIdx;
 use tracing::debug;
 
 impl<'tcx> GotocCtx<'tcx> {
@@ -98,6 +99,11 @@ pub fn tuple_fld_name(n: usize) -> String {
         format!("{}", n)
     }
 
+    /// The name for the state of a generator, e.g. `Unresumed` or `Suspend0`
+    pub fn generator_variant_name(idx: VariantIdx) -> String {
+        GeneratorSubsts::variant_name(idx).to_string()
+    }
+
     /// The name for the struct field on a vtable for a given function. Because generic
     /// functions can share the same name, we need to use the index of the entry in the
     /// vtable. This is the same index that will be passed in virtual function calls as
@@ -0,0 +1,105 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Resolves the paths to functions given as arguments to RMC attributes, e.g. `#[rmc::stub(f, g)]`.
+//!
+//! Attributes are not resolved by the compiler, so we resolve their paths ourselves. We only
+//! support the common cases: paths that start with `crate`, `self`, `super`, the name of an item of
+//! the current module, or the name of a crate, followed by the names of modules, items, or
+//! inherent methods. Glob imports and the prelude are not taken into account.
+
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::{DefId, LocalDefId, CRATE_DEF_ID};
+use rustc_hir::{ItemKind, UseKind};
+use rustc_middle::ty::TyCtxt;
+
+/// Resolves `path`, written in the module `module`, to the function it names.
+pub fn resolve_fn_path(
+    tcx: TyCtxt<'_>,
+    module: LocalDefId,
+    path: &[String],
+) -> Result<DefId, String> {
+    let mut segments = path.iter();
+    let first = segments.next().ok_or_else(|| "empty path".to_string())?;
+    let mut def_id = match first.as_str() {
+        "crate" => CRATE_DEF_ID.to_def_id(),
+        "self" => module.to_def_id(),
+        "super" => tcx.parent_module_from_def_id(module).to_def_id(),
+        name => resolve_in(tcx, module.to_def_id(), name)
+            .or_else(|| {
+                tcx.crates(())
+                    .iter()
+                    .find(|krate| &*tcx.crate_name(**krate).as_str() == name)
+                    .map(|krate| krate.as_def_id())
+            })
+            .ok_or_else(|| format!("cannot find `{}` in this scope", name))?,
+    };
+    for name in segments {
+        def_id = match name.as_str() {
+            "super" if def_id.is_local() => {
+                tcx.parent_module_from_def_id(def_id.expect_local()).to_def_id()
+            }
+            _ => resolve_in(tcx, def_id, name).ok_or_else(|| {
+                format!("cannot find `{}` in `{}`", name, tcx.def_path_str(def_id))
+            })?,
+        };
+    }
+    match tcx.def_kind(def_id) {
+        DefKind::Fn | DefKind::AssocFn => Ok(def_id),
+        _ => Err(format!("`{}` is not a function", path.join("::"))),
+    }
+}
+
+/// Finds the item or inherent method named `name` in `parent`, which is a module or a type.
+fn resolve_in(tcx: TyCtxt<'_>, parent: DefId, name: &str) -> Option<DefId> {
+    match tcx.def_kind(parent) {
+        DefKind::Mod => match parent.as_local() {
+            Some(local) => {
+                let (module, ..) = tcx.hir().get_module(local);
+                module.item_ids.iter().find_map(|id| {
+                    let item = tcx.hir().item(*id);
+                    if &*item.ident.as_str() != name {
+                        return None;
+                    }
+                    match item.kind {
+                        ItemKind::Use(path, UseKind::Single) => path.res.opt_def_id(),
+                        ItemKind::Use(..) => None,
+                        ItemKind::ExternCrate(_) => {
+                            tcx.extern_mod_stmt_cnum(item.def_id).map(|krate| krate.as_def_id())
+                        }
+                        _ => Some(item.def_id.to_def_id()),
+                    }
+                })
+            }
+            None => tcx
+                .item_children(parent)
+                .iter()
+                .find(|export| &*export.ident.as_str() == name)
+                .and_then(|export| export.res.opt_def_id()),
+        },
+        DefKind::Struct | DefKind::Enum | DefKind::Union => {
+            tcx.inherent_impls(parent).iter().find_map(|impl_def_id| {
+                tcx.associated_items(*impl_def_id)
+                    .in_definition_order()
+                    .find(|item| &*item.ident.as_str() == name)
+                    .map(|item| item.def_id)
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Finds the function named `name` that is defined next to `def_id`, in the same module or impl.
+pub fn resolve_sibling_fn(tcx: TyCtxt<'_>, def_id: DefId, name: &str) -> Option<DefId> {
+    let parent = tcx.parent(def_id)?;
+    let sibling = match tcx.def_kind(parent) {
+        DefKind::Impl => tcx
+            .associated_items(parent)
+            .in_definition_order()
+            .find(|item| &*item.ident.as_str() == name)
+            .map(|item| item.def_id),
+        DefKind::Mod => resolve_in(tcx, parent, name),
+        _ => None,
+    }?;
+    matches!(tcx.def_kind(sibling), DefKind::Fn | DefKind::AssocFn).then(|| sibling)
+}
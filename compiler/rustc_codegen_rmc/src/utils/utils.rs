@@ -1,9 +1,10 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 use super::super::codegen::TypeExt;
+use crate::context::UnsupportedConstruct;
 use crate::GotocCtx;
 use cbmc::btree_string_map;
-use cbmc::goto_program::{Expr, Location, Stmt, SymbolTable, Type};
+use cbmc::goto_program::{Expr, Location, PropertyClass, Stmt, SymbolTable, Type};
 use tracing::debug;
 
 // Should move into rvalue
@@ -34,15 +35,34 @@ pub fn codegen_unimplemented(
         loc: Location,
         url: &str,
     ) -> Expr {
-        // We should possibly upgrade this to a warning in the future, but for now emit at least something
+        self.codegen_unimplemented_with_detail(operation_name, None, t, loc, url)
+    }
+
+    /// Like `codegen_unimplemented`, with more information on this occurrence of the construct,
+    /// e.g. the function that a `drop_in_place` call is for. It is kept apart from the name of the
+    /// construct so that the report groups all the occurrences of the construct together.
+    pub fn codegen_unimplemented_with_detail(
+        &mut self,
+        operation_name: &str,
+        detail: Option<String>,
+        t: Type,
+        loc: Location,
+        url: &str,
+    ) -> Expr {
         debug!("codegen_unimplemented: {} at {}", operation_name, loc.short_string());
+        let description = match &detail {
+            Some(detail) => format!("{} ({})", operation_name, detail),
+            None => operation_name.to_string(),
+        };
+        self.record_unsupported_construct(operation_name, detail, &loc, Some(url));
 
         let body = vec![
             // Assert false to alert the user that there is a path that uses an unimplemented feature.
-            Stmt::assert_false(
+            self.codegen_assert_false(
+                PropertyClass::UnsupportedConstruct,
                 &format!(
                     "{} is not currently supported by RMC. Please post your example at {} ",
-                    operation_name, url
+                    description, url
                 ),
                 loc.clone(),
             ),
@@ -53,6 +73,27 @@ pub fn codegen_unimplemented(
 
         Expr::statement_expression(body, t).with_location(loc)
     }
+
+    /// Records a construct that RMC does not support, so that it can be reported to the user
+    /// at the end of codegen.
+    pub fn record_unsupported_construct(
+        &mut self,
+        construct: &str,
+        detail: Option<String>,
+        loc: &Location,
+        url: Option<&str>,
+    ) {
+        let construct = UnsupportedConstruct {
+            construct: construct.to_string(),
+            detail,
+            function: self.current_fn.as_ref().map(|f| f.readable_name().to_string()),
+            location: loc.short_string(),
+            url: url.map(|url| url.to_string()),
+        };
+        // The same construct is found once for each instance of a generic function, and a set
+        // only keeps the first one.
+        self.unsupported_constructs.insert(construct);
+    }
 }
 
 impl<'tcx> GotocCtx<'tcx> {
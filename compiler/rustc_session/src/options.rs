@@ -1042,6 +1042,9 @@ mod parse {
         "make cfg(version) treat the current version as incomplete (default: no)"),
     asm_comments: bool = (false, parse_bool, [TRACKED],
         "generate comments into the assembly (may change behavior) (default: no)"),
+    asm_overapproximation: bool = (false, parse_bool, [TRACKED],
+        "over-approximate inline assembly by making its outputs, and the memory behind its pointer \
+        inputs, nondeterministic (RMC only) (default: no)"),
     ast_json: bool = (false, parse_bool, [UNTRACKED],
         "print the AST as JSON and halt (default: no)"),
     ast_json_noexpand: bool = (false, parse_bool, [UNTRACKED],
@@ -1059,6 +1062,9 @@ mod parse {
         "the backend to use"),
     combine_cgu: bool = (false, parse_bool, [TRACKED],
         "combine CGUs into a single one"),
+    concurrency: bool = (false, parse_bool, [TRACKED],
+        "model spawned threads as concurrent threads, and let weak compare-and-exchange \
+        operations fail spuriously (RMC only) (default: no)"),
     crate_attr: Vec<String> = (Vec::new(), parse_string_push, [TRACKED],
         "inject the given attribute in the crate"),
     debug_info_for_profiling: bool = (false, parse_bool, [TRACKED],
@@ -1226,6 +1232,9 @@ mod parse {
         "support compiling tests with panic=abort (default: no)"),
     panic_in_drop: PanicStrategy = (PanicStrategy::Unwind, parse_panic_strategy, [TRACKED],
         "panic strategy for panics in drops"),
+    panic_unwinding: bool = (false, parse_bool, [TRACKED],
+        "model unwinding of panics, so that cleanup code runs and `catch_unwind` catches \
+        them (RMC only) (default: no)"),
     parse_only: bool = (false, parse_bool, [UNTRACKED],
         "parse only; do not compile, assemble, or link (default: no)"),
     partially_uninit_const_threshold: Option<usize> = (None, parse_opt_number, [TRACKED],
@@ -1366,6 +1375,8 @@ mod parse {
         "in diagnostics, use heuristics to shorten paths referring to items"),
     ui_testing: bool = (false, parse_bool, [UNTRACKED],
         "emit compiler diagnostics in a form suitable for UI testing (default: no)"),
+    uninit_checks: bool = (false, parse_bool, [TRACKED],
+        "check for reads of uninitialized memory (RMC only) (default: no)"),
     unleash_the_miri_inside_of_you: bool = (false, parse_bool, [TRACKED],
         "take the brakes off const evaluation. NOTE: this is unsound (default: no)"),
     unpretty: Option<String> = (None, parse_unpretty, [UNTRACKED],
@@ -1388,6 +1399,9 @@ mod parse {
         "use legacy .ctors section for initializers rather than .init_array"),
     validate_mir: bool = (false, parse_bool, [UNTRACKED],
         "validate MIR after each transformation"),
+    validity_checks: bool = (false, parse_bool, [TRACKED],
+        "check that values produced from raw bytes, e.g. by `transmute`, are valid for their type \
+        (RMC only) (default: no)"),
     verbose: bool = (false, parse_bool, [UNTRACKED],
         "in general, enable more debug printouts (default: no)"),
     verify_llvm_ir: bool = (false, parse_bool, [TRACKED],
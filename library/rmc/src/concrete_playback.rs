@@ -0,0 +1,62 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module replays the values of a counterexample found by RMC in a regular Rust test. The
+//! tests generated by `rmc --concrete-playback` call `concrete_playback_run` with the bytes of
+//! each value returned by `rmc::nondet` in the counterexample, in the order they were returned.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+thread_local! {
+    static CONCRETE_VALUES: RefCell<Option<VecDeque<Vec<u8>>>> = RefCell::new(None);
+}
+
+/// Runs `harness` so that every call to `rmc::nondet` returns the next value of `values`.
+///
+/// # Example:
+///
+/// ```rust,ignore
+/// #[test]
+/// fn rmc_concrete_playback_check_add() {
+///     let concrete_values: Vec<Vec<u8>> = vec![
+///         // 255
+///         vec![255],
+///     ];
+///     rmc::concrete_playback_run(concrete_values, check_add);
+/// }
+/// ```
+pub fn concrete_playback_run<F: Fn()>(values: Vec<Vec<u8>>, harness: F) {
+    CONCRETE_VALUES.with(|concrete_values| {
+        *concrete_values.borrow_mut() = Some(values.into_iter().collect());
+    });
+    harness();
+    CONCRETE_VALUES.with(|concrete_values| {
+        *concrete_values.borrow_mut() = None;
+    });
+}
+
+/// Returns the next value to replay. This is what `rmc::nondet` does outside of RMC.
+pub(crate) fn next_value<T>() -> T {
+    // RMC does not put zero-sized values in the counterexample, since they have no bytes.
+    if std::mem::size_of::<T>() == 0 {
+        // SAFETY: Reading a zero-sized value reads no memory, so any aligned pointer is valid.
+        return unsafe { std::ptr::NonNull::<T>::dangling().as_ptr().read() };
+    }
+    let bytes = CONCRETE_VALUES.with(|concrete_values| {
+        concrete_values
+            .borrow_mut()
+            .as_mut()
+            .expect("rmc::nondet can only be executed by RMC or by `rmc::concrete_playback_run`")
+            .pop_front()
+            .expect("the counterexample has no more values for rmc::nondet")
+    });
+    assert_eq!(
+        bytes.len(),
+        std::mem::size_of::<T>(),
+        "the counterexample value does not have the size of `{}`",
+        std::any::type_name::<T>()
+    );
+    // SAFETY: The bytes come from a value of type `T` that RMC found, so they are valid for `T`.
+    unsafe { std::ptr::read_unaligned(bytes.as_ptr() as *const T) }
+}
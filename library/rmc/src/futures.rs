@@ -0,0 +1,50 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! A minimal executor, so that proof harnesses can run async code.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::ptr;
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+/// A waker that does nothing, since [`block_on`] polls its future until it completes anyway.
+const NOOP_RAW_WAKER: RawWaker = RawWaker::new(ptr::null(), &NOOP_VTABLE);
+
+const NOOP_VTABLE: RawWakerVTable = RawWakerVTable::new(noop_clone, noop, noop, noop);
+
+unsafe fn noop_clone(_data: *const ()) -> RawWaker {
+    NOOP_RAW_WAKER
+}
+
+unsafe fn noop(_data: *const ()) {}
+
+/// Runs the future `fut` to completion on the current thread, and returns its output.
+///
+/// The future is polled again whenever it returns [`Poll::Pending`], so a future that waits for
+/// something that never happens makes the harness loop forever, which RMC reports as an
+/// unwinding assertion failure when the loop is bounded with `--unwind`.
+///
+/// # Example:
+///
+/// ```rust
+/// async fn add(a: u32, b: u32) -> u32 {
+///     a + b
+/// }
+///
+/// let x: u8 = rmc::nondet();
+/// let y = rmc::block_on(add(x.into(), 1));
+/// assert!(y > x.into());
+/// ```
+pub fn block_on<T>(fut: impl Future<Output = T>) -> T {
+    let waker = unsafe { Waker::from_raw(NOOP_RAW_WAKER) };
+    let cx = &mut Context::from_waker(&waker);
+    let mut fut = fut;
+    // Safety: `fut` is a local that is not moved after it is pinned.
+    let mut fut = unsafe { Pin::new_unchecked(&mut fut) };
+    loop {
+        if let Poll::Ready(output) = fut.as_mut().poll(cx) {
+            return output;
+        }
+    }
+}
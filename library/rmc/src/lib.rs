@@ -2,6 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 #![feature(rustc_attrs)] // Used for rustc_diagnostic_item.
 
+mod concrete_playback;
+mod futures;
+
+pub use concrete_playback::concrete_playback_run;
+pub use futures::block_on;
+pub use rmc_macros::{
+    asm_model, ensures, exists, forall, proof, proof_for_contract, requires, stub, unwind,
+};
+
 /// Creates an assumption that will be valid after this statement run. Note that the assumption
 /// will only be applied for paths that follow the assumption. If the assumption doesn't hold, the
 /// program will exit successfully.
@@ -41,13 +50,82 @@ pub fn assume(_cond: bool) {}
 /// let inputA = rmc::nondet::<i32>();
 /// fn_under_verification(inputA);
 /// ```
+///
+/// Outside of RMC, this returns the values of a counterexample replayed by
+/// [`concrete_playback_run`].
 #[inline(never)]
 #[rustc_diagnostic_item = "RmcNonDet"]
 pub fn nondet<T>() -> T {
-    unimplemented!("RMC nondet")
+    concrete_playback::next_value()
 }
 
 /// Function used in tests for cases where the condition is not always true.
 #[inline(never)]
 #[rustc_diagnostic_item = "RmcExpectFail"]
 pub fn expect_fail(_cond: bool, _message: &str) {}
+
+/// Declares an invariant for the loop that immediately follows this statement, optionally along
+/// with a measure that decreases on every iteration. RMC then proves the loop with
+/// `goto-instrument --apply-loop-contracts` instead of unwinding it.
+///
+/// The invariant and the measure are evaluated at the head of the loop, and can refer to any
+/// variable in scope.
+///
+/// # Example:
+///
+/// ```rust
+/// let n: usize = rmc::nondet();
+/// let mut i = 0;
+/// rmc::loop_invariant!(i <= n, decreases: n - i);
+/// while i < n {
+///     i += 1;
+/// }
+/// assert!(i == n);
+/// ```
+#[macro_export]
+macro_rules! loop_invariant {
+    ($invariant:expr) => {
+        $crate::__loop_invariant(|| $invariant)
+    };
+    ($invariant:expr, decreases: $measure:expr) => {
+        $crate::__loop_invariant_decreases(|| $invariant, || $measure)
+    };
+}
+
+#[doc(hidden)]
+#[inline(never)]
+#[rustc_diagnostic_item = "RmcLoopInvariant"]
+pub fn __loop_invariant<F: Fn() -> bool>(invariant: F) {
+    // Calling the closure makes sure it gets codegenned.
+    let _ = invariant();
+}
+
+#[doc(hidden)]
+#[inline(never)]
+#[rustc_diagnostic_item = "RmcLoopInvariantDecreases"]
+pub fn __loop_invariant_decreases<F: Fn() -> bool, D: Fn() -> M, M>(invariant: F, measure: D) {
+    // Calling the closures makes sure they get codegenned.
+    let _ = invariant();
+    let _ = measure();
+}
+
+/// Sets the value that `target` refers to to an unconstrained value, without dropping the old one.
+/// The function that replaces calls by a contract uses it on the `&mut` arguments.
+#[doc(hidden)]
+#[inline(never)]
+#[rustc_diagnostic_item = "RmcHavoc"]
+pub fn __havoc<T: ?Sized>(_target: &mut T) {}
+
+#[doc(hidden)]
+#[inline(never)]
+#[rustc_diagnostic_item = "RmcForall"]
+pub fn __forall<F: Fn(usize) -> bool>(lower: usize, upper: usize, predicate: F) -> bool {
+    (lower..upper).all(predicate)
+}
+
+#[doc(hidden)]
+#[inline(never)]
+#[rustc_diagnostic_item = "RmcExists"]
+pub fn __exists<F: Fn(usize) -> bool>(lower: usize, upper: usize, predicate: F) -> bool {
+    (lower..upper).any(predicate)
+}
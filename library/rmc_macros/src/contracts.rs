@@ -0,0 +1,216 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Expansion of the function contract attributes, `#[rmc::requires(..)]` and `#[rmc::ensures(..)]`.
+//!
+//! The outermost contract attribute of a function collects every other contract attribute of the
+//! same function and generates two companion functions next to it:
+//!
+//! - `__rmc_check_<name>`, which assumes the preconditions, runs a copy of the original body, and
+//!   asserts the postconditions. Calls are redirected to it when verifying a harness annotated with
+//!   `#[rmc::proof_for_contract(<path to the function>)]`.
+//! - `__rmc_replace_<name>`, which asserts the preconditions, havocs the return value (and the
+//!   targets of `&mut` arguments), and assumes the postconditions. Calls are redirected to it
+//!   everywhere else.
+//!
+//! The original function is tagged with `#[rmctool::contract(<check>, <replace>)]` so the codegen
+//! backend can list it in the crate metadata, along with the symbols of the two functions, which it
+//! finds next to the original one; the driver then redirects the calls with
+//! `goto-instrument --replace-calls`. The two functions are tagged with
+//! `#[rmctool::contract_companion]` so that the backend codegens them although nothing calls them.
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::visit_mut::{self, VisitMut};
+use syn::{parse_quote, Attribute, Expr, FnArg, Ident, ItemFn, Pat, ReturnType, Type};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ClauseKind {
+    Requires,
+    Ensures,
+}
+
+/// The name of the function that checks the contract of `function`.
+fn check_name(function: &Ident) -> Ident {
+    format_ident!("__rmc_check_{}", function)
+}
+
+/// The name of the function that replaces calls to `function` by its contract.
+fn replace_name(function: &Ident) -> Ident {
+    format_ident!("__rmc_replace_{}", function)
+}
+
+/// If `attr` is a contract attribute (`#[rmc::requires]`, `#[requires]`, ...), returns its kind.
+fn clause_kind(attr: &Attribute) -> Option<ClauseKind> {
+    let segments: Vec<String> = attr.path.segments.iter().map(|s| s.ident.to_string()).collect();
+    let name = match segments.as_slice() {
+        [name] => name,
+        [krate, name] if krate == "rmc" => name,
+        _ => return None,
+    };
+    match name.as_str() {
+        "requires" => Some(ClauseKind::Requires),
+        "ensures" => Some(ClauseKind::Ensures),
+        _ => None,
+    }
+}
+
+/// Replaces every `old(e)` by a fresh variable, and remembers `e` so that it can be evaluated
+/// before the function is called.
+struct OldValues {
+    values: Vec<(Ident, Expr)>,
+}
+
+impl VisitMut for OldValues {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        if let Expr::Call(call) = expr {
+            if let Expr::Path(func) = &*call.func {
+                if func.path.is_ident("old") && call.args.len() == 1 {
+                    let mut value = call.args[0].clone();
+                    self.visit_expr_mut(&mut value);
+                    let var = format_ident!("__rmc_old_{}", self.values.len());
+                    self.values.push((var.clone(), value));
+                    *expr = parse_quote!(#var);
+                    return;
+                }
+            }
+        }
+        visit_mut::visit_expr_mut(self, expr);
+    }
+}
+
+/// An argument of the function with a contract.
+struct Arg {
+    name: Ident,
+    /// Whether the argument is a `&mut` reference, in which case its target may be modified.
+    is_mut_ref: bool,
+}
+
+fn collect_args(function: &ItemFn) -> syn::Result<Vec<Arg>> {
+    function
+        .sig
+        .inputs
+        .iter()
+        .map(|input| match input {
+            FnArg::Receiver(receiver) => Ok(Arg {
+                name: Ident::new("self", Span::call_site()),
+                is_mut_ref: receiver.reference.is_some() && receiver.mutability.is_some(),
+            }),
+            FnArg::Typed(arg) => match &*arg.pat {
+                Pat::Ident(pat) => Ok(Arg {
+                    name: pat.ident.clone(),
+                    is_mut_ref: matches!(&*arg.ty, Type::Reference(r) if r.mutability.is_some()),
+                }),
+                pat => Err(syn::Error::new_spanned(
+                    pat,
+                    "function contracts only support arguments bound to a single name",
+                )),
+            },
+        })
+        .collect()
+}
+
+pub fn expand(kind: ClauseKind, attr: TokenStream, item: TokenStream) -> TokenStream {
+    match expand_contract(kind, attr, item) {
+        Ok(tokens) => tokens,
+        Err(error) => error.to_compile_error(),
+    }
+}
+
+fn expand_contract(
+    kind: ClauseKind,
+    attr: TokenStream,
+    item: TokenStream,
+) -> syn::Result<TokenStream> {
+    let mut function: ItemFn = syn::parse2(item)?;
+
+    // Collect the clause of this attribute and of every other contract attribute on the function.
+    let mut clauses = vec![(kind, syn::parse2::<Expr>(attr)?)];
+    let mut other_attrs = vec![];
+    for attr in function.attrs.drain(..) {
+        match clause_kind(&attr) {
+            Some(kind) => clauses.push((kind, attr.parse_args::<Expr>()?)),
+            None => other_attrs.push(attr),
+        }
+    }
+    function.attrs = other_attrs;
+
+    if !function.sig.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &function.sig.generics,
+            "function contracts are not supported on generic functions",
+        ));
+    }
+    if let Some(asyncness) = &function.sig.asyncness {
+        return Err(syn::Error::new_spanned(
+            asyncness,
+            "function contracts are not supported on async functions",
+        ));
+    }
+
+    let mut old_values = OldValues { values: vec![] };
+    let mut requires = vec![];
+    let mut requires_msgs = vec![];
+    let mut ensures = vec![];
+    let mut ensures_msgs = vec![];
+    for (kind, mut clause) in clauses {
+        // The messages show the clauses as written, before `old(..)` is replaced.
+        match kind {
+            ClauseKind::Requires => {
+                requires_msgs.push(format!("contract precondition `{}` violated", quote!(#clause)));
+                requires.push(clause);
+            }
+            ClauseKind::Ensures => {
+                ensures_msgs.push(format!("contract postcondition `{}` violated", quote!(#clause)));
+                old_values.visit_expr_mut(&mut clause);
+                ensures.push(clause);
+            }
+        }
+    }
+    let (old_vars, old_exprs): (Vec<Ident>, Vec<Expr>) = old_values.values.into_iter().unzip();
+
+    let args = collect_args(&function)?;
+    let mut_ref_args: Vec<&Ident> = args.iter().filter(|a| a.is_mut_ref).map(|a| &a.name).collect();
+
+    let name = &function.sig.ident;
+    let check = check_name(name);
+    let replace = replace_name(name);
+    let ret: Type = match &function.sig.output {
+        ReturnType::Default => parse_quote!(()),
+        ReturnType::Type(_, ty) => (**ty).clone(),
+    };
+    let body = &function.block;
+
+    let mut check_sig = function.sig.clone();
+    check_sig.ident = check.clone();
+    let mut replace_sig = function.sig.clone();
+    replace_sig.ident = replace.clone();
+
+    let vis = &function.vis;
+
+    Ok(quote! {
+        #[rmctool::contract(#check, #replace)]
+        #function
+
+        #[rmctool::contract_companion]
+        #[allow(dead_code, unused_mut, unused_variables, clippy::redundant_closure_call)]
+        #vis #check_sig {
+            #(rmc::assume(#requires);)*
+            #(let #old_vars = (#old_exprs).clone();)*
+            let result: #ret = (|| -> #ret #body)();
+            #(assert!(#ensures, "{}", #ensures_msgs);)*
+            result
+        }
+
+        #[rmctool::contract_companion]
+        #[allow(dead_code, unused_mut, unused_variables)]
+        #vis #replace_sig {
+            #(assert!(#requires, "{}", #requires_msgs);)*
+            #(let #old_vars = (#old_exprs).clone();)*
+            #(rmc::__havoc(&mut *#mut_ref_args);)*
+            let result: #ret = rmc::nondet();
+            #(rmc::assume(#ensures);)*
+            result
+        }
+    })
+}
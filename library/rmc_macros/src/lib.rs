@@ -0,0 +1,189 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This crate provides the procedural macros exported by the `rmc` library.
+//!
+//! Each attribute macro rewrites the user facing attribute (e.g. `#[rmc::proof]`) into an attribute in the
+//! `rmctool` namespace, which `rmc-rustc` registers as a tool. The codegen backend then looks for
+//! `rmctool::*` attributes when it translates a function.
+//!
+//! We cannot register `rmc` itself as a tool, since its name would clash with the `rmc` crate.
+
+extern crate proc_macro;
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::Token;
+
+mod contracts;
+mod quantifiers;
+
+use contracts::ClauseKind;
+use quantifiers::QuantifierKind;
+
+/// Marks a function as a proof harness.
+///
+/// The function must not take any arguments. `rmc` and `cargo rmc` verify every proof harness
+/// found in the crate unless a specific entry point is requested with `--function`.
+#[proc_macro_attribute]
+pub fn proof(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        let attr = proc_macro2::TokenStream::from(attr);
+        return syn::Error::new_spanned(attr, "#[rmc::proof] does not take any arguments")
+            .to_compile_error()
+            .into();
+    }
+    proof_harness(TokenStream::new(), item)
+}
+
+/// Adds the attributes shared by every proof harness, plus the given `extra` attributes.
+fn proof_harness(extra: TokenStream, item: TokenStream) -> TokenStream {
+    let mut result = TokenStream::new();
+    result.extend("#[rmctool::proof]".parse::<TokenStream>().unwrap());
+    result.extend(extra);
+    // Nothing calls a harness, so the backend codegens it because of the `rmctool::proof`
+    // attribute, and records its symbol name for the driver to use as the CBMC entry point.
+    result.extend("#[allow(dead_code)]".parse::<TokenStream>().unwrap());
+    result.extend(item);
+    result
+}
+
+/// Sets the loop unwinding bound CBMC uses when verifying a proof harness, e.g.
+/// `#[rmc::unwind(10)]`.
+///
+/// This overrides the global bound given with `--cbmc-args --unwind N` for this harness only.
+/// On a function that is not a proof harness, this sets the bound of the loops of the function
+/// instead, in every harness.
+#[proc_macro_attribute]
+pub fn unwind(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let bound = match syn::parse::<syn::LitInt>(attr) {
+        Ok(lit) if lit.suffix().is_empty() && lit.base10_parse::<u32>().is_ok() => lit,
+        Ok(lit) => return unwind_error(lit.span()),
+        Err(error) => return unwind_error(error.span()),
+    };
+    let item = proc_macro2::TokenStream::from(item);
+    quote::quote!(
+        #[rmctool::unwind(#bound)]
+        #item
+    )
+    .into()
+}
+
+/// The error for an argument of `#[rmc::unwind]` that is not a `u32` literal.
+fn unwind_error(span: Span) -> TokenStream {
+    syn::Error::new(span, "#[rmc::unwind] expects an unsigned integer, e.g. #[rmc::unwind(10)]")
+        .to_compile_error()
+        .into()
+}
+
+/// Adds a precondition to the contract of a function, e.g. `#[rmc::requires(x < 100)]`.
+///
+/// Calls to a function with a contract are replaced by its contract when verifying any harness
+/// other than the ones annotated with `#[rmc::proof_for_contract]` for that function.
+#[proc_macro_attribute]
+pub fn requires(attr: TokenStream, item: TokenStream) -> TokenStream {
+    contracts::expand(ClauseKind::Requires, attr.into(), item.into()).into()
+}
+
+/// Adds a postcondition to the contract of a function, e.g. `#[rmc::ensures(result > old(*x))]`.
+///
+/// The postcondition can refer to the return value as `result`, and to the value an expression had
+/// when the function was called as `old(expr)`.
+#[proc_macro_attribute]
+pub fn ensures(attr: TokenStream, item: TokenStream) -> TokenStream {
+    contracts::expand(ClauseKind::Ensures, attr.into(), item.into()).into()
+}
+
+/// Marks a function as a proof harness that checks the contract of the function at the given
+/// path, e.g. `#[rmc::proof_for_contract(my_module::my_function)]`.
+///
+/// When verifying this harness, calls to `my_function` check its contract against its body instead
+/// of being replaced by the contract.
+#[proc_macro_attribute]
+pub fn proof_for_contract(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let target = match syn::parse::<syn::Path>(attr) {
+        Ok(path) => path,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    proof_harness(quote::quote!(#[rmctool::proof_for_contract(#target)]).into(), item)
+}
+
+/// Replaces every call to a function by a call to another function with the same signature when
+/// verifying this proof harness, e.g. `#[rmc::stub(std::process::id, mock_id)]`.
+///
+/// The function can be defined in any crate, including the standard library. The replacement
+/// cannot be generic; calls to a generic function are replaced for its instances that have the
+/// signature of the replacement.
+#[proc_macro_attribute]
+pub fn stub(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let paths = match Punctuated::<syn::Path, Token![,]>::parse_terminated.parse(attr) {
+        Ok(paths) => paths,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    let (target, replacement) = match (paths.first(), paths.last()) {
+        (Some(target), Some(replacement)) if paths.len() == 2 => (target, replacement),
+        _ => {
+            return syn::Error::new(
+                Span::call_site(),
+                "#[rmc::stub] expects a function and its replacement, e.g. #[rmc::stub(f, g)]",
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
+    let mut harness = match syn::parse::<syn::ItemFn>(item) {
+        Ok(harness) => harness,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    // Only the attribute names the replacement, so we refer to it in the harness to make sure
+    // that it is codegenned.
+    harness.block.stmts.insert(0, syn::parse_quote!(let _ = #replacement as *const ();));
+    quote::quote!(
+        #[rmctool::stub(#target, #replacement)]
+        #harness
+    )
+    .into()
+}
+
+/// Gives a model for the inline assembly of the function at the given path, e.g.
+/// `#[rmc::asm_model(timer::read_counter)]`.
+///
+/// The `asm!` block of the function is replaced by a call to the annotated function, which takes
+/// the values of the `in` and `inout` operands of the block and returns the values of its `out`
+/// and `inout` operands (as a tuple, unless there is exactly one). The function must have a
+/// single `asm!` block.
+#[proc_macro_attribute]
+pub fn asm_model(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let target = match syn::parse::<syn::Path>(attr) {
+        Ok(path) => path,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    let item = proc_macro2::TokenStream::from(item);
+    // Nothing calls the model, so the backend codegens it because of the `rmctool::asm_model`
+    // attribute.
+    quote::quote!(
+        #[rmctool::asm_model(#target)]
+        #[allow(dead_code)]
+        #item
+    )
+    .into()
+}
+
+/// Checks that a predicate holds for every index in a range, e.g.
+/// `rmc::forall!(|i in 0..v.len()| v[i] == 0)`.
+///
+/// The index has type `usize`. The predicate must not call functions that contain loops.
+#[proc_macro]
+pub fn forall(tokens: TokenStream) -> TokenStream {
+    quantifiers::expand(QuantifierKind::Forall, tokens.into()).into()
+}
+
+/// Checks that a predicate holds for some index in a range, e.g.
+/// `rmc::exists!(|i in 0..v.len()| v[i] == 0)`.
+///
+/// The index has type `usize`. The predicate must not call functions that contain loops.
+#[proc_macro]
+pub fn exists(tokens: TokenStream) -> TokenStream {
+    quantifiers::expand(QuantifierKind::Exists, tokens.into()).into()
+}
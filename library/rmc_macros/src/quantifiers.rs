@@ -0,0 +1,69 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Expansion of the quantifier macros, `rmc::forall!(|i in lo..hi| pred)` and
+//! `rmc::exists!(|i in lo..hi| pred)`.
+//!
+//! Both expand to a call to a hidden function of the `rmc` library that takes the bounds of the
+//! range and the predicate as a closure. The codegen backend recognizes these functions and turns
+//! the call into a CBMC quantifier over the index.
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{Expr, ExprRange, Ident, RangeLimits, Token};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum QuantifierKind {
+    Forall,
+    Exists,
+}
+
+/// `|variable in lower..upper| predicate`
+struct Quantifier {
+    variable: Ident,
+    lower: Expr,
+    upper: Expr,
+    predicate: Expr,
+}
+
+impl Parse for Quantifier {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<Token![|]>()?;
+        let variable = input.parse()?;
+        input.parse::<Token![in]>()?;
+        // The range cannot be parsed as an expression directly, since `lo..hi | pred` is a valid
+        // expression. Everything up to the closing `|` belongs to the range.
+        let mut range = TokenStream::new();
+        while !input.is_empty() && !input.peek(Token![|]) {
+            range.extend(std::iter::once(input.parse::<TokenTree>()?));
+        }
+        input.parse::<Token![|]>()?;
+        let predicate = input.parse()?;
+
+        let range: ExprRange = syn::parse2(range)?;
+        match (range.from, range.limits, range.to) {
+            (Some(lower), RangeLimits::HalfOpen(_), Some(upper)) => {
+                Ok(Quantifier { variable, lower: *lower, upper: *upper, predicate })
+            }
+            (_, limits, _) => Err(syn::Error::new_spanned(
+                limits,
+                "quantifiers expect a range of the form `lower..upper`",
+            )),
+        }
+    }
+}
+
+pub fn expand(kind: QuantifierKind, tokens: TokenStream) -> TokenStream {
+    let Quantifier { variable, lower, upper, predicate } = match syn::parse2(tokens) {
+        Ok(quantifier) => quantifier,
+        Err(error) => return error.to_compile_error(),
+    };
+    let function = match kind {
+        QuantifierKind::Forall => quote!(rmc::__forall),
+        QuantifierKind::Exists => quote!(rmc::__exists),
+    };
+    quote! {
+        #function(#lower, #upper, |#variable: usize| -> bool { #predicate })
+    }
+}
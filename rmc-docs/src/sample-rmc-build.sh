@@ -54,11 +54,6 @@ CBMC_ARGS=(
   --pointer-check
   --pointer-primitive-check
   --pointer-overflow-check
-  --signed-overflow-check
-  --undefined-shift-check
-  --unsigned-overflow-check
-  --conversion-check
-  --div-by-zero-check
   --float-overflow-check
   --nan-check
   # Additional options
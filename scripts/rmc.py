@@ -3,6 +3,7 @@
 
 import subprocess
 import atexit
+import json
 import os
 import os.path
 import sys
@@ -16,20 +17,24 @@ MY_PATH = pathlib.Path(__file__).parent.parent.absolute()
 GEN_C_LIB = MY_PATH / "library" / "rmc" / "gen_c_lib.c"
 EXIT_CODE_SUCCESS = 0
 CBMC_VERIFICATION_FAILURE_EXIT_CODE = 10
+# Returned instead of CBMC's failure code when the only failed properties are unsupported constructs
+VERIFICATION_INCONCLUSIVE_EXIT_CODE = 11
 
 MEMORY_SAFETY_CHECKS = ["--bounds-check",
                         "--pointer-check",
                         "--pointer-primitive-check"]
-OVERFLOW_CHECKS = ["--conversion-check",
-                   "--div-by-zero-check",
-                   "--float-overflow-check",
+# Integer overflow, shift, division and conversion checks are not included: the RMC compiler
+# generates them where Rust requires them, while CBMC would also check arithmetic that wraps around
+# and `as` casts that truncate or saturate in Rust.
+OVERFLOW_CHECKS = ["--float-overflow-check",
                    "--nan-check",
-                   "--pointer-overflow-check",
-                   "--signed-overflow-check",
-                   "--undefined-shift-check",
-                   "--unsigned-overflow-check"]
+                   "--pointer-overflow-check"]
 UNWINDING_CHECKS = ["--unwinding-assertions"]
 
+# The class of the assertions that replace unsupported constructs. Property classes are named by
+# `PropertyClass::as_str` in `compiler/cbmc/src/goto_program/property_class.rs`.
+UNSUPPORTED_CONSTRUCT_CLASS = "unsupported_construct"
+
 # A Scanner is intended to match a pattern with an output
 # and edit the output based on an edit function
 class Scanner:
@@ -111,7 +116,8 @@ def run_cmd(
         verbose=False,
         debug=False,
         scanners=[],
-        dry_run=False):
+        dry_run=False,
+        return_output=False):
     # If this a dry run, we emulate running a successful process whose output is the command itself
     # We set `output_to` to `stdout` so that the output is not omitted below
     if dry_run:
@@ -145,13 +151,29 @@ def run_cmd(
         with open(output_to, "w") as f:
             f.write(stdout)
 
+    if return_output:
+        return process.returncode, stdout
     return process.returncode
 
-def rustc_flags(mangler, symbol_table_passes):
+def rustc_flags(mangler, symbol_table_passes, validity_checks=False, uninit_checks=False,
+                panic_unwinding=False, concurrency=False, asm_overapproximation=False, overflow_checks=True):
     flags = [
         "-Z", f"symbol-mangling-version={mangler}",
         "-Z", f"symbol_table_passes={' '.join(symbol_table_passes)}",
     ]
+    # Arithmetic overflow is checked by the assertions that rustc inserts, so we leave them out
+    if not overflow_checks:
+        flags += ["-C", "overflow-checks=off"]
+    if validity_checks:
+        flags += ["-Z", "validity-checks"]
+    if uninit_checks:
+        flags += ["-Z", "uninit-checks"]
+    if panic_unwinding:
+        flags += ["-Z", "panic-unwinding"]
+    if concurrency:
+        flags += ["-Z", "concurrency"]
+    if asm_overapproximation:
+        flags += ["-Z", "asm-overapproximation"]
     if "RUSTFLAGS" in os.environ:
         flags += os.environ["RUSTFLAGS"].split(" ")
     return flags
@@ -168,12 +190,22 @@ def compile_single_rust_file(
         dry_run=False,
         use_abs=False,
         abs_type="std",
-        symbol_table_passes=[]):
+        symbol_table_passes=[],
+        validity_checks=False,
+        uninit_checks=False,
+        panic_unwinding=False,
+        concurrency=False,
+        asm_overapproximation=False,
+        overflow_checks=True):
     if not keep_temps:
         atexit.register(delete_file, output_filename)
         atexit.register(delete_file, base + ".type_map.json")
+        atexit.register(delete_file, base + ".rmc-metadata.json")
+        atexit.register(delete_file, base + ".unsupported.json")
 
-    build_cmd = [RMC_RUSTC_EXE] + rustc_flags(mangler, symbol_table_passes)
+    build_cmd = [RMC_RUSTC_EXE] + rustc_flags(mangler, symbol_table_passes, validity_checks, uninit_checks,
+                                               panic_unwinding, concurrency, asm_overapproximation,
+                                               overflow_checks)
 
     if use_abs:
         build_cmd += ["-Z", "force-unstable-if-unmarked=yes",
@@ -188,12 +220,18 @@ def compile_single_rust_file(
 
     return run_cmd(build_cmd, env=build_env, label="compile", verbose=verbose, debug=debug, dry_run=dry_run)
 
-# Generates a symbol table (and some other artifacts) from a rust crate
-def cargo_build(crate, target_dir, verbose=False, debug=False, mangler="v0", dry_run=False, symbol_table_passes=[]):
+# Generates a symbol table (and some other artifacts) from a rust crate.
+# Returns the exit code of `cargo build`, and the path without extension of the artifacts of each crate it built.
+def cargo_build(crate, target_dir, verbose=False, debug=False, mangler="v0", dry_run=False, symbol_table_passes=[],
+                validity_checks=False, uninit_checks=False, panic_unwinding=False, concurrency=False,
+                asm_overapproximation=False, overflow_checks=True):
     ensure(os.path.isdir(crate), f"Invalid path to crate: {crate}")
 
-    rustflags = rustc_flags(mangler, symbol_table_passes)
-    build_cmd = ["cargo", "build", "--lib", "--target-dir", str(target_dir)]
+    rustflags = rustc_flags(mangler, symbol_table_passes, validity_checks, uninit_checks,
+                            panic_unwinding, concurrency, asm_overapproximation, overflow_checks)
+    # Diagnostics are rendered as usual, and cargo lists the files of each crate it builds as JSON
+    build_cmd = ["cargo", "build", "--lib", "--target-dir", str(target_dir),
+                 "--message-format=json-render-diagnostics"]
     build_env = {"RUSTFLAGS": " ".join(rustflags),
                  "RUSTC": RMC_RUSTC_EXE,
                  "PATH": os.environ["PATH"]
@@ -204,7 +242,220 @@ def cargo_build(crate, target_dir, verbose=False, debug=False, mangler="v0", dry
         build_cmd.append("-v")
     if dry_run:
         print("{}".format(build_env))
-    return run_cmd(build_cmd, env=build_env, cwd=crate, label="build", verbose=verbose, debug=debug, dry_run=dry_run)
+    retcode, output = run_cmd(build_cmd, env=build_env, cwd=crate, label="build", verbose=verbose, debug=debug,
+                              dry_run=dry_run, return_output=True)
+    return retcode, get_cargo_artifacts(output)
+
+# Returns the path without extension of the artifacts listed in the JSON messages of `cargo build`, which is where
+# `rmc-rustc` writes its outputs (e.g. `deps/foo-1234.symtab.json` for `deps/libfoo-1234.rlib`).
+# Crates that are already up to date are listed too, but stale artifacts of other builds are not.
+def get_cargo_artifacts(output):
+    artifacts = []
+    for line in output.splitlines():
+        if not line.startswith("{"):
+            continue
+        message = json.loads(line)
+        if message.get("reason") != "compiler-artifact":
+            continue
+        for filename in message["filenames"]:
+            directory, name = os.path.split(os.path.splitext(filename)[0])
+            if name.startswith("lib") and filename.endswith((".rlib", ".rmeta", ".so", ".dylib")):
+                name = name[len("lib"):]
+            artifact = os.path.join(directory, name)
+            if artifact not in artifacts:
+                artifacts.append(artifact)
+    return artifacts
+
+# Reads the metadata files written by the compiler next to the symbol tables
+def load_metadata(metadata_files):
+    metadata = []
+    for metadata_file in metadata_files:
+        # Metadata files are missing on dry runs
+        if not os.path.isfile(metadata_file):
+            continue
+        with open(metadata_file) as f:
+            metadata.append(json.load(f))
+    return metadata
+
+# Returns the proof harnesses (`#[rmc::proof]`) found in the metadata files
+def get_proof_harnesses(metadata_files):
+    return [harness for metadata in load_metadata(metadata_files) for harness in metadata["proof_harnesses"]]
+
+# Determines the entry points to verify: the function given by the user, or else every proof harness
+# found in the metadata files, or else `main`. A proof harness can be given by its path, e.g.
+# `my_module::check_foo`, and any other function by its symbol name.
+def get_entry_points(function, metadata_files):
+    harnesses = get_proof_harnesses(metadata_files)
+    if function is not None:
+        matches = [harness["mangled_name"] for harness in harnesses if harness["pretty_name"] == function]
+        return matches if matches else [function]
+    return [harness["mangled_name"] for harness in harnesses] or ["main"]
+
+# Returns the name to report for the entry point `function`: the path of the proof harness it is, if any
+def get_entry_point_name(function, metadata_files):
+    harness = get_harness(function, metadata_files)
+    return harness["pretty_name"] if harness is not None else function
+
+# Returns the functions with a contract found in the metadata files
+def get_contracts(metadata_files):
+    return [contract for metadata in load_metadata(metadata_files) for contract in metadata["contracts"]]
+
+# Redirects the calls to functions with a contract: to the function that checks the contract, if
+# `harness` is a proof for that contract, and to the function that replaces calls by the contract
+# otherwise
+def replace_calls_with_contracts(goto_filename, harness, contracts, verbose=False, dry_run=False):
+    if not contracts:
+        return EXIT_CODE_SUCCESS
+    for_contract = harness.get("for_contract") if harness is not None else None
+    replacements = []
+    for contract in contracts:
+        if contract["mangled_name"] == for_contract:
+            replacement = contract["check_name"]
+        else:
+            replacement = contract["replace_name"]
+        replacements.append(f"{contract['mangled_name']}:{replacement}")
+    return run_goto_instrument(goto_filename, goto_filename, ["--replace-calls", ",".join(replacements)],
+                               verbose, dry_run=dry_run)
+
+# Redirects the calls to the functions that `harness` replaces with `#[rmc::stub]`
+def replace_calls_with_stubs(goto_filename, harness, verbose=False, dry_run=False):
+    stubs = harness.get("stubs") if harness is not None else None
+    if not stubs:
+        return EXIT_CODE_SUCCESS
+    replacements = [f"{stub['original_name']}:{stub['replacement_name']}" for stub in stubs]
+    return run_goto_instrument(goto_filename, goto_filename, ["--replace-calls", ",".join(replacements)],
+                               verbose, dry_run=dry_run)
+
+# Instruments the loops annotated with `rmc::loop_invariant!`, if any, with their contracts
+def apply_loop_contracts(goto_filename, metadata_files, verbose=False, dry_run=False):
+    if not any(metadata["has_loop_contracts"] for metadata in load_metadata(metadata_files)):
+        return EXIT_CODE_SUCCESS
+    return run_goto_instrument(goto_filename, goto_filename, ["--apply-loop-contracts"], verbose, dry_run=dry_run)
+
+# Instruments the accesses to shared memory with checks for data races between threads
+def add_race_checks(goto_filename, verbose=False, dry_run=False):
+    return run_goto_instrument(goto_filename, goto_filename, ["--race-check"], verbose, dry_run=dry_run)
+
+# Returns the metadata of the proof harness named `function`, or None if it is not a proof harness
+def get_harness(function, metadata_files):
+    for harness in get_proof_harnesses(metadata_files):
+        if harness["mangled_name"] == function:
+            return harness
+    return None
+
+# Adds the CBMC flags requested by the attributes of a proof harness.
+# A bound given with `#[rmc::unwind(N)]` takes precedence over a global `--unwind`.
+def add_harness_cbmc_flags(cbmc_args, harness):
+    if harness is None or harness.get("unwind_value") is None:
+        return
+    unwind_value = str(harness["unwind_value"])
+    if "--unwind" in cbmc_args:
+        cbmc_args[cbmc_args.index("--unwind") + 1] = unwind_value
+    else:
+        cbmc_args.extend(["--unwind", unwind_value])
+
+# Returns the functions whose loops are bounded with `#[rmc::unwind(N)]` in the metadata files
+def get_loop_bounds(metadata_files):
+    return [bound for metadata in load_metadata(metadata_files) for bound in metadata["loop_bounds"]]
+
+# Returns the ids of the loops of each function of a goto program (e.g. `foo.0`), as listed by
+# `goto-instrument --show-loops`
+def get_loop_ids(goto_filename, verbose=False, dry_run=False):
+    cmd = ["goto-instrument", "--show-loops", goto_filename]
+    retcode, output = run_cmd(cmd, label="goto-instrument", verbose=verbose, dry_run=dry_run, return_output=True)
+    loop_ids = {}
+    for line in output.splitlines():
+        match = re.match(r"^Loop ((.+)\.[0-9]+):$", line.strip())
+        if match:
+            loop_ids.setdefault(match.group(2), []).append(match.group(1))
+    return retcode, loop_ids
+
+# Bounds the loops of the functions annotated with `#[rmc::unwind(N)]` with CBMC's `--unwindset`.
+# These bounds take precedence over the bound of the harness, and a `--unwindset` given by the user
+# takes precedence over them.
+def add_loop_bounds(cbmc_args, goto_filename, loop_bounds, verbose=False, dry_run=False):
+    if not loop_bounds:
+        return EXIT_CODE_SUCCESS
+    retcode, loop_ids = get_loop_ids(goto_filename, verbose, dry_run)
+    if retcode != EXIT_CODE_SUCCESS:
+        return retcode
+    unwindset = [f"{loop_id}:{bound['unwind_value']}"
+                 for bound in loop_bounds for loop_id in loop_ids.get(bound["mangled_name"], [])]
+    if not unwindset:
+        return EXIT_CODE_SUCCESS
+    if "--unwindset" in cbmc_args:
+        index = cbmc_args.index("--unwindset") + 1
+        cbmc_args[index] = ",".join(unwindset + [cbmc_args[index]])
+    else:
+        cbmc_args.extend(["--unwindset", ",".join(unwindset)])
+    return EXIT_CODE_SUCCESS
+
+# Returns the verification result that corresponds to the exit code of an entry point
+def verification_result(retcode):
+    if retcode == EXIT_CODE_SUCCESS:
+        return "VERIFICATION SUCCESSFUL"
+    if retcode == CBMC_VERIFICATION_FAILURE_EXIT_CODE:
+        return "VERIFICATION FAILED"
+    if retcode == VERIFICATION_INCONCLUSIVE_EXIT_CODE:
+        return "VERIFICATION INCONCLUSIVE (an unsupported construct is reachable)"
+    return f"ERROR (exit code {retcode})"
+
+# Prints the verification result of each entry point
+def print_harness_summary(results):
+    inconclusive = [function for (function, retcode) in results if retcode == VERIFICATION_INCONCLUSIVE_EXIT_CODE]
+    failed = [function for (function, retcode) in results
+              if retcode not in [EXIT_CODE_SUCCESS, VERIFICATION_INCONCLUSIVE_EXIT_CODE]]
+    print("[RMC] Harness results:")
+    for (function, retcode) in results:
+        print(f"[RMC]   {function}: {verification_result(retcode)}")
+    print(f"[RMC] {len(failed)} of {len(results)} harnesses failed, {len(inconclusive)} inconclusive")
+
+# Runs `verify_fn` on each entry point, and prints a line with the result of each one. If there is
+# more than one, prints which one is being verified and a summary of all results at the end.
+# Returns the exit code of the first failing entry point, if any. An inconclusive result is only
+# returned if no entry point failed for another reason.
+def verify_entry_points(entry_points, verify_fn, metadata_files, quiet=False):
+    results = []
+    for function in entry_points:
+        name = get_entry_point_name(function, metadata_files)
+        if len(entry_points) > 1 and not quiet:
+            print(f"[RMC] Verifying harness: {name}")
+        retcode = verify_fn(function)
+        if not quiet:
+            print(f"[RMC] Harness {name}: {verification_result(retcode)}")
+        results.append((name, retcode))
+
+    if len(results) > 1 and not quiet:
+        print_harness_summary(results)
+
+    for (_, retcode) in results:
+        if retcode not in [EXIT_CODE_SUCCESS, VERIFICATION_INCONCLUSIVE_EXIT_CODE]:
+            return retcode
+    for (_, retcode) in results:
+        if retcode != EXIT_CODE_SUCCESS:
+            return retcode
+    return EXIT_CODE_SUCCESS
+
+# Returns the ids of the properties reported as failed in CBMC's output, either in text or XML
+def get_failed_properties(output):
+    text_failures = re.findall(r"^\[([^\]]+)\] .*: FAILURE$", output, re.MULTILINE)
+    xml_failures = re.findall(r"<result property=\"([^\"]+)\" status=\"FAILURE\"", output)
+    return text_failures + xml_failures
+
+# Returns the class of a property from its id, which has the form `<function>.<class>.<n>`
+def get_property_class(prop):
+    parts = prop.rsplit(".", 2)
+    return parts[1] if len(parts) == 3 else None
+
+# Distinguishes the verification failures caused only by reachable unsupported constructs, which
+# RMC replaces with `unsupported_construct` assertions, from the failures of other properties.
+def get_cbmc_verdict(retcode, output):
+    if retcode != CBMC_VERIFICATION_FAILURE_EXIT_CODE:
+        return retcode
+    failed_classes = [get_property_class(prop) for prop in get_failed_properties(output)]
+    if failed_classes and all(cls == UNSUPPORTED_CONSTRUCT_CLASS for cls in failed_classes):
+        return VERIFICATION_INCONCLUSIVE_EXIT_CODE
+    return retcode
 
 # Adds information about unwinding to the RMC output
 def append_unwind_tip(text):
@@ -244,14 +495,90 @@ def run_cbmc(cbmc_filename, cbmc_args, verbose=False, quiet=False, dry_run=False
         unwind_asserts_pattern = ".*unwinding assertion.*: FAILURE"
         unwind_asserts_scanner = Scanner(unwind_asserts_pattern, append_unwind_tip)
         scanners.append(unwind_asserts_scanner)
-    return run_cmd(
+    retcode, output = run_cmd(
         cbmc_cmd,
         label="cbmc",
         output_to="stdout",
         verbose=verbose,
         quiet=quiet,
         scanners=scanners,
-        dry_run=dry_run)
+        dry_run=dry_run,
+        return_output=True)
+    return get_cbmc_verdict(retcode, output)
+
+# Returns the values returned by `rmc::nondet` in a CBMC trace, in order. The RMC compiler stores
+# each of them in a variable whose name starts with `rmc_nondet`, which is declared and then assigned,
+# so only the last of consecutive assignments to the same variable is kept.
+def get_nondet_values(trace):
+    values = []
+    last_lhs = None
+    for step in trace:
+        if step.get("stepType") != "assignment":
+            continue
+        lhs = step.get("lhs", "")
+        if not re.search(r"(^|::)rmc_nondet_[0-9]+$", lhs):
+            last_lhs = None
+            continue
+        if lhs == last_lhs:
+            values[-1] = step["value"]
+        else:
+            values.append(step["value"])
+        last_lhs = lhs
+    return values
+
+# Returns the bytes of a value in a CBMC JSON trace, or None if it is not a scalar
+def get_value_bytes(value):
+    if "binary" not in value or value.get("name") == "pointer":
+        return None
+    binary = value["binary"]
+    return list(int(binary, 2).to_bytes((len(binary) + 7) // 8, "little"))
+
+# Returns the source of a unit test that runs `harness_name` with `values` as the results of `rmc::nondet`
+def generate_playback_test(harness_name, values):
+    test_name = "rmc_concrete_playback_" + re.sub(r"\W+", "_", harness_name)
+    lines = ["#[test]",
+             f"fn {test_name}() {{",
+             "    let concrete_values: Vec<Vec<u8>> = vec!["]
+    for value in values:
+        lines.append(f"        // {value.get('data', '')}")
+        lines.append(f"        vec!{get_value_bytes(value)},")
+    lines += ["    ];",
+              f"    rmc::concrete_playback_run(concrete_values, {harness_name});",
+              "}"]
+    return "\n".join(lines) + "\n"
+
+# Runs CBMC again on a goto program that failed verification to get the trace of a failure, and
+# writes a unit test that replays it with concrete values to `output_filename`.
+# Failures of properties other than unsupported constructs are preferred.
+def run_concrete_playback(cbmc_filename, cbmc_args, harness_name, output_filename,
+                          verbose=False, quiet=False, dry_run=False):
+    cbmc_cmd = ["cbmc"] + cbmc_args + ["--trace", "--json-ui", cbmc_filename]
+    retcode, output = run_cmd(cbmc_cmd, label="cbmc-playback", verbose=verbose, quiet=quiet or not dry_run,
+                              dry_run=dry_run, return_output=True)
+    # The JSON output is only printed on dry runs, which only show the command
+    if dry_run or retcode != CBMC_VERIFICATION_FAILURE_EXIT_CODE:
+        return retcode
+
+    results = [result for message in json.loads(output) if "result" in message for result in message["result"]]
+    failures = [result for result in results if result["status"] == "FAILURE"]
+    failures.sort(key=lambda result: get_property_class(result["property"]) == UNSUPPORTED_CONSTRUCT_CLASS)
+    if not failures:
+        return retcode
+    values = get_nondet_values(failures[0]["trace"])
+    unsupported = [value for value in values if get_value_bytes(value) is None]
+    if unsupported:
+        print(f"[RMC] warning: concrete playback does not support `rmc::nondet` values of type "
+              f"{unsupported[0].get('type')}; no test generated for {harness_name}")
+        return retcode
+
+    test = generate_playback_test(harness_name, values)
+    with open(output_filename, "w") as f:
+        f.write(test)
+    if not quiet:
+        print(f"[RMC] Concrete playback test for {harness_name} (property {failures[0]['property']}), "
+              f"written to {output_filename}:")
+        print(test)
+    return retcode
 
 # Generates a viewer report from a goto program
 def run_visualize(
@@ -294,6 +621,9 @@ def run_visualize(
     run_cbmc_viewer(cbmc_filename, results_filename, coverage_filename,
                     property_filename, verbose, quiet, srcdir, wkdir, os.path.join(outdir, "report"), dry_run=dry_run)
 
+    if retcode == CBMC_VERIFICATION_FAILURE_EXIT_CODE:
+        with open(results_filename) as f:
+            retcode = get_cbmc_verdict(retcode, f.read())
     return retcode
 
 # Handler for calling cbmc-viewer
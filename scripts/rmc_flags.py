@@ -100,8 +100,9 @@ def add_linking_flags(make_group, add_flag, config):
     add_flag(group, "--c-lib", type=pl.Path, nargs="*", default=[],
              action=ExtendAction,
              help="Link external C files referenced by Rust code")
-    add_flag(group, "--function", default="main",
-             help="Entry point for verification")
+    add_flag(group, "--function", default=None,
+             help="Entry point for verification; defaults to every proof harness (`#[rmc::proof]`), "
+                  "or `main` if there are none")
 
 # Add flags that produce extra artifacts.
 def add_artifact_flags(make_group, add_flag, config):
@@ -120,24 +121,37 @@ def add_artifact_flags(make_group, add_flag, config):
                   "at the cost of some readability")
     add_flag(group, "--gen-symbols", default=False, action=BooleanOptionalAction,
              help="Generate a goto symbol table")
+    add_flag(group, "--concrete-playback", default=False, action=BooleanOptionalAction,
+             help="Generate a unit test that replays the counterexample of each failed harness "
+                  "with concrete values for `rmc::nondet`")
     add_flag(group, "--keep-temps", default=False, action=BooleanOptionalAction,
              help="Keep temporary files generated throughout RMC process")
     add_flag(group, "--target-dir", type=pl.Path, default=default_target, metavar="DIR",
              help=f"Directory for all generated artifacts; defaults to \"{default_target}\"")
 
-# Add flags to turn off default checks.
+# Add flags to turn default checks off, or optional checks on.
 def add_check_flags(make_group, add_flag, config):
-    group = make_group("Check flags", "Disable some or all default checks.")
+    group = make_group("Check flags", "Disable some or all default checks, or enable optional ones.")
+    add_flag(group, "--asm-overapproximation", default=False, action=BooleanOptionalAction,
+             help="Over-approximate inline assembly instead of failing when it is reached")
+    add_flag(group, "--concurrency", default=False, action=BooleanOptionalAction,
+             help="Run spawned threads concurrently, and check for data races")
     add_flag(group, "--default-checks", default=True, action=BooleanOptionalAction,
              help="Turn on all default checks")
     add_flag(group, "--memory-safety-checks", default=True, action=BooleanOptionalAction,
              help="Turn on default memory safety checks")
     add_flag(group, "--overflow-checks", default=True, action=BooleanOptionalAction,
-             help="Turn on default overflow checks")
+             help="Turn on default overflow checks, including the arithmetic overflow checks that rustc inserts")
+    add_flag(group, "--panic-unwinding", default=False, action=BooleanOptionalAction,
+             help="Unwind panics, running cleanup code, so that `catch_unwind` can catch them")
     add_flag(group, "--undefined-function-checks", default=True, action=BooleanOptionalAction,
              help="Turn on undefined function checks")
     add_flag(group, "--unwinding-checks", default=True, action=BooleanOptionalAction,
              help="Turn on default unwinding checks")
+    add_flag(group, "--validity-checks", default=False, action=BooleanOptionalAction,
+             help="Check that values produced from raw bytes, e.g. by transmute, are valid for their type")
+    add_flag(group, "--uninit-checks", default=False, action=BooleanOptionalAction,
+             help="Check that reads of memory behind pointers and in unions are initialized")
 
 # Add flags needed only for visualizer.
 def add_visualizer_flags(make_group, add_flag, config):
@@ -0,0 +1,18 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that RMC checks the alignment of pointers that are read from or
+// written to, and not of those passed to the `_unaligned` functions.
+use std::ptr;
+
+pub fn main() {
+    let mut buf: [u32; 2] = [0; 2];
+    let offset: usize = rmc::nondet();
+    rmc::assume(offset < 4);
+    let p = unsafe { (buf.as_mut_ptr() as *mut u8).add(offset) } as *mut u32;
+    unsafe {
+        ptr::write_unaligned(p, 1);
+        let _ = ptr::read(p);
+        *p = 2;
+    }
+}
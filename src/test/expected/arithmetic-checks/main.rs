@@ -0,0 +1,16 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that RMC generates the arithmetic checks required by Rust, and no
+// others: wrapping operations are not checked, while the `unchecked_*`
+// intrinsics are checked for undefined behavior.
+#![feature(core_intrinsics)]
+
+pub fn main() {
+    let a: u8 = rmc::nondet();
+    let b: u32 = rmc::nondet();
+    let _ = a.wrapping_add(1);
+    let _ = a << b;
+    let _ = unsafe { std::intrinsics::unchecked_shl(a, 8) };
+    let _ = unsafe { std::intrinsics::unchecked_div(a, a) };
+}
@@ -0,0 +1,26 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that `#[rmc::asm_model]` rejects a function with several `asm!` blocks, since it cannot
+// tell which block the model is for.
+#![feature(asm)]
+
+fn add_two(x: u64) -> u64 {
+    let y: u64;
+    let z: u64;
+    unsafe { asm!("lea {0}, [{1} + 1]", out(reg) y, in(reg) x, options(pure, nomem, nostack)) };
+    unsafe { asm!("lea {0}, [{1} + 1]", out(reg) z, in(reg) y, options(pure, nomem, nostack)) };
+    z
+}
+
+#[rmc::asm_model(add_two)]
+fn add_one_model(x: u64) -> u64 {
+    x.wrapping_add(1)
+}
+
+#[rmc::proof]
+fn check_add_two() {
+    let x: u64 = rmc::nondet();
+    rmc::assume(x < 100);
+    assert!(add_two(x) == x + 2);
+}
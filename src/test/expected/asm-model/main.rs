@@ -0,0 +1,68 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that `#[rmc::asm_model]` replaces the inline assembly of a function by a call to the
+// model, which takes the inputs of the assembly and returns its outputs.
+#![feature(asm)]
+
+fn add_one(x: u64) -> u64 {
+    let y: u64;
+    unsafe { asm!("lea {0}, [{1} + 1]", out(reg) y, in(reg) x, options(pure, nomem, nostack)) };
+    y
+}
+
+fn read_counter() -> u64 {
+    let lo: u32;
+    let hi: u32;
+    unsafe { asm!("rdtsc", out("eax") lo, out("edx") hi, options(nomem, nostack)) };
+    ((hi as u64) << 32) | lo as u64
+}
+
+mod timer {
+    pub fn read_counter() -> u64 {
+        let lo: u32;
+        let hi: u32;
+        unsafe { asm!("rdtsc", out("eax") lo, out("edx") hi, options(nomem, nostack)) };
+        ((hi as u64) << 32) | lo as u64
+    }
+
+    // Models are given to functions by path, so they do not apply to functions with the same name.
+    #[rmc::asm_model(self::read_counter)]
+    fn read_counter_model() -> (u32, u32) {
+        (5, 0)
+    }
+}
+
+#[rmc::asm_model(add_one)]
+fn add_one_model(x: u64) -> u64 {
+    x.wrapping_add(1)
+}
+
+#[rmc::asm_model(read_counter)]
+fn read_counter_model() -> (u32, u32) {
+    let lo: u32 = rmc::nondet();
+    rmc::assume(lo < 1000);
+    (lo, 0)
+}
+
+#[rmc::proof]
+fn check_add_one() {
+    let x: u64 = rmc::nondet();
+    rmc::assume(x < 100);
+    assert!(add_one(x) == x + 1);
+}
+
+#[rmc::proof]
+fn check_read_counter() {
+    assert!(read_counter() < 1000);
+}
+
+#[rmc::proof]
+fn check_timer_read_counter() {
+    assert!(timer::read_counter() == 5);
+}
+
+#[rmc::proof]
+fn check_read_counter_fail() {
+    assert!(read_counter() == 0);
+}
@@ -5,9 +5,6 @@
 /// This leads us to the land of foreign types, ReifyFnPointer, and transmute.
 /// The "C" output from RMC is about 1KLOC, vs 80LOC for the same version with straight `assert!`.
 ///     https://github.com/model-checking/rmc/issues/14
-/// The assertion message printed to the user on success is uninformative:
-///     "library/std/src/macros.rs line 17 a panicking function core::panicking::panic_fmt is invoked: SUCCESS"
-///     https://github.com/model-checking/rmc/issues/13
 
 pub fn main() {
     let x = 1;
@@ -0,0 +1,22 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that the messages given to `assert!`, `assert_eq!`, `panic!` and
+// `rmc::expect_fail` become the descriptions of the properties that RMC checks.
+// The values of formatted arguments are not known statically, so they are
+// shown as `{}`.
+
+pub fn main() {
+    let x: u32 = rmc::nondet();
+    let y: u32 = rmc::nondet();
+    rmc::assume(x < 100 && y < 100);
+    assert!(x < 100, "x is out of range");
+    assert!(y < 50, "y is too large: {}", y);
+    assert_eq!(x, x, "x differs from itself");
+    assert_ne!(x, x + 1, "{} equals its successor", x);
+    if x == 42 {
+        panic!("the answer");
+    }
+    rmc::expect_fail(x + y < 100, "the sum can overflow the range");
+    assert!(x + y < 200, "{0} plus {0}", x);
+}
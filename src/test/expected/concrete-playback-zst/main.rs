@@ -0,0 +1,18 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// rmc-flags: --concrete-playback
+
+// Check that zero-sized values are not part of the counterexample, so that the values of the
+// generated unit test line up with the calls to `rmc::nondet` when it is replayed.
+
+struct Marker;
+
+pub fn main() {
+    let x: u8 = rmc::nondet();
+    let _unit: () = rmc::nondet();
+    let _marker: Marker = rmc::nondet();
+    let _empty: [u32; 0] = rmc::nondet();
+    let y: bool = rmc::nondet();
+    assert!(x != 7 || !y);
+}
@@ -0,0 +1,12 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// rmc-flags: --concrete-playback
+
+// Check that RMC generates a unit test with the values of the counterexample.
+
+pub fn main() {
+    let x: u32 = rmc::nondet();
+    let y: bool = rmc::nondet();
+    assert!(x != 0x01020304 || !y);
+}
@@ -0,0 +1,14 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that RMC reports a dereference of a pointer to a local whose storage
+// was deallocated when it went out of scope.
+fn dangling() -> *const u32 {
+    let x = 10;
+    &x
+}
+
+pub fn main() {
+    let p = dangling();
+    let _ = unsafe { *p };
+}
@@ -0,0 +1,81 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that function contracts are checked against the body of the function by the harnesses
+// annotated with `#[rmc::proof_for_contract]`, and replace calls to the function everywhere else.
+
+#[rmc::requires(x < 100)]
+#[rmc::ensures(result == x + 1)]
+fn increment(x: u32) -> u32 {
+    x + 1
+}
+
+#[rmc::requires(*x < 100)]
+#[rmc::ensures(*x == old(*x) + 1)]
+fn increment_in_place(x: &mut u32) {
+    *x += 1;
+}
+
+#[rmc::proof_for_contract(increment)]
+fn check_increment() {
+    let x: u32 = rmc::nondet();
+    increment(x);
+}
+
+#[rmc::proof_for_contract(increment_in_place)]
+fn check_increment_in_place() {
+    let mut x: u32 = rmc::nondet();
+    increment_in_place(&mut x);
+}
+
+#[rmc::proof]
+fn use_contracts() {
+    let mut x: u32 = rmc::nondet();
+    rmc::assume(x < 50);
+    let y = increment(x);
+    increment_in_place(&mut x);
+    assert!(x == y);
+}
+
+#[rmc::proof]
+fn violate_precondition() {
+    increment(200);
+}
+
+// Functions with a contract can share their name with other functions with a contract.
+mod wrapping {
+    #[rmc::ensures(result == x.wrapping_add(1))]
+    pub fn increment(x: u32) -> u32 {
+        x.wrapping_add(1)
+    }
+}
+
+struct Counter(u32);
+
+impl Counter {
+    #[rmc::requires(self.0 < 100)]
+    #[rmc::ensures(self.0 == old(self.0) + 1)]
+    fn increment(&mut self) {
+        self.0 += 1;
+    }
+}
+
+#[rmc::proof_for_contract(wrapping::increment)]
+fn check_wrapping_increment() {
+    let x: u32 = rmc::nondet();
+    wrapping::increment(x);
+}
+
+#[rmc::proof_for_contract(Counter::increment)]
+fn check_counter_increment() {
+    let mut counter = Counter(rmc::nondet());
+    counter.increment();
+}
+
+#[rmc::proof]
+fn use_same_name_contracts() {
+    let mut counter = Counter(u32::MAX);
+    counter.increment();
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --panic-unwinding
+
+// Check that with `--panic-unwinding`, a panic caught by `catch_unwind` is not a
+// failure, while a panic that is not caught is.
+use std::panic;
+
+pub fn main() {
+    let x: u8 = rmc::nondet();
+    let result = panic::catch_unwind(|| {
+        assert!(x != 1, "caught");
+    });
+    assert!(result.is_err() == (x == 1));
+    assert!(x != 2, "not caught");
+}
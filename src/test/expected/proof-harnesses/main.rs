@@ -0,0 +1,20 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// When no `--function` is given, RMC verifies every function annotated with
+// `#[rmc::proof]` and reports a result for each of them.
+
+#[rmc::proof]
+fn check_increment() {
+    let x: u8 = rmc::nondet();
+    rmc::assume(x < 10);
+    assert!(x + 1 > x);
+}
+
+#[rmc::proof]
+fn check_nondet_is_not_five() {
+    let x: u8 = rmc::nondet();
+    assert!(x != 5); // Expected failure
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that the properties RMC generates are tagged with their class, which
+// CBMC reports as part of the property id.
+
+pub fn main() {
+    let a: u8 = rmc::nondet();
+    let b = a + 1;
+    let arr = [1, 2, 3];
+    let i: usize = rmc::nondet();
+    assert!(arr[i] > 0);
+    rmc::expect_fail(b > a, "b wraps around");
+}
@@ -0,0 +1,52 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that `#[rmc::stub]` replaces the calls to a function, including a generic function and a
+// function of the standard library, only when verifying the harness it annotates.
+
+fn checksum(data: &[u8]) -> u32 {
+    data.iter().fold(0, |sum: u32, byte| sum.wrapping_mul(31).wrapping_add(*byte as u32))
+}
+
+fn is_valid(data: &[u8]) -> bool {
+    checksum(data) == 42
+}
+
+fn mock_checksum(_data: &[u8]) -> u32 {
+    42
+}
+
+fn parse<T: std::str::FromStr>(input: &str) -> Option<T> {
+    input.parse().ok()
+}
+
+fn mock_parse(_input: &str) -> Option<u8> {
+    Some(7)
+}
+
+fn mock_id() -> u32 {
+    1
+}
+
+#[rmc::proof]
+#[rmc::stub(checksum, mock_checksum)]
+fn check_stubbed_checksum() {
+    assert!(is_valid(&[1, 2, 3]));
+}
+
+#[rmc::proof]
+fn check_checksum() {
+    assert!(is_valid(&[1, 2, 3]));
+}
+
+#[rmc::proof]
+#[rmc::stub(parse, mock_parse)]
+fn check_parse() {
+    assert!(parse::<u8>("12") == Some(7));
+}
+
+#[rmc::proof]
+#[rmc::stub(std::process::id, mock_id)]
+fn check_process_id() {
+    assert!(std::process::id() == 1);
+}
@@ -0,0 +1,17 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that RMC checks the preconditions of `offset` and
+// `copy_nonoverlapping`, and reports violations as undefined behavior.
+#![feature(core_intrinsics)]
+
+pub fn main() {
+    let mut a = [1, 2, 3, 4];
+    let ptr: *mut i32 = &mut a[0];
+    unsafe {
+        let end = std::intrinsics::offset(ptr, 4);
+        let _ = std::intrinsics::offset(end, 1);
+        std::ptr::copy_nonoverlapping(ptr, ptr.add(2), 2);
+        std::ptr::copy_nonoverlapping(ptr, ptr.add(1), 2);
+    }
+}
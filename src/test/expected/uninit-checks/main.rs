@@ -0,0 +1,14 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --uninit-checks
+
+// Check that `--uninit-checks` reports reads of uninitialized heap memory.
+use std::alloc::{alloc, Layout};
+
+pub fn main() {
+    unsafe {
+        let p = alloc(Layout::new::<u32>()) as *mut u32;
+        let _x = *p;
+    }
+}
@@ -0,0 +1,55 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// cbmc-flags: --unwind 1
+
+// Check that `#[rmc::unwind(N)]` sets the unwinding bound of each proof harness,
+// overriding the global bound given through `--cbmc-args`, and that on other functions it sets
+// the bound of their loops, overriding the bound of the harness.
+
+fn halve_until_zero(mut a: u32) -> u32 {
+    loop {
+        a = a / 2;
+        if a == 0 {
+            break;
+        }
+    }
+    a
+}
+
+#[rmc::unwind(11)]
+fn halve_until_zero_bounded(mut a: u32) -> u32 {
+    loop {
+        a = a / 2;
+        if a == 0 {
+            break;
+        }
+    }
+    a
+}
+
+#[rmc::proof]
+#[rmc::unwind(11)]
+fn check_enough_unwinding() {
+    let a: u32 = rmc::nondet();
+    rmc::assume(a < 1024);
+    assert!(halve_until_zero(a) == 0);
+}
+
+#[rmc::proof]
+#[rmc::unwind(9)]
+fn check_insufficient_unwinding() {
+    let a: u32 = rmc::nondet();
+    rmc::assume(a < 1024);
+    assert!(halve_until_zero(a) == 0);
+}
+
+#[rmc::proof]
+#[rmc::unwind(9)]
+fn check_function_unwinding() {
+    let a: u32 = rmc::nondet();
+    rmc::assume(a < 1024);
+    assert!(halve_until_zero_bounded(a) == 0);
+}
+
+fn main() {}
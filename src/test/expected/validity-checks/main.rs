@@ -0,0 +1,13 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --validity-checks
+
+// Check that `--validity-checks` checks the values produced by `transmute`
+// and `ptr::read`.
+pub fn main() {
+    let c: u32 = rmc::nondet();
+    let _ = unsafe { std::mem::transmute::<u32, char>(c) };
+    let b: u8 = rmc::nondet();
+    let _ = unsafe { std::ptr::read(&b as *const u8 as *const bool) };
+}
@@ -0,0 +1,19 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-verify-fail
+
+// Dereferencing a misaligned raw pointer is undefined behavior, even to create a reference.
+#[repr(C)]
+struct Header {
+    kind: u16,
+    len: u16,
+}
+
+pub fn main() {
+    let buf: [u16; 4] = [0; 4];
+    let offset: usize = rmc::nondet();
+    rmc::assume(offset < 4);
+    let ptr = unsafe { (buf.as_ptr() as *const u8).add(offset) } as *const Header;
+    let header = unsafe { &*ptr };
+    assert!(header.kind == 0);
+}
@@ -0,0 +1,10 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-verify-fail
+
+// `ptr::read` requires the pointer to be aligned.
+pub fn main() {
+    let buf: [u32; 2] = [0; 2];
+    let ptr = unsafe { (buf.as_ptr() as *const u8).add(1) } as *const u32;
+    let _ = unsafe { std::ptr::read(ptr) };
+}
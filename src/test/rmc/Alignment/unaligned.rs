@@ -0,0 +1,25 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// The `_unaligned` functions and `addr_of!` accept misaligned pointers.
+use std::ptr;
+
+#[repr(C)]
+struct Header {
+    kind: u16,
+    len: u16,
+}
+
+pub fn main() {
+    let buf: [u8; 5] = [0xFF, 1, 0, 4, 0];
+    let header = buf[1..].as_ptr() as *const Header;
+    let copy = unsafe { ptr::read_unaligned(header) };
+    assert!(copy.kind == u16::from_le_bytes([1, 0]));
+    let len = unsafe { ptr::addr_of!((*header).len) };
+    assert!(unsafe { ptr::read_unaligned(len) } == 4);
+
+    let mut out = [0u8; 5];
+    let dst = out[1..].as_mut_ptr() as *mut Header;
+    unsafe { ptr::write_unaligned(dst, copy) };
+    assert!(out[1] == 1);
+}
@@ -0,0 +1,12 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --no-overflow-checks
+
+// Without overflow checks, arithmetic wraps around like in a release build.
+pub fn main() {
+    let x: u8 = rmc::nondet();
+    let y = x + 1;
+    assert!(x == u8::MAX || y > x);
+    assert!(x != u8::MAX || y == 0);
+}
@@ -0,0 +1,11 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-verify-fail
+
+// Check that an overflow in `unchecked_add` is reported as undefined behavior.
+#![feature(core_intrinsics)]
+
+pub fn main() {
+    let a: u8 = rmc::nondet();
+    let _ = unsafe { std::intrinsics::unchecked_add(a, 1) };
+}
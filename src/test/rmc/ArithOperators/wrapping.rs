@@ -0,0 +1,29 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that wrapping arithmetic, which is common in hashing and checksum
+// code, is not reported as an overflow.
+
+fn checksum(data: &[u8]) -> u32 {
+    let mut hash: u32 = 0x811c9dc5;
+    for byte in data {
+        hash ^= *byte as u32;
+        hash = hash.wrapping_mul(0x01000193);
+    }
+    hash
+}
+
+pub fn main() {
+    let a: u32 = rmc::nondet();
+    let b: u32 = rmc::nondet();
+    assert!(a.wrapping_add(b).wrapping_sub(b) == a);
+    assert!(a.wrapping_mul(1) == a);
+
+    let c: i8 = rmc::nondet();
+    assert!(c.wrapping_neg().wrapping_neg() == c);
+    let d: u32 = rmc::nondet();
+    assert!(1u8.wrapping_shl(d) == 1u8 << (d % 8));
+
+    let data: [u8; 2] = rmc::nondet();
+    assert!(checksum(&data) == checksum(&data));
+}
@@ -0,0 +1,15 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --asm-overapproximation
+// rmc-verify-fail
+
+// Inline assembly without `nomem` or `readonly` may write to the memory behind its pointer inputs.
+#![feature(asm)]
+
+pub fn main() {
+    let mut value: u32 = 7;
+    let ptr = &mut value as *mut u32;
+    unsafe { asm!("mov dword ptr [{0}], 1", in(reg) ptr, options(nostack)) };
+    assert!(value == 7);
+}
@@ -0,0 +1,15 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --asm-overapproximation
+
+// Execution does not continue after `noreturn` inline assembly.
+#![feature(asm)]
+
+pub fn main() {
+    let x: u8 = rmc::nondet();
+    if x == 0 {
+        unsafe { asm!("ud2", options(noreturn)) };
+    }
+    assert!(x != 0);
+}
@@ -0,0 +1,14 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --asm-overapproximation
+// rmc-verify-fail
+
+// The outputs of inline assembly are nondeterministic with `--asm-overapproximation`.
+#![feature(asm)]
+
+pub fn main() {
+    let x: u32;
+    unsafe { asm!("mov {0:e}, 5", out(reg) x, options(nomem, nostack)) };
+    assert!(x == 5);
+}
@@ -0,0 +1,19 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --asm-overapproximation
+
+// With `--asm-overapproximation`, inline assembly does not prevent verifying the rest of the
+// function, and `nomem` assembly does not change the memory behind its pointer inputs.
+#![feature(asm)]
+
+pub fn main() {
+    let mut value: u32 = 7;
+    let ptr = &mut value as *mut u32;
+    let mut x: u64 = 3;
+    unsafe {
+        asm!("nop");
+        asm!("add {0}, 1", inout(reg) x, in(reg) ptr, options(nomem, nostack));
+    }
+    assert!(value == 7);
+}
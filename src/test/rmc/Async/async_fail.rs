@@ -0,0 +1,17 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-verify-fail
+
+// Assertions in async code are checked.
+
+async fn double(x: u32) -> u32 {
+    x * 2
+}
+
+pub fn main() {
+    let x: u32 = rmc::nondet();
+    rmc::assume(x < 100);
+    let y = rmc::block_on(async { double(x).await + 1 });
+    assert!(y % 2 == 0);
+}
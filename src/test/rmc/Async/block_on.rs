@@ -0,0 +1,19 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Async functions can be run from a harness with `rmc::block_on`.
+
+async fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+async fn add_three(a: u32, b: u32, c: u32) -> u32 {
+    let ab = add(a, b).await;
+    add(ab, c).await
+}
+
+pub fn main() {
+    let x: u8 = rmc::nondet();
+    let sum = rmc::block_on(add_three(x.into(), 2, 3));
+    assert!(sum == x as u32 + 5);
+}
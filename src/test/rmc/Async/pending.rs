@@ -0,0 +1,36 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// `rmc::block_on` polls a future again after it returns `Poll::Pending`.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+struct YieldOnce {
+    yielded: bool,
+}
+
+impl Future for YieldOnce {
+    type Output = u32;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u32> {
+        if self.yielded {
+            Poll::Ready(7)
+        } else {
+            self.yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+async fn wait() -> u32 {
+    let a = YieldOnce { yielded: false }.await;
+    let b = YieldOnce { yielded: false }.await;
+    a + b
+}
+
+pub fn main() {
+    assert!(rmc::block_on(wait()) == 14);
+}
@@ -0,0 +1,13 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// compare_exchange fails, and does not store, when the value is not the expected one.
+use std::sync::atomic::{AtomicU32, Ordering};
+
+pub fn main() {
+    let a = AtomicU32::new(1);
+    assert!(a.compare_exchange(0, 2, Ordering::SeqCst, Ordering::SeqCst) == Err(1));
+    assert!(a.load(Ordering::SeqCst) == 1);
+    assert!(a.compare_exchange(1, 2, Ordering::SeqCst, Ordering::SeqCst) == Ok(1));
+    assert!(a.load(Ordering::SeqCst) == 2);
+}
@@ -0,0 +1,29 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that float to integer casts saturate, and cast NaN to 0.
+
+pub fn main() {
+    assert!(f32::NAN as u8 == 0);
+    assert!(f64::NAN as i32 == 0);
+    assert!(f32::INFINITY as u128 == u128::MAX);
+    assert!(f64::NEG_INFINITY as i64 == i64::MIN);
+    assert!(300.5f32 as u8 == 255);
+    assert!(-1.5f64 as u8 == 0);
+    assert!(-0.9f64 as u8 == 0);
+    assert!(-129.0f32 as i8 == -128);
+    assert!(-128.9f32 as i8 == -128);
+    assert!(127.9f64 as i8 == 127);
+
+    let x: f32 = rmc::nondet();
+    let y = x as u8;
+    if x.is_nan() {
+        assert!(y == 0);
+    } else if x >= 255.0 {
+        assert!(y == 255);
+    } else if x <= 0.0 {
+        assert!(y == 0);
+    } else {
+        assert!(y as f32 <= x && x < y as f32 + 1.0);
+    }
+}
@@ -0,0 +1,12 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that `to_int_unchecked` of a value in the range of the target type
+// truncates it.
+
+pub fn main() {
+    let x: f64 = rmc::nondet();
+    rmc::assume(x > -129.0 && x < 128.0);
+    let y: i8 = unsafe { x.to_int_unchecked() };
+    assert!(y == x as i8);
+}
@@ -0,0 +1,12 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-verify-fail
+
+// Check that `to_int_unchecked` of a value that does not fit in the target
+// type is reported as undefined behavior.
+
+pub fn main() {
+    let x: f32 = rmc::nondet();
+    rmc::assume(x > 100.0);
+    let _: i8 = unsafe { x.to_int_unchecked() };
+}
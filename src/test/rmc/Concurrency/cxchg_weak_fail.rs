@@ -0,0 +1,15 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --concurrency
+// rmc-verify-fail
+
+// compare_exchange_weak may fail even when the value is the expected one.
+use std::sync::atomic::{AtomicU32, Ordering};
+
+pub fn main() {
+    let a = AtomicU32::new(0);
+    let result = a.compare_exchange_weak(0, 1, Ordering::SeqCst, Ordering::SeqCst);
+    assert!(result.is_err() || a.load(Ordering::SeqCst) == 1);
+    assert!(result.is_ok());
+}
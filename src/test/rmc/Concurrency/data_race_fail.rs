@@ -0,0 +1,16 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --concurrency
+// rmc-verify-fail
+
+// Both threads write to a static without synchronization, which is a data race.
+use std::thread;
+
+static mut VALUE: u32 = 0;
+
+pub fn main() {
+    let handle = thread::spawn(|| unsafe { VALUE = 1 });
+    unsafe { VALUE = 2 };
+    handle.join().unwrap();
+}
@@ -0,0 +1,21 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --concurrency
+
+// Atomic increments of two threads are never lost, and `join` returns the result of the thread.
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+pub fn main() {
+    let counter = Arc::new(AtomicUsize::new(0));
+    let other = counter.clone();
+    let handle = thread::spawn(move || {
+        other.fetch_add(1, Ordering::SeqCst);
+        7
+    });
+    counter.fetch_add(1, Ordering::SeqCst);
+    assert!(handle.join().unwrap() == 7);
+    assert!(counter.load(Ordering::SeqCst) == 2);
+}
@@ -0,0 +1,19 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --concurrency
+
+// Each thread has its own copy of a thread local, which starts with its initial value.
+use std::cell::Cell;
+use std::thread;
+
+thread_local! {
+    static COUNT: Cell<u32> = Cell::new(1);
+}
+
+pub fn main() {
+    COUNT.with(|count| count.set(5));
+    let handle = thread::spawn(|| COUNT.with(|count| count.get()));
+    assert!(handle.join().unwrap() == 1);
+    assert!(COUNT.with(|count| count.get()) == 5);
+}
@@ -0,0 +1,16 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-verify-fail
+
+// `copy_nonoverlapping` is undefined behavior if the source and destination
+// regions overlap.
+use std::ptr;
+
+pub fn main() {
+    let mut a = [1, 2, 3, 4];
+    let src: *const i32 = &a[0];
+    let dst: *mut i32 = &mut a[1];
+    unsafe {
+        ptr::copy_nonoverlapping(src, dst, 2);
+    }
+}
@@ -0,0 +1,35 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Closures that capture nothing can be coerced to function pointers, e.g. to fill
+// a table of callbacks.
+struct Handler {
+    id: u8,
+    callback: fn(u32, u32) -> u32,
+}
+
+fn apply(f: fn(u32) -> bool, x: u32) -> bool {
+    f(x)
+}
+
+pub fn main() {
+    let handlers = [
+        Handler { id: 0, callback: |a, b| a.wrapping_add(b) },
+        Handler { id: 1, callback: |a, _| a },
+        Handler { id: 2, callback: |_, _| 7 },
+    ];
+    let a: u32 = rmc::nondet();
+    let b: u32 = rmc::nondet();
+    for handler in handlers.iter() {
+        let result = (handler.callback)(a, b);
+        match handler.id {
+            0 => assert!(result == a.wrapping_add(b)),
+            1 => assert!(result == a),
+            _ => assert!(result == 7),
+        }
+    }
+
+    assert!(apply(|x| x % 2 == 0, 4));
+    let unit: fn() = || {};
+    unit();
+}
@@ -0,0 +1,17 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that a call to a function with a contract and a `&mut [u8]` argument is replaced by the
+// contract, which gives the slice an arbitrary value that satisfies the postconditions.
+#[rmc::requires(!buf.is_empty())]
+#[rmc::ensures(buf[0] == 0)]
+fn clear_first(buf: &mut [u8]) {
+    buf[0] = 0;
+}
+
+pub fn main() {
+    let mut buf = [1u8, 2, 3];
+    clear_first(&mut buf);
+    assert!(buf[0] == 0);
+    assert!(buf.len() == 3);
+}
@@ -0,0 +1,30 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Dropping a suspended generator drops the locals it keeps.
+
+#![feature(generators, generator_trait)]
+
+use std::ops::Generator;
+use std::pin::Pin;
+
+static mut DROPS: u32 = 0;
+
+struct Guard;
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        unsafe { DROPS += 1 };
+    }
+}
+
+pub fn main() {
+    let mut generator = || {
+        let _guard = Guard;
+        yield;
+    };
+    Pin::new(&mut generator).resume(());
+    assert!(unsafe { DROPS } == 0);
+    drop(generator);
+    assert!(unsafe { DROPS } == 1);
+}
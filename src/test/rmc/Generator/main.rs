@@ -2,8 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 // Check that we can codegen code that has a Generator type present,
-// as long as the path is not dynamically used. Full Generator support
-// tracked in: https://github.com/model-checking/rmc/issues/416
+// even if the path is not dynamically used.
 
 #![feature(generators, generator_trait)]
 
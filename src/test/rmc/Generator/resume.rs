@@ -0,0 +1,24 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// A generator yields its values in order, and keeps its locals across yields.
+
+#![feature(generators, generator_trait)]
+
+use std::ops::{Generator, GeneratorState};
+use std::pin::Pin;
+
+pub fn main() {
+    let start: u8 = rmc::nondet();
+    let mut generator = move || {
+        let mut x = start as u32;
+        yield x;
+        x += 1;
+        yield x;
+        x * 2
+    };
+    let mut generator = Pin::new(&mut generator);
+    assert!(generator.as_mut().resume(()) == GeneratorState::Yielded(start as u32));
+    assert!(generator.as_mut().resume(()) == GeneratorState::Yielded(start as u32 + 1));
+    assert!(generator.as_mut().resume(()) == GeneratorState::Complete(2 * (start as u32 + 1)));
+}
@@ -0,0 +1,21 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// The argument of `resume` is the value of the `yield` expression it resumes.
+
+#![feature(generators, generator_trait)]
+
+use std::ops::{Generator, GeneratorState};
+use std::pin::Pin;
+
+pub fn main() {
+    let mut generator = |a: u32| {
+        let b = yield a;
+        let c = yield a + b;
+        a + b + c
+    };
+    let mut generator = Pin::new(&mut generator);
+    assert!(generator.as_mut().resume(1) == GeneratorState::Yielded(1));
+    assert!(generator.as_mut().resume(2) == GeneratorState::Yielded(3));
+    assert!(generator.as_mut().resume(3) == GeneratorState::Complete(6));
+}
@@ -0,0 +1,22 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-verify-fail
+
+// Resuming a generator after it completed panics.
+
+#![feature(generators, generator_trait)]
+
+use std::ops::Generator;
+use std::pin::Pin;
+
+pub fn main() {
+    let mut generator = || {
+        yield 1;
+        2
+    };
+    let mut generator = Pin::new(&mut generator);
+    generator.as_mut().resume(());
+    generator.as_mut().resume(());
+    generator.as_mut().resume(());
+}
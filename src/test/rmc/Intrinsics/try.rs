@@ -0,0 +1,27 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --panic-unwinding
+
+// See discussion on https://github.com/model-checking/rmc/issues/267
+#![feature(core_intrinsics)]
+use std::intrinsics::r#try;
+
+static mut CAUGHT: bool = false;
+
+fn try_fn(_data: *mut u8) {
+    panic!("foo");
+}
+
+fn catch_fn(_data: *mut u8, _payload: *mut u8) {
+    unsafe { CAUGHT = true };
+}
+
+pub fn main() {
+    unsafe {
+        // The panic unwinds out of `try_fn`, so `try` calls `catch_fn` and returns 1.
+        let result = r#try(try_fn, std::ptr::null_mut(), catch_fn);
+        assert!(result == 1);
+        assert!(CAUGHT);
+    }
+}
@@ -0,0 +1,15 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// The loop bound is unconstrained, so this can only be verified with the loop invariant
+// (unwinding the loop would never terminate).
+
+pub fn main() {
+    let n: u32 = rmc::nondet();
+    let mut i: u32 = 0;
+    rmc::loop_invariant!(i <= n, decreases: n - i);
+    while i < n {
+        i += 1;
+    }
+    assert!(i == n);
+}
@@ -0,0 +1,14 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// The invariant is too weak to prove the assertion after the loop.
+
+pub fn main() {
+    let n: u32 = rmc::nondet();
+    let mut i: u32 = 0;
+    rmc::loop_invariant!(true);
+    while i < n {
+        i += 1;
+    }
+    rmc::expect_fail(i == n, "The invariant does not imply i == n");
+}
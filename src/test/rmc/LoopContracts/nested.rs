@@ -0,0 +1,22 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Each invariant belongs to the loop right after it: the first one to the outer loop, and the
+// second one, written inside the outer loop, to the inner loop.
+
+pub fn main() {
+    let n: u32 = rmc::nondet();
+    let mut i: u32 = 0;
+    let mut total: u64 = 0;
+    rmc::loop_invariant!(i <= n && total <= i as u64 * 4, decreases: n - i);
+    while i < n {
+        let mut j: u32 = 0;
+        rmc::loop_invariant!(j <= 4 && total <= i as u64 * 4 + j as u64, decreases: 4 - j);
+        while j < 4 {
+            total += 1;
+            j += 1;
+        }
+        i += 1;
+    }
+    assert!(total <= n as u64 * 4);
+}
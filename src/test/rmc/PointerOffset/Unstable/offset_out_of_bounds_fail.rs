@@ -0,0 +1,17 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-verify-fail
+
+#![feature(core_intrinsics)]
+use std::intrinsics::offset;
+
+pub fn main() {
+    let a = [0; 5];
+    let ptr: *const i32 = &a[1];
+    unsafe {
+        // The result may point one element past the end of `a`, but no
+        // further, otherwise the call to `offset` is undefined behavior.
+        let _ = offset(ptr, 4);
+        let _ = offset(ptr, 5);
+    }
+}
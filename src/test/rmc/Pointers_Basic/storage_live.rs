@@ -0,0 +1,14 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// A local declared in a loop body is live again in every iteration, so
+// pointers to it are valid within the iteration that created them.
+pub fn main() {
+    let mut sum = 0;
+    for i in 0..3 {
+        let x = i;
+        let p: *const u32 = &x;
+        sum += unsafe { *p };
+    }
+    assert!(sum == 3);
+}
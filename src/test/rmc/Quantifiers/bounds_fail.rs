@@ -0,0 +1,12 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-verify-fail
+
+// The predicate indexes the array out of bounds for some indices of the range, so it does not
+// hold for every index, although every element of the array is small.
+
+pub fn main() {
+    let a: [u8; 8] = [0; 8];
+    assert!(rmc::forall!(|i in 0..10| a[i] < 100));
+}
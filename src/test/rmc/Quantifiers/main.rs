@@ -0,0 +1,13 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Quantified properties over a whole array, without unwinding a loop over its elements.
+
+pub fn main() {
+    let mut a: [u8; 8] = rmc::nondet();
+    rmc::assume(rmc::forall!(|i in 0..8| a[i] < 100));
+    a[3] = 7;
+    assert!(rmc::forall!(|i in 0..8| a[i] < 100));
+    assert!(rmc::exists!(|i in 0..8| a[i] == 7));
+    assert!(!rmc::exists!(|i in 4..4| a[i] == 7));
+}
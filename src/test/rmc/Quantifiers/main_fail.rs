@@ -0,0 +1,11 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Quantified properties that do not hold for every array.
+
+pub fn main() {
+    let a: [u8; 8] = rmc::nondet();
+    rmc::assume(rmc::forall!(|i in 0..8| a[i] < 100));
+    rmc::expect_fail(rmc::forall!(|i in 0..8| a[i] < 50), "Some element may be at least 50");
+    rmc::expect_fail(rmc::exists!(|i in 0..8| a[i] == 7), "No element may be 7");
+}
@@ -0,0 +1,16 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-verify-fail
+
+// Without another thread, nothing notifies the condition variable.
+use std::sync::{Condvar, Mutex};
+
+pub fn main() {
+    let ready = Mutex::new(false);
+    let cvar = Condvar::new();
+    let mut guard = ready.lock().unwrap();
+    while !*guard {
+        guard = cvar.wait(guard).unwrap();
+    }
+}
@@ -0,0 +1,24 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --concurrency
+// rmc-verify-fail
+
+// Two threads that lock two mutexes in opposite orders may deadlock.
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+pub fn main() {
+    let first = Arc::new(Mutex::new(0));
+    let second = Arc::new(Mutex::new(0));
+    let (other_first, other_second) = (first.clone(), second.clone());
+    let handle = thread::spawn(move || {
+        let _second = other_second.lock().unwrap();
+        *other_first.lock().unwrap() += 1;
+    });
+    {
+        let _first = first.lock().unwrap();
+        *second.lock().unwrap() += 1;
+    }
+    handle.join().unwrap();
+}
@@ -0,0 +1,22 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// A single-threaded harness can lock and unlock a shared mutex.
+use std::sync::{Arc, Mutex};
+
+fn add(state: &Arc<Mutex<u32>>, x: u32) {
+    *state.lock().unwrap() += x;
+}
+
+pub fn main() {
+    let state = Arc::new(Mutex::new(1));
+    add(&state, 2);
+    add(&state.clone(), 3);
+    {
+        let guard = state.lock().unwrap();
+        assert!(*guard == 6);
+        assert!(state.try_lock().is_err());
+    }
+    assert!(state.try_lock().is_ok());
+    assert!(!state.is_poisoned());
+}
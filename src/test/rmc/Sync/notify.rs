@@ -0,0 +1,25 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --concurrency
+
+// A thread waiting on a condition variable wakes up when another thread notifies it.
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+pub fn main() {
+    let pair = Arc::new((Mutex::new(false), Condvar::new()));
+    let other = pair.clone();
+    let handle = thread::spawn(move || {
+        let (ready, cvar) = &*other;
+        *ready.lock().unwrap() = true;
+        cvar.notify_one();
+    });
+    let (ready, cvar) = &*pair;
+    let mut guard = ready.lock().unwrap();
+    while !*guard {
+        guard = cvar.wait(guard).unwrap();
+    }
+    drop(guard);
+    handle.join().unwrap();
+}
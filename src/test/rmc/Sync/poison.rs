@@ -0,0 +1,24 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --panic-unwinding
+
+// A mutex whose guard is dropped by a panic is poisoned.
+use std::panic;
+use std::sync::Mutex;
+
+pub fn main() {
+    let state = Mutex::new(0);
+    let result = panic::catch_unwind(|| {
+        let mut guard = state.lock().unwrap();
+        *guard += 1;
+        panic!("panic while holding the lock");
+    });
+    assert!(result.is_err());
+    assert!(state.is_poisoned());
+    let value = match state.lock() {
+        Ok(_) => 0,
+        Err(poisoned) => *poisoned.into_inner(),
+    };
+    assert!(value == 1);
+}
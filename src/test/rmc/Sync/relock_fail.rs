@@ -0,0 +1,14 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-verify-fail
+
+// Locking a mutex that the current thread holds never returns.
+use std::sync::Mutex;
+
+pub fn main() {
+    let state = Mutex::new(0);
+    let guard = state.lock().unwrap();
+    let value = *state.lock().unwrap();
+    assert!(value == *guard);
+}
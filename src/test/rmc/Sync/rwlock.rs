@@ -0,0 +1,21 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Read locks are shared, and exclude the write lock.
+use std::sync::RwLock;
+
+pub fn main() {
+    let lock = RwLock::new(5);
+    {
+        let r1 = lock.read().unwrap();
+        let r2 = lock.read().unwrap();
+        assert!(*r1 + *r2 == 10);
+        assert!(lock.try_write().is_err());
+    }
+    {
+        let mut w = lock.write().unwrap();
+        *w += 1;
+        assert!(lock.try_read().is_err());
+    }
+    assert!(*lock.read().unwrap() == 6);
+}
@@ -0,0 +1,17 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --concurrency
+
+// Increments under a mutex are not lost.
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+pub fn main() {
+    let counter = Arc::new(Mutex::new(0));
+    let other = counter.clone();
+    let handle = thread::spawn(move || *other.lock().unwrap() += 1);
+    *counter.lock().unwrap() += 1;
+    handle.join().unwrap();
+    assert!(*counter.lock().unwrap() == 2);
+}
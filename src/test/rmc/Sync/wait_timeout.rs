@@ -0,0 +1,15 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Without another thread, waiting with a timeout times out.
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+
+pub fn main() {
+    let ready = Mutex::new(false);
+    let cvar = Condvar::new();
+    let guard = ready.lock().unwrap();
+    let (guard, result) = cvar.wait_timeout(guard, Duration::from_millis(10)).unwrap();
+    assert!(result.timed_out());
+    assert!(!*guard);
+}
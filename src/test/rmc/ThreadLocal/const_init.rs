@@ -0,0 +1,15 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Thread locals can be accessed through the `#[thread_local]` attribute directly.
+#![feature(thread_local)]
+
+#[thread_local]
+static mut DEPTH: u8 = 3;
+
+pub fn main() {
+    unsafe {
+        DEPTH += 1;
+        assert!(DEPTH == 4);
+    }
+}
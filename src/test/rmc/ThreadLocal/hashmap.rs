@@ -0,0 +1,13 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// `HashMap::new` reads its random keys from a thread local.
+use std::collections::HashMap;
+
+pub fn main() {
+    let mut map = HashMap::new();
+    let key: u8 = rmc::nondet();
+    map.insert(key, 1);
+    assert!(map.get(&key) == Some(&1));
+    assert!(map.len() == 1);
+}
@@ -0,0 +1,27 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// A thread local is initialized on its first access, and keeps its value afterwards.
+use std::cell::{Cell, RefCell};
+
+static mut INITS: u32 = 0;
+
+fn init() -> u32 {
+    unsafe { INITS += 1 };
+    10
+}
+
+thread_local! {
+    static COUNTER: Cell<u32> = Cell::new(init());
+    static LOG: RefCell<Vec<u32>> = RefCell::new(Vec::new());
+}
+
+pub fn main() {
+    assert!(unsafe { INITS } == 0);
+    COUNTER.with(|c| c.set(c.get() + 1));
+    COUNTER.with(|c| assert!(c.get() == 11));
+    assert!(unsafe { INITS } == 1);
+
+    LOG.with(|log| log.borrow_mut().push(1));
+    LOG.with(|log| assert!(log.borrow().len() == 1));
+}
@@ -0,0 +1,17 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --uninit-checks
+// rmc-verify-fail
+
+// Reading memory from `alloc` before writing it is undefined behavior.
+use std::alloc::{alloc, dealloc, Layout};
+
+pub fn main() {
+    let layout = Layout::new::<u32>();
+    unsafe {
+        let p = alloc(layout) as *mut u32;
+        let _x = *p;
+        dealloc(p as *mut u8, layout);
+    }
+}
@@ -0,0 +1,22 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --uninit-checks
+
+// `alloc_zeroed` returns initialized memory, and `alloc` memory is initialized
+// once it is written.
+use std::alloc::{alloc, alloc_zeroed, dealloc, Layout};
+
+pub fn main() {
+    let layout = Layout::new::<u64>();
+    unsafe {
+        let zeroed = alloc_zeroed(layout) as *mut u64;
+        assert!(*zeroed == 0);
+        dealloc(zeroed as *mut u8, layout);
+
+        let written = alloc(layout) as *mut u64;
+        *written = 5;
+        assert!(*written == 5);
+        dealloc(written as *mut u8, layout);
+    }
+}
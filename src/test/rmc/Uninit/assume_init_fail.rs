@@ -0,0 +1,14 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --uninit-checks
+// rmc-verify-fail
+
+// Only the first element is written, so `assume_init` reads uninitialized memory.
+use std::mem::MaybeUninit;
+
+pub fn main() {
+    let mut pair = MaybeUninit::<[u8; 2]>::uninit();
+    unsafe { (pair.as_mut_ptr() as *mut u8).write(1) };
+    let _pair = unsafe { pair.assume_init() };
+}
@@ -0,0 +1,27 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --uninit-checks
+
+// Memory that is written before it is read is initialized, whether it is written
+// directly, through a pointer or by a copy.
+use std::mem::MaybeUninit;
+
+pub fn main() {
+    let mut a = MaybeUninit::<u32>::uninit();
+    a.write(7);
+    assert!(unsafe { a.assume_init() } == 7);
+
+    let mut b = MaybeUninit::<[u16; 2]>::uninit();
+    unsafe {
+        let p = b.as_mut_ptr() as *mut u16;
+        p.write(1);
+        p.add(1).write(2);
+    }
+    let b = unsafe { b.assume_init() };
+    assert!(b[0] + b[1] == 3);
+
+    let mut c = MaybeUninit::<[u16; 2]>::uninit();
+    unsafe { std::ptr::copy_nonoverlapping(&b, c.as_mut_ptr(), 1) };
+    assert!(unsafe { c.assume_init() } == b);
+}
@@ -0,0 +1,19 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --panic-unwinding
+
+// `catch_unwind` returns `Ok` when its closure returns, and `Err` when it panics.
+use std::panic;
+
+pub fn main() {
+    let result = panic::catch_unwind(|| {
+        let _hello = "hello!";
+    });
+    assert!(result.is_ok());
+
+    let result = panic::catch_unwind(|| {
+        panic!("oh no!");
+    });
+    assert!(result.is_err());
+}
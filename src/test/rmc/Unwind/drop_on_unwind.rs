@@ -0,0 +1,35 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --panic-unwinding
+
+// The locals of the frames that a panic unwinds are dropped.
+use std::panic;
+
+static mut DROPS: u32 = 0;
+
+struct Guard;
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        unsafe { DROPS += 1 };
+    }
+}
+
+fn inner(fail: bool) -> u32 {
+    let _guard = Guard;
+    if fail {
+        panic!("inner failed");
+    }
+    1
+}
+
+pub fn main() {
+    let fail: bool = rmc::nondet();
+    let result = panic::catch_unwind(|| {
+        let _outer = Guard;
+        inner(fail)
+    });
+    assert!(result.is_err() == fail);
+    assert!(unsafe { DROPS } == 2);
+}
@@ -0,0 +1,18 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --panic-unwinding
+
+// A failed bounds or overflow check panics, so `catch_unwind` catches it like a call to `panic!`.
+use std::panic;
+
+pub fn main() {
+    let a = [1, 2, 3];
+    let index: usize = rmc::nondet();
+    let result = panic::catch_unwind(|| a[index]);
+    assert!(result.is_ok() == (index < 3));
+
+    let x: u8 = rmc::nondet();
+    let result = panic::catch_unwind(|| x + 1);
+    assert!(result.is_err() == (x == u8::MAX));
+}
@@ -0,0 +1,24 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --panic-unwinding
+
+// A panic is caught by the innermost `catch_unwind`, and the outer one returns normally.
+use std::panic;
+
+fn checked_div(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        panic!("division by zero");
+    }
+    a / b
+}
+
+pub fn main() {
+    let b: u32 = rmc::nondet();
+    let outer = panic::catch_unwind(|| {
+        let inner = panic::catch_unwind(|| checked_div(10, b));
+        inner.unwrap_or(0)
+    });
+    let value = outer.unwrap();
+    assert!(b != 0 || value == 0);
+}
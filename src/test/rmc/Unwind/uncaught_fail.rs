@@ -0,0 +1,22 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --panic-unwinding
+// rmc-verify-fail
+
+// A panic that no `catch_unwind` catches is still a failure.
+struct Guard;
+
+impl Drop for Guard {
+    fn drop(&mut self) {}
+}
+
+fn fail() {
+    let _guard = Guard;
+    panic!("not caught");
+}
+
+pub fn main() {
+    let _guard = Guard;
+    fail();
+}
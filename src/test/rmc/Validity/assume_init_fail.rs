@@ -0,0 +1,14 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --validity-checks
+// rmc-verify-fail
+
+// `assume_init` on bytes that are not a valid `bool` is undefined behavior.
+use std::mem::MaybeUninit;
+
+pub fn main() {
+    let mut slot = MaybeUninit::<bool>::uninit();
+    unsafe { slot.as_mut_ptr().cast::<u8>().write(2) };
+    let _flag = unsafe { slot.assume_init() };
+}
@@ -0,0 +1,20 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --validity-checks
+
+// Raw pointers do not need to point to valid values, so dangling pointers pass the validity checks.
+struct Node {
+    next: *const Node,
+    flag: bool,
+}
+
+pub fn main() {
+    let addr: usize = 8;
+    let ptr: *const bool = unsafe { std::mem::transmute(addr) };
+    assert!(ptr as usize == 8);
+
+    let node = Node { next: addr as *const Node, flag: true };
+    let copy = unsafe { std::ptr::read(&node as *const Node) };
+    assert!(copy.flag);
+}
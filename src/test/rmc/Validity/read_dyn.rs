@@ -0,0 +1,38 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --validity-checks
+
+// Reading references to trait objects, alone or inside other values, passes the validity checks.
+use std::any::Any;
+
+trait Shape {
+    fn area(&self) -> u32;
+}
+
+struct Square(u32);
+
+impl Shape for Square {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+}
+
+struct Holder<'a> {
+    shape: &'a dyn Shape,
+}
+
+pub fn main() {
+    let square = Square(3);
+    let shape: &dyn Shape = &square;
+    let copy: &dyn Shape = unsafe { std::ptr::read(&shape) };
+    assert!(copy.area() == 9);
+
+    let holder = Holder { shape };
+    let holder_copy = unsafe { std::ptr::read(&holder) };
+    assert!(holder_copy.shape.area() == 9);
+
+    let any: Option<&dyn Any> = Some(&5u8);
+    let any_copy = unsafe { std::ptr::read(&any) };
+    assert!(any_copy.is_some());
+}
@@ -0,0 +1,17 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --validity-checks
+// rmc-verify-fail
+
+// Transmuting a byte that is not a discriminant of the enum is undefined behavior.
+#[repr(u8)]
+enum Kind {
+    Data = 1,
+    Ack = 2,
+}
+
+pub fn main() {
+    let byte: u8 = rmc::nondet();
+    let _kind: Kind = unsafe { std::mem::transmute(byte) };
+}
@@ -0,0 +1,28 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --validity-checks
+
+// Values built from valid bytes pass the validity checks.
+use std::mem::MaybeUninit;
+
+#[derive(Clone, Copy, PartialEq)]
+#[repr(u8)]
+enum Kind {
+    Data = 1,
+    Ack = 2,
+}
+
+pub fn main() {
+    let byte: u8 = rmc::nondet();
+    rmc::assume(byte == 1 || byte == 2);
+    let kind: Kind = unsafe { std::mem::transmute(byte) };
+    assert!(kind == Kind::Data || kind == Kind::Ack);
+
+    let flag = unsafe { std::ptr::read(&1u8 as *const u8 as *const bool) };
+    assert!(flag);
+
+    let mut slot = MaybeUninit::<char>::uninit();
+    slot.write('a');
+    assert!(unsafe { slot.assume_init() } == 'a');
+}
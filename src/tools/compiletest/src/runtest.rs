@@ -2356,7 +2356,7 @@ fn run_rmc_test(&self) {
     /// If the test file contains expected failures in some locations, ensure
     /// that verification does not succeed in those locations.
     fn verify_expect_fail(str: &str) -> Vec<usize> {
-        let re = Regex::new(r"line [0-9]+ EXPECTED FAIL: SUCCESS").unwrap();
+        let re = Regex::new(r"(?m)line [0-9]+ EXPECTED FAIL(: .*)?: SUCCESS$").unwrap();
         let mut lines = vec![];
         for m in re.find_iter(str) {
             let splits = m.as_str().split_ascii_whitespace();